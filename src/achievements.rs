@@ -0,0 +1,135 @@
+use crate::game::{EnemyType, GameState};
+use crate::quests::GameEvent;
+
+/// Long-term goals that carry over from run to run.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Achievement {
+    FirstBlood,
+    Escapee,
+    NothingToLose,
+    FastFood,
+    PunkBuster,
+    ByAThread,
+    JunkArtist,
+    Locksmith,
+    DumpsterDiver,
+}
+
+impl Achievement {
+    pub const ALL: [Achievement; 9] = [
+        Achievement::FirstBlood,
+        Achievement::Escapee,
+        Achievement::NothingToLose,
+        Achievement::FastFood,
+        Achievement::PunkBuster,
+        Achievement::ByAThread,
+        Achievement::JunkArtist,
+        Achievement::Locksmith,
+        Achievement::DumpsterDiver,
+    ];
+
+    /// Stable name for the profile file.
+    pub fn id(&self) -> &'static str {
+        match self {
+            Achievement::FirstBlood => "first_blood",
+            Achievement::Escapee => "escapee",
+            Achievement::NothingToLose => "nothing_to_lose",
+            Achievement::FastFood => "fast_food",
+            Achievement::PunkBuster => "punk_buster",
+            Achievement::ByAThread => "by_a_thread",
+            Achievement::JunkArtist => "junk_artist",
+            Achievement::Locksmith => "locksmith",
+            Achievement::DumpsterDiver => "dumpster_diver",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Achievement::ALL.into_iter().find(|a| a.id() == id)
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Achievement::FirstBlood => "First Blood",
+            Achievement::Escapee => "Over the Fence",
+            Achievement::NothingToLose => "Nothing to Lose",
+            Achievement::FastFood => "Fast Food",
+            Achievement::PunkBuster => "Punk Buster",
+            Achievement::ByAThread => "By a Thread",
+            Achievement::JunkArtist => "Junk Artist",
+            Achievement::Locksmith => "Locksmith",
+            Achievement::DumpsterDiver => "Dumpster Diver",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Achievement::FirstBlood => "Defeat your first enemy",
+            Achievement::Escapee => "Escape the alley",
+            Achievement::NothingToLose => "Escape without any armor on",
+            Achievement::FastFood => "Escape in under 100 turns",
+            Achievement::PunkBuster => "Defeat every Rival Punk in the alley",
+            Achievement::ByAThread => "Take a hit and live with 1 HP left",
+            Achievement::JunkArtist => "Craft something out of junk",
+            Achievement::Locksmith => "Open every lock in the alley",
+            Achievement::DumpsterDiver => "Scavenge every dumpster in one run",
+        }
+    }
+
+    /// Whether `event`, having just happened in `game`, earns this.
+    pub fn earned_by(&self, event: &GameEvent, game: &GameState) -> bool {
+        match (self, event) {
+            (Achievement::FirstBlood, GameEvent::EnemyDefeated(_)) => true,
+            (Achievement::Escapee, GameEvent::Escaped) => true,
+            (Achievement::NothingToLose, GameEvent::Escaped) => {
+                game.player.inventory.armor.is_none()
+            }
+            (Achievement::FastFood, GameEvent::Escaped) => game.turn_count < 100,
+            (Achievement::PunkBuster, GameEvent::EnemyDefeated(EnemyType::RivalPunk)) => !game
+                .enemies
+                .iter()
+                .any(|e| e.is_alive && e.enemy_type == EnemyType::RivalPunk),
+            (Achievement::ByAThread, GameEvent::Hurt) => game.player.health == 1,
+            (Achievement::JunkArtist, GameEvent::Crafted(_)) => true,
+            (Achievement::Locksmith, GameEvent::LockOpened(_)) => {
+                game.locks.iter().all(|lock| lock.open)
+            }
+            (Achievement::DumpsterDiver, GameEvent::DumpsterScavenged) => {
+                game.dumpsters.iter().all(|dumpster| dumpster.searched)
+            }
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ids_round_trip() {
+        for achievement in Achievement::ALL {
+            assert_eq!(Achievement::from_id(achievement.id()), Some(achievement));
+        }
+        assert_eq!(Achievement::from_id("nope"), None);
+    }
+
+    #[test]
+    fn test_achievements_check_the_game() {
+        let mut game = GameState::new(60, 20);
+        game.turn_count = 150;
+        game.player.inventory.armor = None;
+
+        assert!(Achievement::Escapee.earned_by(&GameEvent::Escaped, &game));
+        assert!(Achievement::NothingToLose.earned_by(&GameEvent::Escaped, &game));
+        assert!(!Achievement::FastFood.earned_by(&GameEvent::Escaped, &game));
+        assert!(!Achievement::Escapee.earned_by(&GameEvent::Hurt, &game));
+
+        // One punk down isn't every punk while another is still around
+        let punk_down = GameEvent::EnemyDefeated(EnemyType::RivalPunk);
+        assert!(!Achievement::PunkBuster.earned_by(&punk_down, &game));
+        for enemy in &mut game.enemies {
+            enemy.is_alive = false;
+        }
+        assert!(Achievement::PunkBuster.earned_by(&punk_down, &game));
+    }
+}
@@ -0,0 +1,176 @@
+use crate::items::{Armor, Consumable, Item, Tool, Weapon};
+use crate::meta::Unlock;
+use std::fmt;
+
+/// Who you were before you ended up behind the burger place. Sets your
+/// stats, what's in your pockets and a passive knack.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Background {
+    GutterBrawler,
+    DumpsterDiver,
+    SquatterMedic,
+    SkatePunk,
+}
+
+impl Background {
+    pub const ALL: [Background; 4] = [
+        Background::GutterBrawler,
+        Background::DumpsterDiver,
+        Background::SquatterMedic,
+        Background::SkatePunk,
+    ];
+
+    /// Stable name for save files.
+    pub fn id(&self) -> &'static str {
+        match self {
+            Background::GutterBrawler => "gutter_brawler",
+            Background::DumpsterDiver => "dumpster_diver",
+            Background::SquatterMedic => "squatter_medic",
+            Background::SkatePunk => "skate_punk",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Background::ALL.into_iter().find(|b| b.id() == id)
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Background::GutterBrawler => "Grew up scrapping behind bars. Hits hard, takes a hit.",
+            Background::DumpsterDiver => "Knows every bin in town and what's safe to reach into.",
+            Background::SquatterMedic => "Patched up half the squat with a sewing kit and vodka.",
+            Background::SkatePunk => "Fast, slippery and gone before anyone lands a punch.",
+        }
+    }
+
+    pub fn max_health(&self) -> i32 {
+        match self {
+            Background::GutterBrawler => 120,
+            Background::DumpsterDiver => 90,
+            Background::SquatterMedic => 100,
+            Background::SkatePunk => 80,
+        }
+    }
+
+    pub fn attack(&self) -> i32 {
+        match self {
+            Background::GutterBrawler => 12,
+            Background::DumpsterDiver => 9,
+            Background::SquatterMedic => 8,
+            Background::SkatePunk => 10,
+        }
+    }
+
+    pub fn starting_item(&self) -> Item {
+        match self {
+            Background::GutterBrawler => Item::Weapon(Weapon::named("Brass Knuckles").unwrap()),
+            Background::DumpsterDiver => Item::Tool(Tool::LockPicks),
+            Background::SquatterMedic => Item::Consumable(Consumable::named("Bandages").unwrap()),
+            Background::SkatePunk => Item::Armor(Armor::named("Padded Hoodie").unwrap()),
+        }
+    }
+
+    pub fn passive_name(&self) -> &'static str {
+        match self {
+            Background::GutterBrawler => "Haymaker",
+            Background::DumpsterDiver => "Bin Sense",
+            Background::SquatterMedic => "Field Medic",
+            Background::SkatePunk => "Kickflip",
+        }
+    }
+
+    pub fn passive(&self) -> &'static str {
+        match self {
+            Background::GutterBrawler => "+10% critical hit chance",
+            Background::DumpsterDiver => "Half the dumpster hazards, +1 junk per bin",
+            Background::SquatterMedic => "Healing items and scavenged food heal 50% more",
+            Background::SkatePunk => "+15 evasion, and retreating costs no stamina",
+        }
+    }
+
+    /// The profile unlock needed to pick this one, if any.
+    pub fn required_unlock(&self) -> Option<Unlock> {
+        match self {
+            Background::SkatePunk => Some(Unlock::SkatePunk),
+            _ => None,
+        }
+    }
+
+    pub fn crit_bonus(&self) -> i32 {
+        if *self == Background::GutterBrawler {
+            10
+        } else {
+            0
+        }
+    }
+
+    pub fn evasion_bonus(&self) -> i32 {
+        if *self == Background::SkatePunk {
+            15
+        } else {
+            0
+        }
+    }
+
+    pub fn free_retreat(&self) -> bool {
+        *self == Background::SkatePunk
+    }
+
+    /// Chance of a dumpster hazard biting, given the bin's usual chance.
+    pub fn hazard_chance(&self, chance: f64) -> f64 {
+        if *self == Background::DumpsterDiver {
+            chance / 2.0
+        } else {
+            chance
+        }
+    }
+
+    pub fn bonus_junk(&self) -> i32 {
+        if *self == Background::DumpsterDiver {
+            1
+        } else {
+            0
+        }
+    }
+
+    /// Scales healing from items and scavenged food.
+    pub fn healing(&self, amount: i32) -> i32 {
+        if *self == Background::SquatterMedic {
+            amount * 3 / 2
+        } else {
+            amount
+        }
+    }
+}
+
+impl fmt::Display for Background {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Background::GutterBrawler => write!(f, "Gutter Brawler"),
+            Background::DumpsterDiver => write!(f, "Dumpster Diver"),
+            Background::SquatterMedic => write!(f, "Squatter Medic"),
+            Background::SkatePunk => write!(f, "Skate Punk"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backgrounds_trade_off() {
+        for background in Background::ALL {
+            // Every starting item resolves
+            let _ = background.starting_item();
+        }
+        let brawler = Background::GutterBrawler;
+        let skater = Background::SkatePunk;
+        assert!(brawler.max_health() > skater.max_health());
+        assert!(skater.evasion_bonus() > brawler.evasion_bonus());
+        assert_eq!(Background::SquatterMedic.healing(20), 30);
+        assert_eq!(Background::DumpsterDiver.hazard_chance(0.5), 0.25);
+        assert_eq!(skater.required_unlock(), Some(Unlock::SkatePunk));
+        assert_eq!(Background::from_id(skater.id()), Some(skater));
+    }
+}
@@ -0,0 +1,175 @@
+use crate::game::EnemyType;
+use std::fmt;
+
+/// Every turn is six minutes, so an hour passes every ten turns.
+pub const MINUTES_PER_TURN: u32 = 6;
+/// Runs start as the sun goes down.
+pub const START_HOUR: u32 = 19;
+/// When the burger place hauls fresh trash out the back door.
+pub const TRASH_HOURS: [u32; 3] = [6, 14, 22];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Phase {
+    Dawn,
+    Day,
+    Dusk,
+    Night,
+}
+
+impl Phase {
+    /// How far you can see, or None when it's light out.
+    pub fn sight_radius(&self) -> Option<f32> {
+        match self {
+            Phase::Day => None,
+            Phase::Dawn | Phase::Dusk => Some(12.0),
+            Phase::Night => Some(6.0),
+        }
+    }
+
+    /// How close you have to be before enemies come after you.
+    pub fn aggro_range(&self) -> f32 {
+        match self {
+            Phase::Day => 8.0,
+            Phase::Dawn | Phase::Dusk => 10.0,
+            Phase::Night => 14.0,
+        }
+    }
+
+    /// Chance an enemy closes in on you each turn.
+    pub fn move_chance(&self) -> f64 {
+        match self {
+            Phase::Day => 0.4,
+            Phase::Dawn | Phase::Dusk => 0.5,
+            Phase::Night => 0.7,
+        }
+    }
+
+    /// Chance something new wanders into the alley each hour.
+    pub fn spawn_chance(&self) -> f64 {
+        match self {
+            Phase::Day => 0.2,
+            Phase::Dawn => 0.25,
+            Phase::Dusk => 0.35,
+            Phase::Night => 0.5,
+        }
+    }
+
+    /// What wanders in at this time of day. Cats come out at night,
+    /// punks hang around while the burger place is open.
+    pub fn spawn_table(&self) -> &'static [(u32, EnemyType)] {
+        match self {
+            Phase::Day => &[(75, EnemyType::Rat), (25, EnemyType::RivalPunk)],
+            Phase::Dawn => &[(70, EnemyType::Rat), (30, EnemyType::FerralCat)],
+            Phase::Dusk => &[
+                (60, EnemyType::Rat),
+                (20, EnemyType::FerralCat),
+                (20, EnemyType::RivalPunk),
+            ],
+            Phase::Night => &[(50, EnemyType::Rat), (50, EnemyType::FerralCat)],
+        }
+    }
+
+    /// What the alley looks like when the phase begins.
+    pub fn announcement(&self) -> &'static str {
+        match self {
+            Phase::Dawn => "The sky goes grey. Dawn's coming.",
+            Phase::Day => "The sun's up. The alley's quieter in daylight.",
+            Phase::Dusk => "The sun's going down. Shadows stretch across the alley.",
+            Phase::Night => "Night falls. Things are moving out there.",
+        }
+    }
+}
+
+impl fmt::Display for Phase {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Phase::Dawn => write!(f, "Dawn"),
+            Phase::Day => write!(f, "Day"),
+            Phase::Dusk => write!(f, "Dusk"),
+            Phase::Night => write!(f, "Night"),
+        }
+    }
+}
+
+/// Time of day, worked out from the turn count.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Clock {
+    pub day: u32,
+    pub hour: u32,
+    pub minute: u32,
+}
+
+impl Clock {
+    pub fn at_turn(turn: u32) -> Self {
+        let minutes = START_HOUR * 60 + turn * MINUTES_PER_TURN;
+        Clock {
+            day: minutes / (24 * 60) + 1,
+            hour: minutes / 60 % 24,
+            minute: minutes % 60,
+        }
+    }
+
+    pub fn phase(&self) -> Phase {
+        match self.hour {
+            5..=6 => Phase::Dawn,
+            7..=17 => Phase::Day,
+            18..=19 => Phase::Dusk,
+            _ => Phase::Night,
+        }
+    }
+
+    /// True on the first turn of a new hour.
+    pub fn is_on_the_hour(&self) -> bool {
+        self.minute < MINUTES_PER_TURN
+    }
+
+    pub fn is_trash_time(&self) -> bool {
+        self.is_on_the_hour() && TRASH_HOURS.contains(&self.hour)
+    }
+}
+
+impl fmt::Display for Clock {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:02}:{:02}", self.hour, self.minute)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clock_from_turns() {
+        let start = Clock::at_turn(0);
+        assert_eq!(start.to_string(), "19:00");
+        assert_eq!(start.phase(), Phase::Dusk);
+
+        // Ten turns to the hour
+        let clock = Clock::at_turn(15);
+        assert_eq!(clock.to_string(), "20:30");
+        assert_eq!(clock.phase(), Phase::Night);
+
+        // Wraps past midnight into the next day
+        let clock = Clock::at_turn(60);
+        assert_eq!((clock.day, clock.hour), (2, 1));
+    }
+
+    #[test]
+    fn test_trash_time_is_on_the_hour() {
+        // 22:00 is turn 30
+        assert!(Clock::at_turn(30).is_trash_time());
+        assert!(!Clock::at_turn(31).is_trash_time());
+        assert!(!Clock::at_turn(20).is_trash_time());
+    }
+
+    #[test]
+    fn test_night_is_darker_and_meaner() {
+        assert!(Phase::Night.sight_radius() < Phase::Dusk.sight_radius());
+        assert!(Phase::Day.sight_radius().is_none());
+        assert!(Phase::Night.aggro_range() > Phase::Day.aggro_range());
+        for phase in [Phase::Dawn, Phase::Day, Phase::Dusk, Phase::Night] {
+            let total: u32 = phase.spawn_table().iter().map(|(w, _)| w).sum();
+            assert_eq!(total, 100);
+        }
+    }
+}
@@ -0,0 +1,216 @@
+use rand::Rng;
+
+/// Rolls at or above `to_hit - GLANCE_BAND` only graze the target.
+const GLANCE_BAND: i32 = 15;
+
+/// Everything about the attacker that matters for a single swing.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AttackProfile {
+    pub accuracy: i32,
+    pub min_damage: i32,
+    pub max_damage: i32,
+    pub crit_chance: i32,
+}
+
+impl AttackProfile {
+    /// Builds a profile whose damage varies by about a quarter around `base`.
+    pub fn new(accuracy: i32, base: i32, crit_chance: i32) -> Self {
+        let spread = (base / 4).max(1);
+        AttackProfile {
+            accuracy,
+            min_damage: (base - spread).max(1),
+            max_damage: base + spread,
+            crit_chance,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DefenseProfile {
+    pub evasion: i32,
+    pub defense: i32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HitKind {
+    Miss,
+    Glancing,
+    Hit,
+    Critical,
+}
+
+/// The outcome of one attack, with enough detail to explain the roll.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AttackResult {
+    pub kind: HitKind,
+    /// d100 roll; lower is better for the attacker.
+    pub roll: i32,
+    /// The roll needed to land the hit at all.
+    pub to_hit: i32,
+    /// Raw damage before the hit kind and defense were applied.
+    pub damage_roll: i32,
+    /// How much defense soaked up.
+    pub blocked: i32,
+    /// Final damage dealt.
+    pub damage: i32,
+}
+
+impl AttackResult {
+    pub fn is_hit(&self) -> bool {
+        self.kind != HitKind::Miss
+    }
+
+    /// Roll breakdown for the combat log, e.g. "roll 34/75, 11 dmg -2 def".
+    pub fn breakdown(&self) -> String {
+        if !self.is_hit() {
+            return format!("roll {}/{}", self.roll, self.to_hit);
+        }
+
+        let mut text = format!(
+            "roll {}/{}, {} dmg",
+            self.roll, self.to_hit, self.damage_roll
+        );
+        match self.kind {
+            HitKind::Glancing => text.push_str(" x0.5"),
+            HitKind::Critical => text.push_str(" x2"),
+            _ => {}
+        }
+        if self.blocked > 0 {
+            text.push_str(&format!(" -{} def", self.blocked));
+        }
+        text
+    }
+}
+
+/// Chance to hit out of 100, never fully certain either way.
+pub fn hit_chance(attack: &AttackProfile, defense: &DefenseProfile) -> i32 {
+    (attack.accuracy - defense.evasion).clamp(5, 95)
+}
+
+pub fn resolve_attack(
+    rng: &mut impl Rng,
+    attack: &AttackProfile,
+    defense: &DefenseProfile,
+) -> AttackResult {
+    let to_hit = hit_chance(attack, defense);
+    let roll = rng.gen_range(1..=100);
+    let damage_roll = rng.gen_range(attack.min_damage..=attack.max_damage);
+
+    let kind = if roll > to_hit {
+        HitKind::Miss
+    } else if roll <= attack.crit_chance {
+        HitKind::Critical
+    } else if roll > to_hit - GLANCE_BAND {
+        HitKind::Glancing
+    } else {
+        HitKind::Hit
+    };
+
+    let scaled = match kind {
+        HitKind::Miss => 0,
+        HitKind::Glancing => (damage_roll / 2).max(1),
+        HitKind::Hit => damage_roll,
+        HitKind::Critical => damage_roll * 2,
+    };
+
+    // Anything that connects does at least 1 damage
+    let damage = if kind == HitKind::Miss {
+        0
+    } else {
+        (scaled - defense.defense).max(1)
+    };
+
+    AttackResult {
+        kind,
+        roll,
+        to_hit,
+        damage_roll,
+        blocked: scaled - damage.min(scaled),
+        damage,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    #[test]
+    fn test_damage_range() {
+        let profile = AttackProfile::new(80, 10, 5);
+        assert_eq!(profile.min_damage, 8);
+        assert_eq!(profile.max_damage, 12);
+
+        // Weak attackers still vary a little and never drop to zero
+        let profile = AttackProfile::new(80, 1, 5);
+        assert_eq!(profile.min_damage, 1);
+        assert_eq!(profile.max_damage, 2);
+    }
+
+    #[test]
+    fn test_hit_chance_is_clamped() {
+        let attack = AttackProfile::new(200, 10, 0);
+        let defense = DefenseProfile {
+            evasion: 0,
+            defense: 0,
+        };
+        assert_eq!(hit_chance(&attack, &defense), 95);
+
+        let attack = AttackProfile::new(0, 10, 0);
+        assert_eq!(hit_chance(&attack, &defense), 5);
+    }
+
+    #[test]
+    fn test_attack_outcomes_follow_the_rules() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let attack = AttackProfile::new(80, 10, 10);
+        let defense = DefenseProfile {
+            evasion: 10,
+            defense: 3,
+        };
+
+        let mut seen = Vec::new();
+        for _ in 0..500 {
+            let result = resolve_attack(&mut rng, &attack, &defense);
+            assert_eq!(result.to_hit, 70);
+            assert!((8..=12).contains(&result.damage_roll));
+            match result.kind {
+                HitKind::Miss => {
+                    assert!(result.roll > 70);
+                    assert_eq!(result.damage, 0);
+                }
+                HitKind::Critical => {
+                    assert!(result.roll <= 10);
+                    assert_eq!(result.damage, result.damage_roll * 2 - 3);
+                }
+                HitKind::Glancing => {
+                    assert!(result.roll > 55);
+                    assert_eq!(result.damage, (result.damage_roll / 2 - 3).max(1));
+                }
+                HitKind::Hit => assert_eq!(result.damage, result.damage_roll - 3),
+            }
+            if !seen.contains(&result.kind) {
+                seen.push(result.kind);
+            }
+        }
+        assert_eq!(seen.len(), 4);
+    }
+
+    #[test]
+    fn test_same_seed_same_fight() {
+        let attack = AttackProfile::new(75, 7, 10);
+        let defense = DefenseProfile {
+            evasion: 10,
+            defense: 0,
+        };
+        let mut a = StdRng::seed_from_u64(7);
+        let mut b = StdRng::seed_from_u64(7);
+        for _ in 0..20 {
+            assert_eq!(
+                resolve_attack(&mut a, &attack, &defense),
+                resolve_attack(&mut b, &attack, &defense)
+            );
+        }
+    }
+}
@@ -0,0 +1,380 @@
+use crate::items::{
+    Armor, Consumable, ConsumableEffect, Inventory, ItemRarity, MaterialKind, Tool, Weapon,
+};
+use std::fmt;
+
+/// Something a recipe eats.
+#[derive(Debug, Clone, Copy)]
+pub enum Ingredient {
+    Material(MaterialKind, u32),
+    /// The equipped weapon, by name.
+    Weapon(&'static str),
+    /// The equipped armor, by name.
+    Armor(&'static str),
+}
+
+/// What a recipe makes. Crafted gear is always Uncommon.
+#[derive(Debug, Clone)]
+pub enum Product {
+    Weapon {
+        name: &'static str,
+        damage_bonus: i32,
+        description: &'static str,
+    },
+    Armor {
+        name: &'static str,
+        defense_bonus: i32,
+        description: &'static str,
+    },
+    Consumable {
+        name: &'static str,
+        effect: ConsumableEffect,
+        description: &'static str,
+    },
+    Tool(Tool),
+}
+
+impl Product {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Product::Weapon { name, .. }
+            | Product::Armor { name, .. }
+            | Product::Consumable { name, .. } => name,
+            Product::Tool(tool) => tool.name(),
+        }
+    }
+
+    pub fn summary(&self) -> String {
+        match self {
+            Product::Weapon { damage_bonus, .. } => format!("Weapon, +{} damage", damage_bonus),
+            Product::Armor { defense_bonus, .. } => format!("Armor, +{} defense", defense_bonus),
+            Product::Consumable { effect, .. } => match effect {
+                ConsumableEffect::Heal(amount) => format!("Consumable, heals {}", amount),
+                ConsumableEffect::DamageBoost(amount, turns) => {
+                    format!("Consumable, +{} damage for {} turns", amount, turns)
+                }
+                ConsumableEffect::DefenseBoost(amount, turns) => {
+                    format!("Consumable, +{} defense for {} turns", amount, turns)
+                }
+            },
+            Product::Tool(_) => "Tool, for getting through locks".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Recipe {
+    pub ingredients: &'static [Ingredient],
+    pub product: Product,
+}
+
+/// The recipe book. Add new combinations here.
+pub const RECIPES: &[Recipe] = &[
+    Recipe {
+        ingredients: &[
+            Ingredient::Weapon("Baseball Bat"),
+            Ingredient::Material(MaterialKind::Nails, 2),
+        ],
+        product: Product::Weapon {
+            name: "Spiked Bat",
+            damage_bonus: 6,
+            description: "With nails driven through it",
+        },
+    },
+    Recipe {
+        ingredients: &[
+            Ingredient::Weapon("Broken Bottle"),
+            Ingredient::Material(MaterialKind::DuctTape, 1),
+        ],
+        product: Product::Weapon {
+            name: "Taped Shiv",
+            damage_bonus: 4,
+            description: "A handle makes all the difference",
+        },
+    },
+    Recipe {
+        ingredients: &[
+            Ingredient::Weapon("Brass Knuckles"),
+            Ingredient::Material(MaterialKind::Nails, 1),
+            Ingredient::Material(MaterialKind::DuctTape, 1),
+        ],
+        product: Product::Weapon {
+            name: "Nail Knuckles",
+            damage_bonus: 5,
+            description: "Points facing outward, hopefully",
+        },
+    },
+    Recipe {
+        ingredients: &[
+            Ingredient::Weapon("Metal Pipe"),
+            Ingredient::Material(MaterialKind::ScrapMetal, 2),
+            Ingredient::Material(MaterialKind::DuctTape, 1),
+        ],
+        product: Product::Weapon {
+            name: "Pipe Mace",
+            damage_bonus: 8,
+            description: "Scrap metal taped to the business end",
+        },
+    },
+    Recipe {
+        ingredients: &[
+            Ingredient::Material(MaterialKind::Bottle, 1),
+            Ingredient::Material(MaterialKind::Cloth, 1),
+        ],
+        product: Product::Weapon {
+            name: "Glass Shank",
+            damage_bonus: 3,
+            description: "Wrapped so you don't cut yourself",
+        },
+    },
+    Recipe {
+        ingredients: &[
+            Ingredient::Armor("Leather Jacket"),
+            Ingredient::Material(MaterialKind::Nails, 3),
+        ],
+        product: Product::Armor {
+            name: "Studded Jacket",
+            defense_bonus: 4,
+            description: "Punk as it gets",
+        },
+    },
+    Recipe {
+        ingredients: &[
+            Ingredient::Armor("Padded Hoodie"),
+            Ingredient::Material(MaterialKind::ScrapMetal, 2),
+            Ingredient::Material(MaterialKind::Wire, 1),
+        ],
+        product: Product::Armor {
+            name: "Plated Hoodie",
+            defense_bonus: 5,
+            description: "Sheet metal wired into the lining",
+        },
+    },
+    Recipe {
+        ingredients: &[
+            Ingredient::Material(MaterialKind::ScrapMetal, 3),
+            Ingredient::Material(MaterialKind::DuctTape, 1),
+        ],
+        product: Product::Armor {
+            name: "Trash Lid Shield",
+            defense_bonus: 3,
+            description: "Strapped to your forearm",
+        },
+    },
+    Recipe {
+        ingredients: &[
+            Ingredient::Material(MaterialKind::Cloth, 2),
+            Ingredient::Material(MaterialKind::DuctTape, 1),
+        ],
+        product: Product::Consumable {
+            name: "Improvised Bandages",
+            effect: ConsumableEffect::Heal(25),
+            description: "Torn rags and tape",
+        },
+    },
+    Recipe {
+        ingredients: &[
+            Ingredient::Material(MaterialKind::Wire, 2),
+            Ingredient::Material(MaterialKind::Nails, 1),
+        ],
+        product: Product::Tool(Tool::LockPicks),
+    },
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CraftError {
+    MissingMaterial(MaterialKind, u32),
+    MissingItem(&'static str),
+    SlotFull(&'static str),
+    AlreadyHave(&'static str),
+}
+
+impl fmt::Display for CraftError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CraftError::MissingMaterial(kind, needed) => {
+                write!(f, "Need {} more {}", needed, kind)
+            }
+            CraftError::MissingItem(name) => write!(f, "Need a {} equipped", name),
+            CraftError::SlotFull(slot) => write!(f, "Your {} slot is full", slot),
+            CraftError::AlreadyHave(name) => write!(f, "You already have {}", name),
+        }
+    }
+}
+
+impl Recipe {
+    /// Checks the recipe against your inventory without touching it.
+    pub fn check(&self, inventory: &Inventory) -> Result<(), CraftError> {
+        for ingredient in self.ingredients {
+            match *ingredient {
+                Ingredient::Material(kind, count) => {
+                    let have = inventory.material_count(kind);
+                    if have < count {
+                        return Err(CraftError::MissingMaterial(kind, count - have));
+                    }
+                }
+                Ingredient::Weapon(name) => {
+                    if inventory.weapon.as_ref().is_none_or(|w| w.name != name) {
+                        return Err(CraftError::MissingItem(name));
+                    }
+                }
+                Ingredient::Armor(name) => {
+                    if inventory.armor.as_ref().is_none_or(|a| a.name != name) {
+                        return Err(CraftError::MissingItem(name));
+                    }
+                }
+            }
+        }
+
+        // The product needs somewhere to go, unless it replaces an ingredient
+        let consumes = |matches: fn(&Ingredient) -> bool| self.ingredients.iter().any(matches);
+        match self.product {
+            Product::Weapon { .. }
+                if inventory.weapon.is_some()
+                    && !consumes(|i| matches!(i, Ingredient::Weapon(_))) =>
+            {
+                Err(CraftError::SlotFull("weapon"))
+            }
+            Product::Armor { .. }
+                if inventory.armor.is_some()
+                    && !consumes(|i| matches!(i, Ingredient::Armor(_))) =>
+            {
+                Err(CraftError::SlotFull("armor"))
+            }
+            Product::Consumable { .. } if inventory.consumable.is_some() => {
+                Err(CraftError::SlotFull("consumable"))
+            }
+            Product::Tool(tool) if inventory.has_tool(tool) => {
+                Err(CraftError::AlreadyHave(tool.name()))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Consumes the ingredients and equips the product.
+    pub fn craft(&self, inventory: &mut Inventory) -> Result<(), CraftError> {
+        self.check(inventory)?;
+
+        for ingredient in self.ingredients {
+            match *ingredient {
+                Ingredient::Material(kind, count) => {
+                    inventory.remove_materials(kind, count);
+                }
+                Ingredient::Weapon(_) => inventory.weapon = None,
+                Ingredient::Armor(_) => inventory.armor = None,
+            }
+        }
+
+        match &self.product {
+            Product::Weapon {
+                name,
+                damage_bonus,
+                description,
+            } => {
+                inventory.weapon = Some(Weapon::new(
+                    name,
+                    *damage_bonus,
+                    ItemRarity::Uncommon,
+                    description,
+                ));
+            }
+            Product::Armor {
+                name,
+                defense_bonus,
+                description,
+            } => {
+                inventory.armor = Some(Armor::new(
+                    name,
+                    *defense_bonus,
+                    ItemRarity::Uncommon,
+                    description,
+                ));
+            }
+            Product::Consumable {
+                name,
+                effect,
+                description,
+            } => {
+                inventory.consumable = Some(Consumable::new(name, effect.clone(), description));
+            }
+            Product::Tool(tool) => {
+                inventory.add_tool(*tool);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for Ingredient {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Ingredient::Material(kind, 1) => write!(f, "{}", kind),
+            Ingredient::Material(kind, count) => write!(f, "{} x{}", kind, count),
+            Ingredient::Weapon(name) | Ingredient::Armor(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn recipe_for(product: &str) -> &'static Recipe {
+        RECIPES
+            .iter()
+            .find(|r| r.product.name() == product)
+            .expect("recipe exists")
+    }
+
+    #[test]
+    fn test_spiked_bat_recipe() {
+        let recipe = recipe_for("Spiked Bat");
+        let mut inventory = Inventory::new();
+        assert_eq!(
+            recipe.check(&inventory),
+            Err(CraftError::MissingItem("Baseball Bat"))
+        );
+
+        inventory.weapon = Some(Weapon::new("Baseball Bat", 3, ItemRarity::Common, "Test"));
+        inventory.add_material(MaterialKind::Nails);
+        assert_eq!(
+            recipe.check(&inventory),
+            Err(CraftError::MissingMaterial(MaterialKind::Nails, 1))
+        );
+
+        inventory.add_material(MaterialKind::Nails);
+        assert!(recipe.craft(&mut inventory).is_ok());
+        assert_eq!(inventory.weapon.as_ref().unwrap().name, "Spiked Bat");
+        assert_eq!(inventory.material_count(MaterialKind::Nails), 0);
+    }
+
+    #[test]
+    fn test_craft_needs_free_slot() {
+        let recipe = recipe_for("Improvised Bandages");
+        let mut inventory = Inventory::new();
+        inventory.add_material(MaterialKind::Cloth);
+        inventory.add_material(MaterialKind::Cloth);
+        inventory.add_material(MaterialKind::DuctTape);
+        inventory.consumable = Some(Consumable::new(
+            "Expired Soda",
+            ConsumableEffect::Heal(10),
+            "Test",
+        ));
+
+        assert_eq!(
+            recipe.craft(&mut inventory),
+            Err(CraftError::SlotFull("consumable"))
+        );
+        // Nothing was used up
+        assert_eq!(inventory.material_count(MaterialKind::Cloth), 2);
+    }
+
+    #[test]
+    fn test_recipes_are_well_formed() {
+        for recipe in RECIPES {
+            assert!(!recipe.ingredients.is_empty());
+            assert!(!recipe.product.name().is_empty());
+        }
+    }
+}
@@ -0,0 +1,209 @@
+use crate::morgue;
+use crate::score::HighScore;
+use std::cmp::Reverse;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Today's date in UTC, like `2024-03-01`. Everyone playing on the same
+/// date gets the same alley.
+pub fn date(now: SystemTime) -> String {
+    morgue::timestamp(now)[..10].to_string()
+}
+
+/// The seed for a date's alley. FNV-1a, so it comes out the same on every
+/// machine and every build.
+pub fn seed(date: &str) -> u64 {
+    date.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// A run on the day's seed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DailyRun {
+    pub date: String,
+    /// Only the first attempt each day counts. Later ones are practice.
+    pub scored: bool,
+}
+
+/// One result on the daily board.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DailyScore {
+    pub date: String,
+    pub entry: HighScore,
+}
+
+impl DailyScore {
+    /// The date, then the same columns as the high score table.
+    fn to_line(&self) -> String {
+        format!("{}\t{}", self.date, self.entry.to_line())
+    }
+
+    fn from_line(line: &str) -> Option<Self> {
+        let (date, rest) = line.split_once('\t')?;
+        Some(DailyScore {
+            date: date.to_string(),
+            entry: HighScore::from_line(rest)?,
+        })
+    }
+}
+
+/// Every daily result played here or imported from someone else, kept
+/// apart from the high score table.
+#[derive(Debug, Clone, Default)]
+pub struct DailyScores {
+    pub entries: Vec<DailyScore>,
+    /// Where the board lives. None keeps it in memory only.
+    path: Option<PathBuf>,
+    /// Why the board couldn't be written, if it couldn't.
+    pub error: Option<String>,
+}
+
+impl DailyScores {
+    /// Reads the board at `path`. A missing or mangled file just means
+    /// fewer results.
+    pub fn open(path: PathBuf) -> Self {
+        let entries = fs::read_to_string(&path)
+            .map(|text| text.lines().filter_map(DailyScore::from_line).collect())
+            .unwrap_or_default();
+        DailyScores {
+            entries,
+            path: Some(path),
+            error: None,
+        }
+    }
+
+    /// The results for `date`, best first.
+    pub fn on(&self, date: &str) -> Vec<&HighScore> {
+        let mut scores: Vec<&HighScore> = self
+            .entries
+            .iter()
+            .filter(|daily| daily.date == date)
+            .map(|daily| &daily.entry)
+            .collect();
+        scores.sort_by_key(|entry| Reverse(entry.score));
+        scores
+    }
+
+    /// Adds a result and writes the board out.
+    pub fn record(&mut self, date: &str, entry: HighScore) {
+        self.entries.push(DailyScore {
+            date: date.to_string(),
+            entry,
+        });
+        self.error = self.save().err().map(|err| err.to_string());
+    }
+
+    /// Writes `date`'s results to a file in `dir` for sharing, named for
+    /// the date and `name`.
+    pub fn export(&self, dir: &Path, date: &str, name: &str) -> io::Result<PathBuf> {
+        fs::create_dir_all(dir)?;
+        let name: String = name
+            .chars()
+            .map(|c| if c.is_alphanumeric() { c } else { '_' })
+            .collect();
+        let path = dir.join(format!("daily_{}_{}.tsv", date, name));
+        let mut text = String::from(HEADER);
+        for daily in self.entries.iter().filter(|daily| daily.date == date) {
+            text.push_str(&daily.to_line());
+            text.push('\n');
+        }
+        fs::write(&path, text)?;
+        Ok(path)
+    }
+
+    /// Merges in the results from every `.tsv` file in `dir`, skipping any
+    /// already on the board. Returns how many were new.
+    pub fn import(&mut self, dir: &Path) -> io::Result<usize> {
+        let mut added = 0;
+        for file in fs::read_dir(dir)? {
+            let path = file?.path();
+            if path.extension().is_none_or(|ext| ext != "tsv") {
+                continue;
+            }
+            let text = fs::read_to_string(&path)?;
+            for daily in text.lines().filter_map(DailyScore::from_line) {
+                if !self.entries.contains(&daily) {
+                    self.entries.push(daily);
+                    added += 1;
+                }
+            }
+        }
+        if added > 0 {
+            self.save()?;
+        }
+        Ok(added)
+    }
+
+    fn save(&self) -> io::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let mut text = String::from(HEADER);
+        for daily in &self.entries {
+            text.push_str(&daily.to_line());
+            text.push('\n');
+        }
+        fs::write(path, text)
+    }
+}
+
+const HEADER: &str = "date\tscore\tname\tresult\tturns\tkills\tdepth\tdifficulty\n";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::difficulty::Difficulty;
+    use crate::score::RunResult;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    fn result(name: &str, kills: u32) -> HighScore {
+        let run = RunResult {
+            won: false,
+            turns: 50,
+            hp_left: 0,
+            enemies_defeated: kills,
+            rarity_points: 0,
+            depth: 1,
+            difficulty: Difficulty::Normal,
+            score_percent: 100,
+        };
+        HighScore::new(name, &run)
+    }
+
+    #[test]
+    fn test_same_day_same_seed() {
+        let morning = UNIX_EPOCH + Duration::from_secs(1_709_251_200);
+        let evening = morning + Duration::from_secs(20 * 3_600);
+        assert_eq!(date(morning), "2024-03-01");
+        assert_eq!(date(evening), "2024-03-01");
+        assert_eq!(seed(&date(morning)), seed(&date(evening)));
+        assert_ne!(seed("2024-03-01"), seed("2024-03-02"));
+    }
+
+    #[test]
+    fn test_results_travel_between_boards() {
+        let dir = std::env::temp_dir().join(format!("rust_punk_daily_{}", std::process::id()));
+        let mut mine = DailyScores::default();
+        mine.record("2024-03-01", result("Ace", 3));
+        mine.record("2024-02-29", result("Ace", 9));
+        let path = mine.export(&dir, "2024-03-01", "Ace").unwrap();
+        assert!(path.ends_with("daily_2024-03-01_Ace.tsv"));
+
+        let mut theirs = DailyScores::default();
+        theirs.record("2024-03-01", result("Slug", 5));
+        assert_eq!(theirs.import(&dir).unwrap(), 1);
+        // Importing twice doesn't double up
+        assert_eq!(theirs.import(&dir).unwrap(), 0);
+
+        let board = theirs.on("2024-03-01");
+        let names: Vec<&str> = board.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["Slug", "Ace"]);
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
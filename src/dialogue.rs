@@ -0,0 +1,320 @@
+//! Branching conversations loaded from the text files in `data/dialogue`.
+//!
+//! A file is a list of nodes. Each node starts with `[id]`, followed by the
+//! lines the NPC says and the player's options:
+//!
+//! ```text
+//! [start]
+//! "Got a light?"
+//! ! reputation +1              <- effect, runs when the node is entered
+//! * "Sure, here." -> thanks    <- option leading to node `thanks`
+//!   ? has material cloth       <- condition, hides the option unless met
+//!   ! take material cloth      <- effect, runs when the option is picked
+//! * Walk away. -> end          <- `end` closes the conversation
+//! ```
+//!
+//! Conditions: `reputation N`, `cash N`, `has weapon|armor|consumable`,
+//! `has material|tool NAME`, `flag NAME`, `!flag NAME`.
+//!
+//! Effects: `give weapon|armor|consumable|material|tool NAME`, `give cash N`,
+//! `take cash N`, `take weapon|armor|consumable`, `take material|tool NAME`,
+//! `reputation +N`, `flag NAME`, `quest ID`, `fight`.
+//!
+//! Lines starting with `#` are comments.
+
+use crate::items::{Armor, Consumable, Item, MaterialKind, Slot, Tool, Weapon};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Condition {
+    Reputation(i32),
+    Cash(u32),
+    Has(Slot),
+    Flag(String),
+    NotFlag(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ItemSpec {
+    Weapon(String),
+    Armor(String),
+    Consumable(String),
+    Material(MaterialKind),
+    Tool(Tool),
+    Cash(u32),
+}
+
+impl ItemSpec {
+    /// Builds the item. Names are checked when the file is parsed, so this
+    /// only fails for hand-built specs.
+    pub fn create(&self) -> Option<Item> {
+        match self {
+            ItemSpec::Weapon(name) => Weapon::named(name).map(Item::Weapon),
+            ItemSpec::Armor(name) => Armor::named(name).map(Item::Armor),
+            ItemSpec::Consumable(name) => Consumable::named(name).map(Item::Consumable),
+            ItemSpec::Material(kind) => Some(Item::Material(*kind)),
+            ItemSpec::Tool(tool) => Some(Item::Tool(*tool)),
+            ItemSpec::Cash(amount) => Some(Item::Cash(*amount)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DialogueEffect {
+    Give(ItemSpec),
+    TakeCash(u32),
+    Take(Slot),
+    Reputation(i32),
+    SetFlag(String),
+    StartQuest(String),
+    Fight,
+}
+
+#[derive(Debug, Clone)]
+pub struct DialogueOption {
+    pub text: String,
+    /// Index of the next node, or None to end the conversation.
+    pub target: Option<usize>,
+    pub conditions: Vec<Condition>,
+    pub effects: Vec<DialogueEffect>,
+}
+
+#[derive(Debug, Clone)]
+pub struct DialogueNode {
+    pub id: String,
+    pub lines: Vec<String>,
+    pub effects: Vec<DialogueEffect>,
+    pub options: Vec<DialogueOption>,
+}
+
+#[derive(Debug, Clone)]
+pub struct DialogueTree {
+    pub nodes: Vec<DialogueNode>,
+}
+
+/// Every conversation shipped with the game, by id.
+const BUNDLED: &[(&str, &str)] = &[
+    ("fry_cook", include_str!("../data/dialogue/fry_cook.txt")),
+    ("veteran", include_str!("../data/dialogue/veteran.txt")),
+];
+
+impl DialogueTree {
+    /// Loads one of the bundled conversations.
+    pub fn bundled(id: &str) -> Self {
+        let (_, source) = BUNDLED
+            .iter()
+            .find(|(name, _)| *name == id)
+            .unwrap_or_else(|| panic!("no dialogue named {}", id));
+        Self::parse(source).unwrap_or_else(|err| panic!("bad dialogue {}: {}", id, err))
+    }
+
+    pub fn parse(source: &str) -> Result<Self, String> {
+        let mut nodes: Vec<DialogueNode> = Vec::new();
+        // Option targets by name, resolved once every node is known
+        let mut targets: Vec<Vec<String>> = Vec::new();
+
+        for (line_no, raw) in source.lines().enumerate() {
+            let line = raw.trim();
+            let err = |msg: &str| format!("line {}: {}", line_no + 1, msg);
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if let Some(id) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                if nodes.iter().any(|n| n.id == id) {
+                    return Err(err(&format!("duplicate node [{}]", id)));
+                }
+                nodes.push(DialogueNode {
+                    id: id.to_string(),
+                    lines: Vec::new(),
+                    effects: Vec::new(),
+                    options: Vec::new(),
+                });
+                targets.push(Vec::new());
+                continue;
+            }
+
+            let node = nodes
+                .last_mut()
+                .ok_or_else(|| err("text before the first [node]"))?;
+
+            if let Some(option) = line.strip_prefix('*') {
+                let (text, target) = option
+                    .rsplit_once("->")
+                    .ok_or_else(|| err("option is missing '-> target'"))?;
+                node.options.push(DialogueOption {
+                    text: text.trim().to_string(),
+                    target: None,
+                    conditions: Vec::new(),
+                    effects: Vec::new(),
+                });
+                targets
+                    .last_mut()
+                    .expect("pushed with node")
+                    .push(target.trim().to_string());
+            } else if let Some(condition) = line.strip_prefix('?') {
+                let condition = parse_condition(condition.trim()).map_err(|e| err(&e))?;
+                node.options
+                    .last_mut()
+                    .ok_or_else(|| err("condition outside of an option"))?
+                    .conditions
+                    .push(condition);
+            } else if let Some(effect) = line.strip_prefix('!') {
+                let effect = parse_effect(effect.trim()).map_err(|e| err(&e))?;
+                // Indented effects belong to the option above them
+                if raw.starts_with(char::is_whitespace) {
+                    node.options
+                        .last_mut()
+                        .ok_or_else(|| err("indented effect outside of an option"))?
+                        .effects
+                        .push(effect);
+                } else {
+                    node.effects.push(effect);
+                }
+            } else {
+                node.lines.push(line.to_string());
+            }
+        }
+
+        if nodes.is_empty() {
+            return Err("no nodes".to_string());
+        }
+
+        let ids: Vec<String> = nodes.iter().map(|n| n.id.clone()).collect();
+        for (node, node_targets) in nodes.iter_mut().zip(targets) {
+            if node.options.is_empty() {
+                return Err(format!("node [{}] has no options", node.id));
+            }
+            for (option, target) in node.options.iter_mut().zip(node_targets) {
+                option.target = if target == "end" {
+                    None
+                } else {
+                    Some(ids.iter().position(|id| *id == target).ok_or_else(|| {
+                        format!("node [{}] points at unknown node [{}]", node.id, target)
+                    })?)
+                };
+            }
+        }
+
+        Ok(DialogueTree { nodes })
+    }
+}
+
+fn parse_slot(words: &[&str]) -> Result<Slot, String> {
+    match words {
+        ["weapon"] => Ok(Slot::Weapon),
+        ["armor"] => Ok(Slot::Armor),
+        ["consumable"] => Ok(Slot::Consumable),
+        ["material", name @ ..] => MaterialKind::from_name(&name.join(" "))
+            .map(Slot::Material)
+            .ok_or_else(|| format!("unknown material '{}'", name.join(" "))),
+        ["tool", name @ ..] => Tool::from_name(&name.join(" "))
+            .map(Slot::Tool)
+            .ok_or_else(|| format!("unknown tool '{}'", name.join(" "))),
+        _ => Err(format!("unknown slot '{}'", words.join(" "))),
+    }
+}
+
+fn parse_number<T: std::str::FromStr>(word: &str) -> Result<T, String> {
+    word.trim_start_matches('+')
+        .parse()
+        .map_err(|_| format!("expected a number, got '{}'", word))
+}
+
+fn parse_condition(text: &str) -> Result<Condition, String> {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    match words.as_slice() {
+        ["reputation", n] => Ok(Condition::Reputation(parse_number(n)?)),
+        ["cash", n] => Ok(Condition::Cash(parse_number(n)?)),
+        ["has", slot @ ..] => Ok(Condition::Has(parse_slot(slot)?)),
+        ["flag", name] => Ok(Condition::Flag(name.to_string())),
+        ["!flag", name] => Ok(Condition::NotFlag(name.to_string())),
+        _ => Err(format!("unknown condition '{}'", text)),
+    }
+}
+
+fn parse_effect(text: &str) -> Result<DialogueEffect, String> {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    match words.as_slice() {
+        ["give", "cash", n] => Ok(DialogueEffect::Give(ItemSpec::Cash(parse_number(n)?))),
+        ["give", kind, name @ ..] => {
+            let name = name.join(" ");
+            let spec = match *kind {
+                "weapon" => ItemSpec::Weapon(name),
+                "armor" => ItemSpec::Armor(name),
+                "consumable" => ItemSpec::Consumable(name),
+                "material" => ItemSpec::Material(
+                    MaterialKind::from_name(&name)
+                        .ok_or_else(|| format!("unknown material '{}'", name))?,
+                ),
+                "tool" => ItemSpec::Tool(
+                    Tool::from_name(&name).ok_or_else(|| format!("unknown tool '{}'", name))?,
+                ),
+                _ => return Err(format!("can't give a '{}'", kind)),
+            };
+            if spec.create().is_none() {
+                return Err(format!("unknown item '{}'", text));
+            }
+            Ok(DialogueEffect::Give(spec))
+        }
+        ["take", "cash", n] => Ok(DialogueEffect::TakeCash(parse_number(n)?)),
+        ["take", slot @ ..] => Ok(DialogueEffect::Take(parse_slot(slot)?)),
+        ["reputation", n] => Ok(DialogueEffect::Reputation(parse_number(n)?)),
+        ["flag", name] => Ok(DialogueEffect::SetFlag(name.to_string())),
+        ["quest", id] => Ok(DialogueEffect::StartQuest(id.to_string())),
+        ["fight"] => Ok(DialogueEffect::Fight),
+        _ => Err(format!("unknown effect '{}'", text)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bundled_dialogue_parses() {
+        for (id, _) in BUNDLED {
+            let tree = DialogueTree::bundled(id);
+            assert_eq!(tree.nodes[0].id, "start");
+        }
+    }
+
+    #[test]
+    fn test_parse_options_conditions_and_effects() {
+        let tree = DialogueTree::parse(
+            "[start]\n\
+             \"Hey.\"\n\
+             ! reputation +1\n\
+             * \"Trade?\" -> trade\n\
+             \x20 ? cash 5\n\
+             \x20 ! take cash 5\n\
+             * Leave. -> end\n\
+             [trade]\n\
+             ! give material duct tape\n\
+             * Done. -> end\n",
+        )
+        .unwrap();
+
+        let start = &tree.nodes[0];
+        assert_eq!(start.lines, vec!["\"Hey.\"".to_string()]);
+        assert_eq!(start.effects, vec![DialogueEffect::Reputation(1)]);
+        assert_eq!(start.options[0].target, Some(1));
+        assert_eq!(start.options[0].conditions, vec![Condition::Cash(5)]);
+        assert_eq!(start.options[0].effects, vec![DialogueEffect::TakeCash(5)]);
+        assert_eq!(start.options[1].target, None);
+        assert_eq!(
+            tree.nodes[1].effects,
+            vec![DialogueEffect::Give(ItemSpec::Material(
+                MaterialKind::DuctTape
+            ))]
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(DialogueTree::parse("").is_err());
+        assert!(DialogueTree::parse("[start]\n* Go -> nowhere\n").is_err());
+        assert!(DialogueTree::parse("[start]\nJust talking.\n").is_err());
+        assert!(DialogueTree::parse("[start]\n! give weapon Lightsaber\n* Ok -> end\n").is_err());
+    }
+}
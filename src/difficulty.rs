@@ -0,0 +1,280 @@
+use std::fmt;
+
+/// Steps the custom settings move in, in percent.
+const CUSTOM_STEP: u32 = 25;
+const CUSTOM_RANGE: (u32, u32) = (25, 300);
+
+/// How hard the whole game pushes back.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+    Nightmare,
+    /// Whatever's in the profile's custom settings.
+    Custom,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 5] = [
+        Difficulty::Easy,
+        Difficulty::Normal,
+        Difficulty::Hard,
+        Difficulty::Nightmare,
+        Difficulty::Custom,
+    ];
+
+    /// Stable name for the profile and score files.
+    pub fn id(&self) -> &'static str {
+        match self {
+            Difficulty::Easy => "easy",
+            Difficulty::Normal => "normal",
+            Difficulty::Hard => "hard",
+            Difficulty::Nightmare => "nightmare",
+            Difficulty::Custom => "custom",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Difficulty::ALL.into_iter().find(|d| d.id() == id)
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Difficulty::Easy => "Softer enemies, more loot and healing. You get back up.",
+            Difficulty::Normal => "The alley as it's meant to be.",
+            Difficulty::Hard => "Tougher, busier alley with less to go around.",
+            Difficulty::Nightmare => "Everything wants you dead and nothing helps.",
+            Difficulty::Custom => "Your own rules. C = Change them",
+        }
+    }
+
+    /// The settings this plays by, with `custom` standing in for Custom.
+    pub fn settings(&self, custom: DifficultySettings) -> DifficultySettings {
+        match self {
+            Difficulty::Easy => DifficultySettings {
+                enemy_health: 75,
+                enemy_attack: 75,
+                enemy_count: 75,
+                loot: 125,
+                healing: 150,
+                permadeath: false,
+            },
+            Difficulty::Normal => DifficultySettings::default(),
+            Difficulty::Hard => DifficultySettings {
+                enemy_health: 125,
+                enemy_attack: 125,
+                enemy_count: 125,
+                loot: 85,
+                healing: 75,
+                permadeath: true,
+            },
+            Difficulty::Nightmare => DifficultySettings {
+                enemy_health: 150,
+                enemy_attack: 150,
+                enemy_count: 150,
+                loot: 70,
+                healing: 50,
+                permadeath: true,
+            },
+            Difficulty::Custom => custom,
+        }
+    }
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad(match self {
+            Difficulty::Easy => "Easy",
+            Difficulty::Normal => "Normal",
+            Difficulty::Hard => "Hard",
+            Difficulty::Nightmare => "Nightmare",
+            Difficulty::Custom => "Custom",
+        })
+    }
+}
+
+/// The knobs a difficulty turns, in percent of Normal.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DifficultySettings {
+    pub enemy_health: u32,
+    pub enemy_attack: u32,
+    /// Enemies in the alley at the start and in each wave.
+    pub enemy_count: u32,
+    /// Chance of dumpsters and enemies having anything on them.
+    pub loot: u32,
+    pub healing: u32,
+    /// Whether dying ends the run. Without it you come to at the alley
+    /// entrance, minus your caps.
+    pub permadeath: bool,
+}
+
+impl Default for DifficultySettings {
+    fn default() -> Self {
+        DifficultySettings {
+            enemy_health: 100,
+            enemy_attack: 100,
+            enemy_count: 100,
+            loot: 100,
+            healing: 100,
+            permadeath: true,
+        }
+    }
+}
+
+impl DifficultySettings {
+    /// Names of the settings, in the order `adjust` numbers them.
+    pub const LABELS: [&'static str; 6] = [
+        "Enemy health",
+        "Enemy attack",
+        "Enemy numbers",
+        "Loot",
+        "Healing",
+        "Permadeath",
+    ];
+
+    pub fn value_text(&self, idx: usize) -> String {
+        match idx {
+            0 => format!("{}%", self.enemy_health),
+            1 => format!("{}%", self.enemy_attack),
+            2 => format!("{}%", self.enemy_count),
+            3 => format!("{}%", self.loot),
+            4 => format!("{}%", self.healing),
+            _ => (if self.permadeath { "On" } else { "Off" }).to_string(),
+        }
+    }
+
+    /// Nudges setting `idx` up or down a step. Permadeath just flips.
+    pub fn adjust(&mut self, idx: usize, up: bool) {
+        let value = match idx {
+            0 => &mut self.enemy_health,
+            1 => &mut self.enemy_attack,
+            2 => &mut self.enemy_count,
+            3 => &mut self.loot,
+            4 => &mut self.healing,
+            _ => {
+                self.permadeath = !self.permadeath;
+                return;
+            }
+        };
+        *value = if up {
+            *value + CUSTOM_STEP
+        } else {
+            value.saturating_sub(CUSTOM_STEP)
+        }
+        .clamp(CUSTOM_RANGE.0, CUSTOM_RANGE.1);
+    }
+
+    pub fn enemy_health(&self, health: i32) -> i32 {
+        (health * self.enemy_health as i32 / 100).max(1)
+    }
+
+    pub fn enemy_attack(&self, attack: i32) -> i32 {
+        (attack * self.enemy_attack as i32 / 100).max(1)
+    }
+
+    /// How many of `count` enemies turn up. Never fewer than one.
+    pub fn enemy_count(&self, count: u32) -> u32 {
+        (count * self.enemy_count / 100).max(1)
+    }
+
+    pub fn healing(&self, amount: i32) -> i32 {
+        amount * self.healing as i32 / 100
+    }
+
+    /// Percent of the usual score a run on these settings earns: more for
+    /// tougher enemies, less for generous loot and healing, and half
+    /// without permadeath.
+    pub fn score_percent(&self) -> u32 {
+        let threat = (self.enemy_health + self.enemy_attack + self.enemy_count) / 3;
+        let help = ((self.loot + self.healing) / 2).max(1);
+        let percent = (threat * 100 / help).clamp(25, 250);
+        if self.permadeath {
+            percent
+        } else {
+            percent / 2
+        }
+    }
+
+    /// Saved as six numbers, permadeath last as 1 or 0.
+    pub fn to_text(self) -> String {
+        format!(
+            "{} {} {} {} {} {}",
+            self.enemy_health,
+            self.enemy_attack,
+            self.enemy_count,
+            self.loot,
+            self.healing,
+            self.permadeath as u8
+        )
+    }
+
+    pub fn from_text(text: &str) -> Option<Self> {
+        let values: Vec<u32> = text
+            .split_whitespace()
+            .map(|value| value.parse().ok())
+            .collect::<Option<_>>()?;
+        let [
+            enemy_health,
+            enemy_attack,
+            enemy_count,
+            loot,
+            healing,
+            permadeath,
+        ] = values[..]
+        else {
+            return None;
+        };
+        let clamp = |n: u32| n.clamp(CUSTOM_RANGE.0, CUSTOM_RANGE.1);
+        Some(DifficultySettings {
+            enemy_health: clamp(enemy_health),
+            enemy_attack: clamp(enemy_attack),
+            enemy_count: clamp(enemy_count),
+            loot: clamp(loot),
+            healing: clamp(healing),
+            permadeath: permadeath != 0,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_harder_is_worth_more() {
+        let custom = DifficultySettings::default();
+        let percents: Vec<u32> = Difficulty::ALL[..4]
+            .iter()
+            .map(|d| d.settings(custom).score_percent())
+            .collect();
+        assert!(percents.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(Difficulty::Normal.settings(custom).score_percent(), 100);
+
+        let nightmare = Difficulty::Nightmare.settings(custom);
+        assert_eq!(nightmare.enemy_health(20), 30);
+        assert_eq!(nightmare.healing(20), 10);
+        assert_eq!(nightmare.enemy_count(3), 4);
+    }
+
+    #[test]
+    fn test_custom_settings_round_trip() {
+        let mut custom = DifficultySettings::default();
+        custom.adjust(0, true);
+        custom.adjust(3, false);
+        custom.adjust(5, true);
+        for _ in 0..20 {
+            custom.adjust(4, true);
+        }
+        assert_eq!(custom.enemy_health, 125);
+        assert_eq!(custom.loot, 75);
+        assert_eq!(custom.healing, 300);
+        assert!(!custom.permadeath);
+        assert_eq!(
+            DifficultySettings::from_text(&custom.to_text()),
+            Some(custom)
+        );
+        assert_eq!(DifficultySettings::from_text("1 2 3"), None);
+    }
+}
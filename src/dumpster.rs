@@ -0,0 +1,234 @@
+use crate::items::MaterialKind;
+use crate::loot::roll_entry;
+use rand::Rng;
+use std::fmt;
+
+/// The different bins out behind the burger place. Each one has its own
+/// loot, its own dangers and its own odds of hiding something special.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DumpsterKind {
+    Regular,
+    GreaseBin,
+    Recycling,
+    Compactor,
+    MedicalWaste,
+    Electronics,
+}
+
+/// Something nasty waiting in a bin.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Hazard {
+    /// Raw damage before armor.
+    Burn(i32),
+    Cut(i32),
+    Crush(i32),
+    Shock(i32),
+    /// Lose 1 HP a turn for this many turns.
+    Infection(u32),
+}
+
+impl Hazard {
+    pub fn describe(&self) -> &'static str {
+        match self {
+            Hazard::Burn(_) => "Hot grease! You burn your hands",
+            Hazard::Cut(_) => "You slice your hand on broken glass",
+            Hazard::Crush(_) => "The compactor lurches and pins your arm",
+            Hazard::Shock(_) => "A busted power supply zaps you",
+            Hazard::Infection(_) => "A used needle jabs you. That's going to get infected",
+        }
+    }
+}
+
+/// What a kind of dumpster tends to hold.
+pub struct LootProfile {
+    pub weapon_chance: f64,
+    pub armor_chance: f64,
+    pub consumable_chance: f64,
+    /// Consumables to pick from, or empty for the usual mix.
+    pub consumables: &'static [&'static str],
+    pub material_count: (u32, u32),
+    /// Junk to pick from, or empty for any kind.
+    pub materials: &'static [MaterialKind],
+    pub cash_chance: f64,
+    pub cash: (u32, u32),
+    /// Chance of getting hurt while digging through it.
+    pub hazard_chance: f64,
+    pub hazard: Option<Hazard>,
+    /// Relative odds that this is where the bolt cutters ended up.
+    pub cutters_weight: u32,
+}
+
+impl DumpsterKind {
+    pub const ALL: [DumpsterKind; 6] = [
+        DumpsterKind::Regular,
+        DumpsterKind::GreaseBin,
+        DumpsterKind::Recycling,
+        DumpsterKind::Compactor,
+        DumpsterKind::MedicalWaste,
+        DumpsterKind::Electronics,
+    ];
+
+    pub fn random(rng: &mut impl Rng) -> Self {
+        Self::ALL[rng.gen_range(0..Self::ALL.len())]
+    }
+
+    pub fn glyph(&self) -> char {
+        match self {
+            DumpsterKind::Regular => '▓',
+            DumpsterKind::GreaseBin => '▄',
+            DumpsterKind::Recycling => '▤',
+            DumpsterKind::Compactor => '█',
+            DumpsterKind::MedicalWaste => '╋',
+            DumpsterKind::Electronics => '▦',
+        }
+    }
+
+    pub fn loot(&self) -> LootProfile {
+        match self {
+            DumpsterKind::Regular => LootProfile {
+                weapon_chance: 0.7,
+                armor_chance: 0.7,
+                consumable_chance: 0.8,
+                consumables: &[],
+                material_count: (0, 3),
+                materials: &[],
+                cash_chance: 0.6,
+                cash: (2, 12),
+                hazard_chance: 0.0,
+                hazard: None,
+                cutters_weight: 2,
+            },
+            DumpsterKind::GreaseBin => LootProfile {
+                weapon_chance: 0.2,
+                armor_chance: 0.1,
+                consumable_chance: 0.95,
+                consumables: &["Burger Leftovers", "Mystery Meat", "Fermented Fries"],
+                material_count: (0, 1),
+                materials: &[MaterialKind::Cloth, MaterialKind::Bottle],
+                cash_chance: 0.3,
+                cash: (1, 6),
+                hazard_chance: 0.35,
+                hazard: Some(Hazard::Burn(10)),
+                cutters_weight: 1,
+            },
+            DumpsterKind::Recycling => LootProfile {
+                weapon_chance: 0.4,
+                armor_chance: 0.0,
+                consumable_chance: 0.2,
+                consumables: &["Expired Soda", "Energy Drink"],
+                material_count: (2, 4),
+                materials: &[
+                    MaterialKind::Bottle,
+                    MaterialKind::ScrapMetal,
+                    MaterialKind::Wire,
+                ],
+                // Bottle deposits add up
+                cash_chance: 0.7,
+                cash: (3, 15),
+                hazard_chance: 0.15,
+                hazard: Some(Hazard::Cut(5)),
+                cutters_weight: 2,
+            },
+            DumpsterKind::Compactor => LootProfile {
+                weapon_chance: 0.5,
+                armor_chance: 0.6,
+                consumable_chance: 0.3,
+                consumables: &[],
+                material_count: (1, 3),
+                materials: &[
+                    MaterialKind::Cloth,
+                    MaterialKind::DuctTape,
+                    MaterialKind::ZipTies,
+                ],
+                cash_chance: 0.3,
+                cash: (2, 8),
+                hazard_chance: 0.1,
+                hazard: Some(Hazard::Crush(14)),
+                cutters_weight: 5,
+            },
+            DumpsterKind::MedicalWaste => LootProfile {
+                weapon_chance: 0.0,
+                armor_chance: 0.1,
+                consumable_chance: 0.9,
+                consumables: &["Bandages", "Painkillers", "Adrenaline Shot"],
+                material_count: (0, 2),
+                materials: &[MaterialKind::Superglue, MaterialKind::Cloth],
+                cash_chance: 0.1,
+                cash: (1, 5),
+                hazard_chance: 0.3,
+                hazard: Some(Hazard::Infection(8)),
+                cutters_weight: 1,
+            },
+            DumpsterKind::Electronics => LootProfile {
+                weapon_chance: 0.3,
+                armor_chance: 0.2,
+                consumable_chance: 0.1,
+                consumables: &[],
+                material_count: (1, 3),
+                materials: &[
+                    MaterialKind::Wire,
+                    MaterialKind::ScrapMetal,
+                    MaterialKind::Superglue,
+                ],
+                cash_chance: 0.4,
+                cash: (2, 10),
+                hazard_chance: 0.25,
+                hazard: Some(Hazard::Shock(8)),
+                cutters_weight: 3,
+            },
+        }
+    }
+
+    /// Picks which of these dumpsters hides the bolt cutters, if there are
+    /// any dumpsters at all.
+    pub fn pick_cutters_dumpster(rng: &mut impl Rng, kinds: &[DumpsterKind]) -> Option<usize> {
+        let table: Vec<(u32, usize)> = kinds
+            .iter()
+            .enumerate()
+            .map(|(idx, kind)| (kind.loot().cutters_weight, idx))
+            .collect();
+        roll_entry(rng, &table)
+    }
+}
+
+impl fmt::Display for DumpsterKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DumpsterKind::Regular => write!(f, "Dumpster"),
+            DumpsterKind::GreaseBin => write!(f, "Grease Bin"),
+            DumpsterKind::Recycling => write!(f, "Recycling"),
+            DumpsterKind::Compactor => write!(f, "Cardboard Compactor"),
+            DumpsterKind::MedicalWaste => write!(f, "Medical Waste"),
+            DumpsterKind::Electronics => write!(f, "E-Waste Bin"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::items::Consumable;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    #[test]
+    fn test_loot_pools_name_real_consumables() {
+        for kind in DumpsterKind::ALL {
+            for name in kind.loot().consumables {
+                assert!(Consumable::named(name).is_some(), "{} in {}", name, kind);
+            }
+        }
+    }
+
+    #[test]
+    fn test_cutters_favour_compactors() {
+        let mut rng = StdRng::seed_from_u64(5);
+        let kinds = [DumpsterKind::MedicalWaste, DumpsterKind::Compactor];
+        let compactor_picks = (0..600)
+            .filter(|_| DumpsterKind::pick_cutters_dumpster(&mut rng, &kinds) == Some(1))
+            .count();
+        // 5 in 6 odds
+        assert!(compactor_picks > 400);
+        assert_eq!(DumpsterKind::pick_cutters_dumpster(&mut rng, &[]), None);
+    }
+}
@@ -1,7 +1,43 @@
-use crate::items::{Armor, Consumable, Inventory, Weapon};
+use crate::achievements::Achievement;
+use crate::backgrounds::Background;
+use crate::clock::Clock;
+use crate::combat::{AttackProfile, DefenseProfile, HitKind, resolve_attack};
+use crate::crafting::RECIPES;
+use crate::daily::{self, DailyRun, DailyScores};
+use crate::dialogue::DialogueEffect;
+use crate::difficulty::{Difficulty, DifficultySettings};
+use crate::dumpster::{DumpsterKind, Hazard};
+use crate::items::{
+    Armor, Consumable, Inventory, Item, ItemPool, MaterialKind, RepairOutcome, Tool, Weapon,
+};
+use crate::keymap::{self, Action};
+use crate::locks::{Lock, LockKind};
+use crate::loot::{GroundItem, roll_loot};
+use crate::messages::{LOG_PAGE, MessageKind, MessageLog};
+use crate::meta::{Unlock, UnlockKind};
+use crate::morgue;
+use crate::npc::Npc;
+use crate::profile::Profile;
+use crate::progression::{LEVEL_UP_ATTACK, LEVEL_UP_HEALTH, Perk, Progression};
+use crate::quests::{GameEvent, QuestLog, QuestUpdate, alley_quests};
+use crate::restock::{TrashRun, TrashRunStep};
+use crate::save::SaveGame;
+use crate::scene::{Leave, PAUSE_OPTIONS, SETTINGS_ROWS, Scene};
+use crate::score::{HighScore, HighScores, MAX_NAME_LEN, RunResult, is_name_char};
+use crate::setup::RunSetup;
+use crate::spawn::{SpawnConfig, SpawnDirector, SpawnPoint, SpawnPointKind};
+use crate::stats::{DeathCause, RunStats};
+use crate::stealth::{Awareness, Noise, NoiseSource, SNEAK_ATTACK_ACCURACY, spot_range};
+use crate::survival::{ATTACK_COST, RUN_COST, Survival};
+use crate::vendor::{TradeError, Vendor};
 use crossterm::event::{KeyCode, KeyEvent};
-use rand::Rng;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::HashSet;
 use std::fmt;
+use std::fs;
+use std::path::PathBuf;
+use std::time::SystemTime;
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Position {
@@ -27,6 +63,14 @@ pub struct Player {
     pub attack: i32,
     pub scavenged_items: u32,
     pub inventory: Inventory,
+    pub progression: Progression,
+    pub survival: Survival,
+    /// Turns left on an infection, each costing 1 HP.
+    pub infection: u32,
+    /// Moving quietly: softer footsteps and harder to spot, but no running.
+    pub sneaking: bool,
+    /// Who they were before the alley. None is a plain punk.
+    pub background: Option<Background>,
 }
 
 impl Player {
@@ -38,17 +82,40 @@ impl Player {
             attack: 10,
             scavenged_items: 0,
             inventory: Inventory::new(),
+            progression: Progression::new(),
+            survival: Survival::new(),
+            infection: 0,
+            sneaking: false,
+            background: None,
         }
     }
 
+    /// Takes on a background's base stats.
+    pub fn set_background(&mut self, background: Background) {
+        self.background = Some(background);
+        self.max_health = background.max_health();
+        self.health = self.max_health;
+        self.attack = background.attack();
+    }
+
+    /// Healing after the background has had its say.
+    pub fn healing(&self, amount: i32) -> i32 {
+        self.background.map_or(amount, |b| b.healing(amount))
+    }
+
     pub fn is_alive(&self) -> bool {
         self.health > 0
     }
 
     pub fn take_damage(&mut self, damage: i32) {
-        let defense = self.inventory.total_defense_bonus();
+        let defense = self.total_defense();
         let actual_damage = (damage - defense).max(1); // Always take at least 1 damage
-        self.health = (self.health - actual_damage).max(0);
+        self.lose_health(actual_damage);
+    }
+
+    /// Loses health directly, for damage that has already been through defense.
+    pub fn lose_health(&mut self, amount: i32) {
+        self.health = (self.health - amount).max(0);
     }
 
     pub fn heal(&mut self, amount: i32) {
@@ -56,17 +123,90 @@ impl Player {
     }
 
     pub fn total_attack(&self) -> i32 {
-        self.attack + self.inventory.total_damage_bonus()
+        self.attack
+            + self.inventory.total_damage_bonus()
+            + 3 * self.progression.perk_rank(Perk::DirtyFighting)
+            - self.survival.attack_penalty()
+    }
+
+    pub fn total_defense(&self) -> i32 {
+        self.inventory.total_defense_bonus() + 2 * self.progression.perk_rank(Perk::ToughSkin)
+    }
+
+    pub fn attack_profile(&self) -> AttackProfile {
+        AttackProfile::new(
+            80 - self.survival.accuracy_penalty(),
+            self.total_attack(),
+            5 + self.background.map_or(0, |b| b.crit_bonus()),
+        )
+    }
+
+    pub fn defense_profile(&self) -> DefenseProfile {
+        DefenseProfile {
+            evasion: 10 + self.background.map_or(0, |b| b.evasion_bonus()),
+            defense: self.total_defense(),
+        }
+    }
+
+    /// Grants XP and raises base stats for every level gained.
+    /// Returns the number of levels gained.
+    pub fn gain_xp(&mut self, amount: u32) -> u32 {
+        let levels = self.progression.gain_xp(amount);
+        for _ in 0..levels {
+            self.max_health += LEVEL_UP_HEALTH;
+            self.attack += LEVEL_UP_ATTACK;
+            self.heal(LEVEL_UP_HEALTH);
+        }
+        levels
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum EnemyType {
     Rat,
     FerralCat,
     RivalPunk,
 }
 
+impl EnemyType {
+    pub const ALL: [EnemyType; 3] = [EnemyType::Rat, EnemyType::FerralCat, EnemyType::RivalPunk];
+
+    pub fn glyph(&self) -> char {
+        match self {
+            EnemyType::Rat => 'r',
+            EnemyType::FerralCat => 'c',
+            EnemyType::RivalPunk => 'P',
+        }
+    }
+
+    /// Accuracy, evasion and crit chance for this kind of enemy.
+    /// Rats are hard to pin down, punks know how to throw a punch.
+    pub fn combat_stats(&self) -> (i32, i32, i32) {
+        match self {
+            EnemyType::Rat => (65, 25, 5),
+            EnemyType::FerralCat => (75, 20, 10),
+            EnemyType::RivalPunk => (80, 10, 8),
+        }
+    }
+
+    pub fn xp_reward(&self) -> u32 {
+        match self {
+            EnemyType::Rat => 10,
+            EnemyType::FerralCat => 25,
+            EnemyType::RivalPunk => 50,
+        }
+    }
+
+    /// How much of a level's spawn budget this enemy takes up.
+    pub fn threat(&self) -> u32 {
+        match self {
+            EnemyType::Rat => 1,
+            EnemyType::FerralCat => 2,
+            EnemyType::RivalPunk => 4,
+        }
+    }
+}
+
 impl fmt::Display for EnemyType {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
@@ -85,6 +225,7 @@ pub struct Enemy {
     pub attack: i32,
     pub enemy_type: EnemyType,
     pub is_alive: bool,
+    pub awareness: Awareness,
 }
 
 impl Enemy {
@@ -96,6 +237,7 @@ impl Enemy {
             attack: 3,
             enemy_type: EnemyType::Rat,
             is_alive: true,
+            awareness: Awareness::Unaware,
         }
     }
 
@@ -107,6 +249,7 @@ impl Enemy {
             attack: 7,
             enemy_type: EnemyType::FerralCat,
             is_alive: true,
+            awareness: Awareness::Unaware,
         }
     }
 
@@ -118,6 +261,23 @@ impl Enemy {
             attack: 10,
             enemy_type: EnemyType::RivalPunk,
             is_alive: true,
+            awareness: Awareness::Unaware,
+        }
+    }
+
+    /// Scales health and attack to the difficulty.
+    pub fn toughened(mut self, rules: &DifficultySettings) -> Self {
+        self.max_health = rules.enemy_health(self.max_health);
+        self.health = self.max_health;
+        self.attack = rules.enemy_attack(self.attack);
+        self
+    }
+
+    pub fn new_of_type(enemy_type: EnemyType, x: i32, y: i32) -> Self {
+        match enemy_type {
+            EnemyType::Rat => Self::new_rat(x, y),
+            EnemyType::FerralCat => Self::new_feral_cat(x, y),
+            EnemyType::RivalPunk => Self::new_rival_punk(x, y),
         }
     }
 
@@ -129,18 +289,30 @@ impl Enemy {
         }
     }
 
-    pub fn get_char(&self) -> char {
-        match self.enemy_type {
-            EnemyType::Rat => 'r',
-            EnemyType::FerralCat => 'c',
-            EnemyType::RivalPunk => 'P',
+    pub fn attack_profile(&self) -> AttackProfile {
+        let (accuracy, _, crit_chance) = self.enemy_type.combat_stats();
+        AttackProfile::new(accuracy, self.attack, crit_chance)
+    }
+
+    pub fn defense_profile(&self) -> DefenseProfile {
+        let (_, evasion, _) = self.enemy_type.combat_stats();
+        DefenseProfile {
+            evasion,
+            defense: 0,
         }
     }
+
+    pub fn get_char(&self) -> char {
+        self.enemy_type.glyph()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::clock::Phase;
+    use crate::quests::QuestStatus;
+    use crate::survival::{MAX_HUNGER, MAX_STAMINA};
 
     #[test]
     fn test_player_creation() {
@@ -176,6 +348,29 @@ mod tests {
         assert_eq!(player.health, 100);
     }
 
+    #[test]
+    fn test_player_level_up() {
+        let mut player = Player::new(0, 0);
+        player.take_damage(30);
+        assert_eq!(player.gain_xp(EnemyType::RivalPunk.xp_reward()), 1);
+        assert_eq!(player.progression.level, 2);
+        assert_eq!(player.max_health, 110);
+        assert_eq!(player.attack, 12);
+        assert_eq!(player.health, 80);
+
+        player.progression.choose_perk(Perk::DirtyFighting);
+        assert_eq!(player.total_attack(), 15);
+    }
+
+    #[test]
+    fn test_tough_skin_reduces_damage() {
+        let mut player = Player::new(0, 0);
+        player.gain_xp(50);
+        player.progression.choose_perk(Perk::ToughSkin);
+        player.take_damage(10);
+        assert_eq!(player.health, 100 + LEVEL_UP_HEALTH - 8);
+    }
+
     #[test]
     fn test_enemy_creation() {
         let rat = Enemy::new_rat(5, 5);
@@ -221,11 +416,666 @@ mod tests {
         assert_eq!(game.height, 20);
         assert!(game.player.is_alive());
         assert_eq!(game.player.scavenged_items, 0);
-        assert_eq!(game.dumpsters.len(), 3);
+        assert_eq!(game.dumpsters.len(), 5);
+        let cutters = game
+            .dumpsters
+            .iter()
+            .filter(|d| d.tool == Some(Tool::BoltCutters));
+        assert_eq!(cutters.count(), 1);
         assert!(!game.enemies.is_empty());
         assert_eq!(game.mode, GameMode::Exploring);
     }
 
+    #[test]
+    fn test_combat_rolls_follow_seed() {
+        let mut a = GameState::with_seed(60, 20, 99);
+        let mut b = GameState::with_seed(60, 20, 99);
+        for _ in 0..3 {
+            a.combat_round(0);
+            b.combat_round(0);
+        }
+        assert_eq!(a.enemies[0].health, b.enemies[0].health);
+        assert_eq!(a.player.health, b.player.health);
+        assert_eq!(a.messages, b.messages);
+    }
+
+    #[test]
+    fn test_ground_items_are_picked_up() {
+        let mut game = GameState::new(60, 20);
+        let position = game.player.position;
+        game.ground_items.push(GroundItem {
+            position,
+            item: Item::Weapon(Weapon::random_generate()),
+        });
+        game.ground_items.push(GroundItem {
+            position,
+            item: Item::Weapon(Weapon::random_generate()),
+        });
+
+        // Only one fits in the weapon slot; the other stays on the ground
+        game.check_ground_items();
+        assert!(game.player.inventory.weapon.is_some());
+        assert_eq!(game.ground_items.len(), 1);
+
+        // Swapping leaves the old weapon behind instead
+        game.swap_ground_item();
+        assert_eq!(game.ground_items.len(), 1);
+        assert!(game.player.inventory.weapon.is_some());
+    }
+
+    #[test]
+    fn test_dialogue_can_start_a_fight() {
+        let mut game = GameState::new(60, 20);
+        let enemies = game.enemies.len();
+        let sarge = game.npcs.iter().position(|n| n.name == "Sarge").unwrap();
+
+        game.start_dialogue(sarge);
+        // "That jacket looks warm." then "Take it from him."
+        game.handle_input(KeyEvent::from(KeyCode::Char('4')));
+        game.handle_input(KeyEvent::from(KeyCode::Char('2')));
+
+        assert_eq!(game.reputation, -3);
+        assert!(game.npcs.iter().all(|n| n.name != "Sarge"));
+        assert_eq!(game.enemies.len(), enemies + 1);
+        assert_eq!(game.mode, GameMode::Combat(enemies));
+    }
+
+    #[test]
+    fn test_main_quest_opens_the_exit() {
+        let mut game = GameState::new(60, 20);
+        game.enemies.clear();
+
+        // Scavenge three dumpsters; the last one holds the bolt cutters
+        for (idx, dumpster) in game.dumpsters.iter_mut().enumerate() {
+            dumpster.cash = 5;
+            dumpster.tool = (idx == 2).then_some(Tool::BoltCutters);
+        }
+        let positions: Vec<Position> = game.dumpsters.iter().map(|d| d.position).collect();
+        for position in &positions[..3] {
+            game.player.position = *position;
+            game.check_dumpster_scavenge();
+        }
+        assert!(game.player.inventory.has_tool(Tool::BoltCutters));
+        assert!(!game.quests.main_complete());
+        let cutters = game.quests.quests.iter().find(|q| q.id == "cutters");
+        assert_eq!(cutters.unwrap().status, QuestStatus::Complete);
+
+        // Cut the chain from just below the gate, then walk through
+        let gate = game.locks.iter().find(|l| l.exit).unwrap().position;
+        game.player.position = Position::new(gate.x, gate.y + 1);
+        game.handle_input(KeyEvent::from(KeyCode::Char('w')));
+        assert!(game.quests.main_complete());
+        assert_eq!(game.mode, GameMode::LevelComplete);
+
+        game.handle_input(KeyEvent::from(KeyCode::Char('w')));
+        assert_eq!(game.mode, GameMode::Victory);
+        assert!(game.profile.has(Achievement::Escapee));
+    }
+
+    #[test]
+    fn test_dumpster_diver_needs_every_bin() {
+        let mut game = GameState::new(60, 20);
+        game.enemies.clear();
+        let scavenge = |game: &mut GameState, idx: usize| {
+            game.dumpsters[idx].restock(&mut game.rng);
+            game.dumpsters[idx].cash = 5;
+            game.player.position = game.dumpsters[idx].position;
+            game.check_dumpster_scavenge();
+        };
+
+        // The same bin over and over, however often it's refilled
+        for _ in 0..game.dumpsters.len() + 1 {
+            scavenge(&mut game, 0);
+        }
+        assert!(game.player.scavenged_items as usize > game.dumpsters.len());
+        assert!(!game.profile.has(Achievement::DumpsterDiver));
+
+        for idx in 1..game.dumpsters.len() {
+            scavenge(&mut game, idx);
+        }
+        assert!(game.profile.has(Achievement::DumpsterDiver));
+    }
+
+    #[test]
+    fn test_time_keeps_passing_after_the_exit_opens() {
+        let mut game = GameState::new(60, 20);
+        game.enemies.clear();
+        game.mode = GameMode::LevelComplete;
+        game.player.inventory.consumable = Consumable::named("Bandages");
+
+        game.handle_input(KeyEvent::from(KeyCode::Char('a')));
+        game.handle_input(KeyEvent::from(KeyCode::Char('e')));
+        assert_eq!(game.turn_count, 2);
+        assert_eq!(game.player.survival.hunger, MAX_HUNGER - 1);
+        assert_eq!(game.mode, GameMode::LevelComplete);
+    }
+
+    #[test]
+    fn test_run_stats_track_what_hurt_you() {
+        let mut game = GameState::new(60, 20);
+        game.player.health = 5;
+        game.player.infection = 10;
+        for _ in 0..3 {
+            game.pass_turn(false);
+        }
+        assert_eq!(game.stats.damage_taken, 3);
+        assert_eq!(game.stats.cause_of_death, None);
+
+        game.hurt_player(10, DeathCause::Killed(EnemyType::Rat));
+        assert_eq!(game.stats.damage_taken, 5);
+        assert_eq!(
+            game.stats.cause_of_death,
+            Some(DeathCause::Killed(EnemyType::Rat))
+        );
+    }
+
+    #[test]
+    fn test_picking_a_background() {
+        let mut game = GameState::new(60, 20);
+        game.mode = GameMode::CharacterSelect(0);
+        // The skate punk has to be bought first
+        for _ in 0..Background::ALL.len() {
+            game.handle_input(KeyEvent::from(KeyCode::Char('s')));
+        }
+        game.handle_input(KeyEvent::from(KeyCode::Enter));
+        assert_eq!(game.mode, GameMode::CharacterSelect(3));
+        assert!(game.notice.is_some());
+
+        game.handle_input(KeyEvent::from(KeyCode::Char('w')));
+        game.handle_input(KeyEvent::from(KeyCode::Char('w')));
+        game.handle_input(KeyEvent::from(KeyCode::Char('w')));
+        game.handle_input(KeyEvent::from(KeyCode::Enter));
+        assert_eq!(game.mode, GameMode::Exploring);
+        assert_eq!(game.player.background, Some(Background::GutterBrawler));
+        assert_eq!(game.player.max_health, 120);
+        assert_eq!(game.player.attack_profile().crit_chance, 15);
+        assert_eq!(
+            game.player.inventory.weapon.as_ref().unwrap().name,
+            "Brass Knuckles"
+        );
+
+        // A medic gets more out of every bandage
+        game.new_run(Background::SquatterMedic);
+        game.player.health = 10;
+        game.use_consumable();
+        assert_eq!(game.player.health, 10 + 45);
+    }
+
+    #[test]
+    fn test_difficulty_scales_the_run() {
+        let easy = RunSetup {
+            difficulty: Difficulty::Easy,
+            rules: Difficulty::Easy.settings(DifficultySettings::default()),
+            ..Default::default()
+        };
+        let mut game = GameState::with_setup(60, 20, 7, &easy);
+        let punk = game
+            .enemies
+            .iter()
+            .find(|e| e.enemy_type == EnemyType::RivalPunk)
+            .unwrap();
+        assert_eq!((punk.max_health, punk.attack), (37, 7));
+        // Picking a fight with an NPC plays by the same rules
+        game.npc_turns_hostile(0);
+        let npc = game.enemies.last().unwrap();
+        assert_eq!((npc.max_health, npc.attack), (37, 7));
+
+        // Easy lets you get back up, for less score
+        game.player.inventory.cash = 30;
+        game.mode = GameMode::GameOver;
+        game.handle_input(KeyEvent::from(KeyCode::Char('x')));
+        assert_eq!(game.mode, GameMode::Exploring);
+        assert_eq!(game.player.health, 50);
+        assert_eq!(game.player.inventory.cash, 0);
+        assert_eq!(game.stats.knockdowns, 1);
+        assert!(game.run_result().score_percent < 100);
+    }
+
+    #[test]
+    fn test_saved_runs_pick_up_where_they_left_off() {
+        let dir = std::env::temp_dir().join(format!("rust_punk_save_{}", std::process::id()));
+        let mut game = GameState::new(60, 20);
+        game.data_dir = Some(dir.clone());
+        game.profile.cred = 500;
+        game.profile.buy(Unlock::PocketKnife).unwrap();
+        game.new_run(Background::SquatterMedic);
+        for c in "dddddsssszaaaawwwwlfflzddddssss".chars() {
+            game.handle_input(KeyEvent::from(KeyCode::Char(c)));
+        }
+        // Looking at the log isn't part of the run
+        assert!(!game.save.keys.contains(&KeyCode::Char('l')));
+        let snapshot = |game: &GameState| {
+            (
+                game.turn_count,
+                game.player.position,
+                game.player.health,
+                game.enemies
+                    .iter()
+                    .map(|e| (e.position, e.health))
+                    .collect::<Vec<_>>(),
+            )
+        };
+        let before = snapshot(&game);
+
+        // Save from the pause menu, then quit to the menu
+        game.scene = Scene::Paused(0);
+        game.handle_scene_input(KeyEvent::from(KeyCode::Char('s')));
+        game.handle_scene_input(KeyEvent::from(KeyCode::Enter));
+        assert_eq!(game.notice.as_deref(), Some("Saved."));
+        game.handle_scene_input(KeyEvent::from(KeyCode::Esc));
+        assert_eq!(game.scene, Scene::Game);
+        game.ask_to_leave(Leave::ToMenu, false);
+        game.handle_scene_input(KeyEvent::from(KeyCode::Char('n')));
+        assert_eq!(game.scene, Scene::Game);
+        game.ask_to_leave(Leave::ToMenu, false);
+        game.handle_scene_input(KeyEvent::from(KeyCode::Char('y')));
+        assert_eq!(game.mode, GameMode::Title(1));
+
+        // Changing the difficulty since doesn't change the saved run
+        game.profile.set_difficulty(Difficulty::Nightmare);
+        game.handle_input(KeyEvent::from(KeyCode::Enter));
+        assert_eq!(snapshot(&game), before);
+        assert_eq!(game.difficulty, Difficulty::Normal);
+        assert_eq!(
+            game.player.inventory.weapon.as_ref().unwrap().name,
+            "Switchblade"
+        );
+
+        // Once the run is over there's nothing to continue
+        game.mode = GameMode::GameOver;
+        game.handle_input(KeyEvent::from(KeyCode::Char('x')));
+        assert!(!game.has_save());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_replaying_keeps_to_the_run() {
+        let mut game = GameState::new(60, 20);
+        game.replaying = true;
+        game.record_event(GameEvent::Crafted("Glass Shank".to_string()));
+        assert!(!game.profile.has(Achievement::JunkArtist));
+        game.finish_run();
+        assert_eq!(game.cred_earned, None);
+
+        game.replaying = false;
+        game.record_event(GameEvent::Crafted("Glass Shank".to_string()));
+        assert!(game.profile.has(Achievement::JunkArtist));
+        game.finish_run();
+        assert!(game.cred_earned.is_some());
+    }
+
+    #[test]
+    fn test_daily_runs_share_an_alley() {
+        let mut game = GameState::new(60, 20);
+        game.profile.cred = 500;
+        game.profile.buy(Unlock::ThickSkin).unwrap();
+        game.start_daily_run();
+        assert!(game.daily.as_ref().unwrap().scored);
+        // Unlocks don't follow you into the daily alley
+        assert_eq!(game.player.max_health, 100);
+        let layout = |game: &GameState| {
+            (
+                game.enemies.iter().map(|e| e.position).collect::<Vec<_>>(),
+                game.dumpsters.iter().map(|d| d.kind).collect::<Vec<_>>(),
+            )
+        };
+        let first = layout(&game);
+
+        // The result goes on the daily board, not the high scores
+        game.mode = GameMode::GameOver;
+        game.handle_input(KeyEvent::from(KeyCode::Enter));
+        game.handle_input(KeyEvent::from(KeyCode::Enter));
+        assert_eq!(game.mode, GameMode::DailyScores { from_title: false });
+        assert!(game.high_scores.entries.is_empty());
+        assert_eq!(game.daily_scores.entries.len(), 1);
+
+        // Another go is the same alley, but only practice
+        game.handle_input(KeyEvent::from(KeyCode::Enter));
+        assert_eq!(game.mode, GameMode::Exploring);
+        assert_eq!(layout(&game), first);
+        assert!(!game.can_record_score());
+    }
+
+    #[test]
+    fn test_unlocks_carry_into_the_next_run() {
+        let mut game = GameState::new(60, 20);
+        game.profile.cred = 500;
+        game.profile.buy(Unlock::PocketKnife).unwrap();
+        game.profile.buy(Unlock::ThickSkin).unwrap();
+        game.profile.buy(Unlock::LoadingDock).unwrap();
+        game.profile.toggle_alley(Unlock::LoadingDock);
+        let punks = |game: &GameState| {
+            game.enemies
+                .iter()
+                .filter(|e| e.enemy_type == EnemyType::RivalPunk)
+                .count()
+        };
+        let usual_punks = punks(&game);
+
+        game.new_run(Background::SquatterMedic);
+        assert_eq!(game.mode, GameMode::Exploring);
+        assert_eq!(
+            game.player.inventory.weapon.as_ref().unwrap().name,
+            "Switchblade"
+        );
+        assert_eq!(game.player.max_health, 110);
+        assert_eq!(game.depth, 2);
+        assert_eq!(punks(&game), usual_punks + 1);
+
+        // Dying banks cred once
+        game.player.health = 0;
+        game.mode = GameMode::GameOver;
+        game.handle_input(KeyEvent::from(KeyCode::Char('x')));
+        let banked = game.profile.cred;
+        assert_eq!(game.cred_earned, Some(game.run_result().score() / 10));
+        game.handle_input(KeyEvent::from(KeyCode::Char('x')));
+        assert_eq!(game.profile.cred, banked);
+        assert_eq!(game.profile.runs, 1);
+    }
+
+    #[test]
+    fn test_recording_a_run_on_the_high_scores() {
+        let mut game = GameState::new(60, 20);
+        game.player.health = 0;
+        game.mode = GameMode::GameOver;
+        game.stats.defeated_enemy(EnemyType::Rat);
+
+        game.handle_input(KeyEvent::from(KeyCode::Enter));
+        assert_eq!(game.mode, GameMode::NameEntry);
+        for c in "Ace\t!".chars() {
+            game.handle_input(KeyEvent::from(KeyCode::Char(c)));
+        }
+        assert_eq!(game.name_entry, "Ace");
+        game.handle_input(KeyEvent::from(KeyCode::Enter));
+        assert_eq!(game.mode, GameMode::HighScores { from_title: false });
+
+        let entry = &game.high_scores.entries[0];
+        assert_eq!(entry.name, "Ace");
+        assert!(!entry.won);
+        assert_eq!(entry.enemies_defeated, 1);
+
+        // Only once per run, and a new run keeps the table
+        game.handle_input(KeyEvent::from(KeyCode::Esc));
+        assert_eq!(game.mode, GameMode::GameOver);
+        game.handle_input(KeyEvent::from(KeyCode::Enter));
+        assert_eq!(game.mode, GameMode::GameOver);
+        game.handle_input(KeyEvent::from(KeyCode::Char('n')));
+        assert_eq!(game.mode, GameMode::CharacterSelect(0));
+        game.handle_input(KeyEvent::from(KeyCode::Enter));
+        assert_eq!(game.mode, GameMode::Exploring);
+        assert_eq!(game.high_scores.entries.len(), 1);
+    }
+
+    #[test]
+    fn test_locks_need_the_right_tool() {
+        let mut game = GameState::new(60, 20);
+        let idx = game
+            .locks
+            .iter()
+            .position(|l| l.kind == LockKind::Padlock)
+            .unwrap();
+        let pos = game.locks[idx].position;
+        assert!(!game.is_walkable(pos));
+
+        // Bare hands get you nowhere
+        game.try_lock(idx);
+        assert!(!game.locks[idx].open);
+
+        game.player.inventory.add_tool(Tool::BoltCutters);
+        for _ in 0..50 {
+            if game.locks[idx].open {
+                break;
+            }
+            game.try_lock(idx);
+        }
+        assert!(game.locks[idx].open);
+        assert!(game.is_walkable(pos));
+        assert!(game.ground_items.iter().any(|g| g.position == pos));
+    }
+
+    #[test]
+    fn test_carried_crowbar_spares_the_weapon() {
+        let mut game = GameState::new(60, 20);
+        let idx = game
+            .locks
+            .iter()
+            .position(|l| l.kind == LockKind::Padlock)
+            .unwrap();
+        game.player.inventory.weapon = Weapon::named("Baseball Bat");
+        game.player.inventory.add_tool(Tool::Crowbar);
+        let durability = game
+            .player
+            .inventory
+            .weapon
+            .as_ref()
+            .unwrap()
+            .durability
+            .current;
+
+        game.try_lock(idx);
+        let weapon = game.player.inventory.weapon.as_ref().unwrap();
+        assert_eq!(weapon.durability.current, durability);
+
+        // Prying with the equipped one wears it down
+        game.player.inventory.remove_tool(Tool::Crowbar);
+        game.player.inventory.weapon = Weapon::named("Crowbar");
+        game.locks[idx].open = false;
+        game.try_lock(idx);
+        let weapon = game.player.inventory.weapon.as_ref().unwrap();
+        assert_eq!(weapon.durability.current, weapon.durability.max - 1);
+    }
+
+    #[test]
+    fn test_infection_drains_until_bandaged() {
+        let mut game = GameState::new(60, 20);
+        game.suffer_hazard(Hazard::Infection(8));
+        let health = game.player.health;
+
+        game.pass_turn(false);
+        assert_eq!(game.player.health, health - 1);
+        assert_eq!(game.player.infection, 7);
+
+        game.player.inventory.consumable = Consumable::named("Bandages");
+        game.use_consumable();
+        assert_eq!(game.player.infection, 0);
+    }
+
+    #[test]
+    fn test_escorted_npc_follows_player() {
+        let mut game = GameState::new(60, 20);
+        game.quests.start("sarge_escort");
+
+        let old_pos = game.player.position;
+        game.move_followers(old_pos);
+        let sarge = game.npcs.iter().find(|n| n.name == "Sarge").unwrap();
+        assert_eq!(sarge.position, old_pos);
+    }
+
+    #[test]
+    fn test_running_costs_stamina() {
+        let mut game = GameState::new(60, 20);
+        game.enemies.clear();
+        let start = game.player.position;
+
+        game.handle_input(KeyEvent::from(KeyCode::Char('W')));
+        assert_eq!(game.player.position, Position::new(start.x, start.y - 2));
+        assert_eq!(game.player.survival.stamina, MAX_STAMINA - RUN_COST);
+
+        // Too winded to run: you only walk
+        game.player.survival.stamina = 0;
+        game.handle_input(KeyEvent::from(KeyCode::Char('W')));
+        assert_eq!(game.player.position, Position::new(start.x, start.y - 3));
+    }
+
+    #[test]
+    fn test_starving_hurts() {
+        let mut game = GameState::new(60, 20);
+        game.player.survival.hunger = 0;
+        game.pass_turn(false);
+        assert_eq!(game.player.health, 99);
+    }
+
+    #[test]
+    fn test_trash_time_refills_dumpsters() {
+        let mut game = GameState::new(60, 20);
+        game.dumpsters[0].has_items = false;
+
+        // 22:00 is closing time; someone walks the trash out
+        game.turn_count = 29;
+        game.pass_turn(false);
+        assert_eq!(game.clock().hour, 22);
+        assert!(game.trash_run.is_some());
+        assert!(!game.dumpsters[0].has_items);
+
+        for _ in 0..30 {
+            game.pass_turn(false);
+        }
+        assert!(game.trash_run.is_none());
+        assert!(game.dumpsters[0].has_items);
+    }
+
+    #[test]
+    fn test_alley_never_goes_quiet() {
+        let mut game = GameState::new(60, 20);
+        game.enemies.clear();
+
+        for _ in 0..game.spawns.config.wave_interval * 5 {
+            game.player.survival.hunger = MAX_HUNGER;
+            game.pass_turn(false);
+        }
+        let threat: u32 = game.enemies.iter().map(|e| e.enemy_type.threat()).sum();
+        assert!(!game.enemies.is_empty());
+        assert!(threat <= game.spawns.config.budget);
+    }
+
+    #[test]
+    fn test_noise_alerts_enemies_in_earshot() {
+        let mut game = GameState::new(60, 20);
+        let pos = game.player.position;
+        game.enemies = vec![Enemy::new_rat(pos.x + 7, pos.y)];
+
+        game.make_noise(NoiseSource::Walking);
+        game.move_enemies();
+        assert_eq!(game.enemies[0].awareness, Awareness::Unaware);
+
+        game.make_noise(NoiseSource::Running);
+        game.move_enemies();
+        assert!(matches!(
+            game.enemies[0].awareness,
+            Awareness::Suspicious { .. }
+        ));
+    }
+
+    #[test]
+    fn test_alley_still_listens_after_the_exit_opens() {
+        let mut game = GameState::new(60, 20);
+        let pos = game.player.position;
+        game.enemies = vec![Enemy::new_rat(pos.x + 7, pos.y)];
+        game.mode = GameMode::LevelComplete;
+
+        game.handle_input(KeyEvent::from(KeyCode::Char('z')));
+        assert!(game.player.sneaking);
+        game.handle_input(KeyEvent::from(KeyCode::Char('z')));
+        game.handle_input(KeyEvent::from(KeyCode::Char('W')));
+        assert_ne!(game.enemies[0].awareness, Awareness::Unaware);
+    }
+
+    #[test]
+    fn test_sneak_attack_on_unaware_enemy() {
+        let mut game = GameState::new(60, 20);
+        let pos = game.player.position;
+        game.enemies = vec![Enemy::new_rival_punk(pos.x, pos.y)];
+        game.check_enemy_encounters();
+        assert_eq!(game.mode, GameMode::Combat(0));
+
+        game.handle_input(KeyEvent::from(KeyCode::Char(' ')));
+        assert!(
+            game.messages
+                .filtered(Some(MessageKind::Combat))
+                .iter()
+                .any(|m| m.text == "Sneak attack!")
+        );
+        assert!(matches!(
+            game.enemies[0].awareness,
+            Awareness::Hunting { .. }
+        ));
+    }
+
+    #[test]
+    fn test_message_log_keeps_the_fight_going() {
+        let mut game = GameState::new(60, 20);
+        for turn in 0..30 {
+            game.add_message(MessageKind::System, format!("Line {}", turn));
+        }
+        let pos = game.player.position;
+        game.enemies = vec![Enemy::new_rival_punk(pos.x, pos.y)];
+        game.check_enemy_encounters();
+
+        game.handle_input(KeyEvent::from(KeyCode::Char('l')));
+        assert!(matches!(game.mode, GameMode::MessageLog { .. }));
+        // Scroll up past the HUD's few lines, then filter to the fight
+        game.handle_input(KeyEvent::from(KeyCode::Home));
+        game.handle_input(KeyEvent::from(KeyCode::Tab));
+        let GameMode::MessageLog { scroll, filter, .. } = game.mode else {
+            panic!("left the log");
+        };
+        assert_eq!((scroll, filter), (0, Some(MessageKind::Combat)));
+        assert!(game.messages.filtered(None).len() > 30);
+
+        game.handle_input(KeyEvent::from(KeyCode::Esc));
+        assert_eq!(game.mode, GameMode::Combat(0));
+    }
+
+    #[test]
+    fn test_help_leaves_the_fight_alone() {
+        let mut game = GameState::new(60, 20);
+        let pos = game.player.position;
+        game.enemies = vec![Enemy::new_rival_punk(pos.x, pos.y)];
+        game.check_enemy_encounters();
+        let health = game.enemies[0].health;
+
+        game.scene = Scene::Help;
+        // Attack keys don't reach the fight while help is up
+        game.handle_scene_input(KeyEvent::from(KeyCode::Char(' ')));
+        assert_eq!(game.scene, Scene::Help);
+        game.handle_scene_input(KeyEvent::from(KeyCode::Char('?')));
+        assert_eq!(game.scene, Scene::Game);
+        assert_eq!(game.mode, GameMode::Combat(0));
+        assert_eq!(game.enemies[0].health, health);
+    }
+
+    #[test]
+    fn test_crafting_costs_a_turn() {
+        let mut game = GameState::new(60, 20);
+        game.enemies.clear();
+        game.player.survival.hunger = 0;
+        game.player.health = 1;
+        game.player.inventory.add_material(MaterialKind::Bottle);
+        game.player.inventory.add_material(MaterialKind::Cloth);
+        let shank = RECIPES
+            .iter()
+            .position(|recipe| recipe.product.name() == "Glass Shank")
+            .unwrap();
+
+        game.mode = GameMode::Crafting(shank);
+        game.handle_input(KeyEvent::from(KeyCode::Enter));
+        assert_eq!(game.turn_count, 1);
+        // The clock doesn't move for free
+        assert_eq!(game.mode, GameMode::GameOver);
+    }
+
+    #[test]
+    fn test_night_limits_sight() {
+        let mut game = GameState::new(60, 20);
+        let far = Position::new(game.player.position.x - 10, game.player.position.y);
+        assert!(game.is_visible(far));
+
+        game.turn_count = 20;
+        assert_eq!(game.clock().phase(), Phase::Night);
+        assert!(!game.is_visible(far));
+        assert!(game.is_visible(game.player.position));
+    }
+
     #[test]
     fn test_dumpster_creation() {
         let dumpster = Dumpster::new(10, 10);
@@ -233,97 +1083,371 @@ mod tests {
         assert_eq!(dumpster.position.y, 10);
         assert!(dumpster.has_items);
     }
+
+    #[test]
+    fn test_dumpster_kinds_use_their_loot_table() {
+        for _ in 0..20 {
+            let dumpster = Dumpster::of_kind(
+                &mut rand::thread_rng(),
+                DumpsterKind::MedicalWaste,
+                3,
+                3,
+                ItemPool::default(),
+                100,
+            );
+            assert!(dumpster.item_weapon.is_none());
+            if let Some(consumable) = &dumpster.item_consumable {
+                let pool = DumpsterKind::MedicalWaste.loot().consumables;
+                assert!(pool.contains(&consumable.name.as_str()));
+            }
+
+            let mut dumpster = Dumpster::of_kind(
+                &mut rand::thread_rng(),
+                DumpsterKind::Recycling,
+                3,
+                3,
+                ItemPool::default(),
+                100,
+            );
+            assert!(dumpster.materials.len() >= 2);
+            dumpster.restock(&mut rand::thread_rng());
+            assert_eq!(dumpster.kind, DumpsterKind::Recycling);
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct Dumpster {
     pub position: Position,
+    pub kind: DumpsterKind,
     pub has_items: bool,
     pub item_weapon: Option<Weapon>,
     pub item_armor: Option<Armor>,
     pub item_consumable: Option<Consumable>,
-    pub has_bolt_cutters: bool,
+    pub materials: Vec<MaterialKind>,
+    pub cash: u32,
+    /// A lock-opening tool stashed in here, if any.
+    pub tool: Option<Tool>,
+    /// The item tables it restocks from.
+    pub pool: ItemPool,
+    /// Drop chances in percent of the usual, from the difficulty.
+    pub loot_percent: u32,
+    /// Whether the player has scavenged it yet this run. Restocking
+    /// doesn't reset it.
+    pub searched: bool,
 }
 
 impl Dumpster {
+    #[cfg(test)]
     pub fn new(x: i32, y: i32) -> Self {
-        let mut rng = rand::thread_rng();
+        Self::of_kind(
+            &mut rand::thread_rng(),
+            DumpsterKind::Regular,
+            x,
+            y,
+            ItemPool::default(),
+            100,
+        )
+    }
+
+    /// Fills a dumpster from its kind's loot table, drawing gear from `pool`
+    /// with drop chances scaled to `loot_percent`.
+    pub fn of_kind(
+        rng: &mut impl Rng,
+        kind: DumpsterKind,
+        x: i32,
+        y: i32,
+        pool: ItemPool,
+        loot_percent: u32,
+    ) -> Self {
+        let loot = kind.loot();
+        let chance = |chance: f64| (chance * loot_percent as f64 / 100.0).min(1.0);
 
         // Generate random items for this dumpster
-        let has_weapon = rng.gen_bool(0.7);
-        let has_armor = rng.gen_bool(0.7);
-        let has_consumable = rng.gen_bool(0.8);
+        let has_weapon = rng.gen_bool(chance(loot.weapon_chance));
+        let has_armor = rng.gen_bool(chance(loot.armor_chance));
+        let has_consumable = rng.gen_bool(chance(loot.consumable_chance));
+        let material_count = rng.gen_range(loot.material_count.0..=loot.material_count.1);
+        let cash = if rng.gen_bool(chance(loot.cash_chance)) {
+            rng.gen_range(loot.cash.0..=loot.cash.1)
+        } else {
+            0
+        };
 
         Dumpster {
             position: Position::new(x, y),
+            kind,
             has_items: true,
             item_weapon: if has_weapon {
-                Some(Weapon::random_generate())
+                Some(Weapon::random_from(rng, pool))
             } else {
                 None
             },
             item_armor: if has_armor {
-                Some(Armor::random_generate())
+                Some(Armor::random_from(rng, pool))
             } else {
                 None
             },
-            item_consumable: if has_consumable {
-                Some(Consumable::random_generate())
-            } else {
+            item_consumable: if !has_consumable {
                 None
+            } else if loot.consumables.is_empty() {
+                Some(Consumable::random(rng))
+            } else {
+                Consumable::named(loot.consumables[rng.gen_range(0..loot.consumables.len())])
             },
-            has_bolt_cutters: false,
+            materials: (0..material_count)
+                .map(|_| {
+                    if loot.materials.is_empty() {
+                        MaterialKind::random(rng)
+                    } else {
+                        loot.materials[rng.gen_range(0..loot.materials.len())]
+                    }
+                })
+                .collect(),
+            cash,
+            tool: None,
+            pool,
+            loot_percent,
+            searched: false,
         }
     }
 
-    pub fn new_with_bolt_cutters(x: i32, y: i32) -> Self {
-        let mut dumpster = Self::new(x, y);
-        dumpster.has_bolt_cutters = true;
-        dumpster
+    /// Fills an emptied dumpster with a fresh roll of trash. The bolt
+    /// cutters only ever turn up once.
+    pub fn restock(&mut self, rng: &mut impl Rng) {
+        *self = Self {
+            searched: self.searched,
+            ..Self::of_kind(
+                rng,
+                self.kind,
+                self.position.x,
+                self.position.y,
+                self.pool,
+                self.loot_percent,
+            )
+        };
     }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum GameMode {
     Exploring,
     Combat(usize), // index of enemy in combat
     Victory,
     GameOver,
-    LevelComplete, // New state when level is complete but chain not cut yet
+    LevelComplete,   // New state when level is complete but chain not cut yet
+    Crafting(usize), // index of the selected recipe
+    LevelUp(usize),  // index of the highlighted perk
+    Shop {
+        selling: bool,
+        selected: usize,
+    },
+    Dialogue {
+        npc: usize,
+        node: usize,
+        selected: usize,
+    },
+    QuestLog,
+    Title(usize), // index of the highlighted menu option
+    NameEntry,
+    HighScores {
+        from_title: bool,
+    },
+    Achievements {
+        from_title: bool,
+    },
+    Unlocks(usize),          // index of the highlighted unlock
+    CharacterSelect(usize),  // index of the highlighted background
+    CustomDifficulty(usize), // index of the highlighted setting
+    DailyScores {
+        from_title: bool,
+    },
+    MessageLog {
+        scroll: usize, // lines up from the newest message
+        filter: Option<MessageKind>,
+        combat: Option<usize>, // the fight to go back to, if any
+    },
+    Quit,
+}
+
+/// Options on the title menu.
+pub const TITLE_OPTIONS: [&str; 8] = [
+    "New Game",
+    "Continue",
+    "Daily Run",
+    "Unlocks",
+    "High Scores",
+    "Achievements",
+    "Settings",
+    "Quit",
+];
+
+impl GameMode {
+    /// Out in the alley, where Esc pauses and Q asks before leaving.
+    pub fn in_run(&self) -> bool {
+        matches!(
+            self,
+            GameMode::Exploring | GameMode::Combat(_) | GameMode::LevelComplete
+        )
+    }
+
+    /// Whether keys pressed here change how the run plays out. Logs and
+    /// menus only look at it, so saves leave them out.
+    pub fn plays_out(&self) -> bool {
+        self.in_run()
+            || matches!(
+                self,
+                GameMode::Crafting(_)
+                    | GameMode::LevelUp(_)
+                    | GameMode::Shop { .. }
+                    | GameMode::Dialogue { .. }
+            )
+    }
+
+    /// Full-screen menus that handle their own Q/Esc instead of quitting the game.
+    pub fn is_menu(&self) -> bool {
+        matches!(
+            self,
+            GameMode::Crafting(_)
+                | GameMode::LevelUp(_)
+                | GameMode::Shop { .. }
+                | GameMode::Dialogue { .. }
+                | GameMode::QuestLog
+                | GameMode::NameEntry
+                | GameMode::HighScores { .. }
+                | GameMode::Achievements { .. }
+                | GameMode::Unlocks(_)
+                | GameMode::CharacterSelect(_)
+                | GameMode::CustomDifficulty(_)
+                | GameMode::DailyScores { .. }
+                | GameMode::MessageLog { .. }
+        )
+    }
 }
 
 pub struct GameState {
     pub player: Player,
     pub enemies: Vec<Enemy>,
     pub dumpsters: Vec<Dumpster>,
+    pub ground_items: Vec<GroundItem>,
+    pub vendor: Vendor,
+    pub npcs: Vec<Npc>,
+    /// How the alley's regulars feel about you. Opens up dialogue options.
+    pub reputation: i32,
+    /// Things that have happened in conversations, e.g. `dale_fed`.
+    pub flags: HashSet<String>,
+    pub quests: QuestLog,
+    /// Where the burger place staff come and go.
+    pub back_door: Position,
+    /// A staff member out restocking the dumpsters, if one is.
+    pub trash_run: Option<TrashRun>,
+    /// Sends in reinforcements over time and when things get loud.
+    pub spawns: SpawnDirector,
+    /// Noises made since enemies last got to react.
+    pub noises: Vec<Noise>,
     pub width: i32,
     pub height: i32,
     pub mode: GameMode,
-    pub messages: Vec<String>,
+    /// Whether a pause menu or the like is on top of `mode`.
+    pub scene: Scene,
+    pub messages: MessageLog,
     pub turn_count: u32,
-    pub chain_position: Position, // Position of the locked chain
+    /// Gates, doors and fences, including the level's exit.
+    pub locks: Vec<Lock>,
+    pub seed: u64,
+    pub rng: StdRng,
+    /// How this run began and every key pressed since, for saving.
+    pub save: SaveGame,
+    /// Set while a saved run's keys are pressed again. The run plays out
+    /// as before, but nothing outside it (achievements, the profile, the
+    /// morgue) hears about it twice.
+    pub replaying: bool,
+    /// How many levels deep the run is. The alley is the first.
+    pub depth: u32,
+    pub difficulty: Difficulty,
+    pub rules: DifficultySettings,
+    pub stats: RunStats,
+    /// Carried over from run to run.
+    pub high_scores: HighScores,
+    pub daily_scores: DailyScores,
+    /// Set when this run is on the day's seed.
+    pub daily: Option<DailyRun>,
+    pub profile: Profile,
+    /// The name being typed in for the high score table.
+    pub name_entry: String,
+    /// Whether this run has gone on the high score table yet.
+    pub score_recorded: bool,
+    /// Made it out of the level alive.
+    pub escaped: bool,
+    /// Where files that outlive a run are written. None (as in tests)
+    /// keeps everything in memory.
+    pub data_dir: Option<PathBuf>,
+    /// The morgue file written when the run ended, or why it couldn't be.
+    pub morgue: Option<Result<PathBuf, String>>,
+    /// Cred banked in the profile when the run ended.
+    pub cred_earned: Option<u32>,
+    /// A one-line note for the menus outside the alley, like why an unlock
+    /// couldn't be bought.
+    pub notice: Option<String>,
 }
 
 impl GameState {
     pub fn new(width: i32, height: i32) -> Self {
-        let mut rng = rand::thread_rng();
+        Self::with_seed(width, height, rand::thread_rng().r#gen())
+    }
+
+    /// Creates a game whose layout and combat rolls are driven by `seed`.
+    pub fn with_seed(width: i32, height: i32, seed: u64) -> Self {
+        Self::with_setup(width, height, seed, &RunSetup::default())
+    }
+
+    /// Creates a game with whatever `setup` has unlocked and picked.
+    pub fn with_setup(width: i32, height: i32, seed: u64, setup: &RunSetup) -> Self {
+        // The same seed always lays out the same alley
+        let mut rng = StdRng::seed_from_u64(seed);
 
         // Player starts near the bottom center
-        let player = Player::new(width / 2, height - 3);
+        let mut player = Player::new(width / 2, height - 3);
+        if let Some(background) = setup.background {
+            player.set_background(background);
+            let _ = player.inventory.try_add(background.starting_item());
+        }
+        player.max_health += setup.bonus_health;
+        player.health = player.max_health;
+        for item in &setup.starting_items {
+            let _ = player.inventory.try_add(item.clone());
+        }
 
         // Create dumpsters in the top area (behind the burger place)
-        let mut dumpsters = Vec::new();
-        for i in 0..2 {
-            dumpsters.push(Dumpster::new(width / 4 + i * (width / 3), 3));
+        // plus a couple further down the alley
+        let spots = [
+            (width / 4, 3),
+            (width / 4 + width / 3, 3),
+            (width * 3 / 4, 3),
+            (6, height / 2),
+            (width - 7, height / 2),
+        ];
+        let kinds: Vec<DumpsterKind> = spots
+            .iter()
+            .map(|_| DumpsterKind::random(&mut rng))
+            .collect();
+        let mut dumpsters: Vec<Dumpster> = spots
+            .iter()
+            .zip(&kinds)
+            .map(|(&(x, y), &kind)| {
+                Dumpster::of_kind(&mut rng, kind, x, y, setup.pool, setup.rules.loot)
+            })
+            .collect();
+        // Some kinds are likelier to hide the bolt cutters
+        if let Some(cutters) = DumpsterKind::pick_cutters_dumpster(&mut rng, &kinds) {
+            dumpsters[cutters].tool = Some(Tool::BoltCutters);
         }
-        // Last dumpster has bolt cutters
-        dumpsters.push(Dumpster::new_with_bolt_cutters(width * 3 / 4, 3));
 
         // Create enemies scattered around
         let mut enemies = Vec::new();
 
         // Add some rats
-        for _ in 0..3 {
+        for _ in 0..setup.rules.enemy_count(3) {
             let x = rng.gen_range(5..width - 5);
             let y = rng.gen_range(5..height - 5);
             enemies.push(Enemy::new_rat(x, y));
@@ -334,83 +1458,405 @@ impl GameState {
         let y = rng.gen_range(5..height / 2);
         enemies.push(Enemy::new_feral_cat(x, y));
 
-        // Add a rival punk near the dumpsters
+        // Add a rival punk near the dumpsters, and more in the rougher alleys
         enemies.push(Enemy::new_rival_punk(width / 2 + 5, 5));
-
-        // Chain is at the exit (top center)
-        let chain_position = Position::new(width / 2, 1);
+        for i in 0..setup.extra_punks as i32 {
+            enemies.push(Enemy::new_rival_punk(width / 4 + 8 * i, height / 2 + 1));
+        }
+        let enemies: Vec<Enemy> = enemies
+            .into_iter()
+            .map(|enemy| enemy.toughened(&setup.rules))
+            .collect();
+
+        // The exit is a chained gate (top center). Kevin keeps a locker out
+        // back, and someone's fenced off a corner of the lot
+        let locks = vec![
+            Lock::new("Chained Gate", LockKind::Chain, width / 2, 1).exit(),
+            Lock::new("Kevin's Locker", LockKind::Padlock, width / 2 + 6, 1).containing(vec![
+                Item::Consumable(Consumable::named("Painkillers").unwrap()),
+                Item::Cash(12),
+            ]),
+            Lock::new("Fenced Lot", LockKind::Fence, width - 4, height - 3).containing(vec![
+                Item::Weapon(Weapon::random_from(&mut rng, setup.pool)),
+            ]),
+        ];
+        let back_door = Position::new(width / 2 - 4, 2);
+
+        // Sewer grates in the floor and gaps in the side walls
+        let spawns = SpawnDirector::new(
+            vec![
+                SpawnPoint::new(SpawnPointKind::SewerGrate, width / 3, height - 5),
+                SpawnPoint::new(SpawnPointKind::SewerGrate, width * 2 / 3, height / 2 + 2),
+                SpawnPoint::new(SpawnPointKind::AlleyMouth, 0, height - 4),
+                SpawnPoint::new(SpawnPointKind::AlleyMouth, width - 1, height / 2 - 2),
+            ],
+            SpawnConfig::alley().scaled(setup.threat_percent * setup.rules.enemy_count / 100),
+        );
+
+        let mut messages = MessageLog::default();
+        for welcome in [
+            "Welcome to RUST PUNK!",
+            "Fight rats and rivals for dumpster scavenge rights!",
+            "Use WASD to move, Space to attack, ? for help",
+            "Find a way through the chained gate! (J = quest log)",
+        ] {
+            messages.push(MessageKind::System, welcome.to_string(), 0);
+        }
 
         GameState {
             player,
             enemies,
             dumpsters,
+            ground_items: Vec::new(),
+            vendor: Vendor::new(&mut rng, width / 2 - 8, 2),
+            npcs: vec![
+                Npc::fry_cook(back_door.x, back_door.y),
+                Npc::veteran(4, height - 4),
+            ],
+            reputation: 0,
+            flags: HashSet::new(),
+            quests: QuestLog::new(alley_quests(back_door)),
+            back_door,
+            trash_run: None,
+            spawns,
+            noises: Vec::new(),
             width,
             height,
             mode: GameMode::Exploring,
-            messages: vec![
-                "Welcome to RUST PUNK!".to_string(),
-                "Fight rats and rivals for dumpster scavenge rights!".to_string(),
-                "Use WASD to move, Space to attack, Q to quit".to_string(),
-                "Find bolt cutters to unlock the exit!".to_string(),
-            ],
+            scene: Scene::Game,
+            messages,
             turn_count: 0,
-            chain_position,
+            locks,
+            seed,
+            rng,
+            save: SaveGame {
+                seed,
+                ..Default::default()
+            },
+            replaying: false,
+            depth: setup.depth,
+            difficulty: setup.difficulty,
+            rules: setup.rules,
+            stats: RunStats::default(),
+            high_scores: HighScores::default(),
+            daily_scores: DailyScores::default(),
+            daily: None,
+            profile: Profile::default(),
+            name_entry: String::new(),
+            score_recorded: false,
+            escaped: false,
+            data_dir: None,
+            morgue: None,
+            cred_earned: None,
+            notice: None,
+        }
+    }
+
+    /// Throws away this run and starts a fresh one as `background`, keeping
+    /// the high scores.
+    pub fn new_run(&mut self, background: Background) {
+        let seed = rand::thread_rng().r#gen();
+        let mut setup = RunSetup::from_profile(&self.profile);
+        setup.background = Some(background);
+        self.begin_run(seed, &setup);
+        self.save = SaveGame::new(seed, &self.profile, Some(background));
+    }
+
+    /// Starts a run on today's seed. Everyone gets a plain punk on Normal
+    /// with nothing unlocked, so the only difference is how you play it.
+    pub fn start_daily_run(&mut self) {
+        let date = daily::date(SystemTime::now());
+        let scored = self.profile.start_daily(&date);
+        let seed = daily::seed(&date);
+        self.begin_run(seed, &RunSetup::default());
+        self.daily = Some(DailyRun { date, scored });
+        self.save = SaveGame {
+            seed,
+            daily: self.daily.clone(),
+            ..Default::default()
+        };
+        if !scored {
+            self.add_message(
+                MessageKind::System,
+                "Practice run: today's score is already in.".to_string(),
+            );
+        }
+    }
+
+    /// Picks the saved run back up by starting it over and pressing all the
+    /// same keys.
+    pub fn continue_run(&mut self) {
+        if !self.has_save() {
+            self.notice = Some("No saved run to continue.".to_string());
+            return;
+        }
+        let Some(save) = self.save_path().and_then(|path| SaveGame::read(&path)) else {
+            self.notice = Some("That save is from another version of the game.".to_string());
+            return;
+        };
+        self.notice = None;
+        self.begin_run(save.seed, &save.setup());
+        self.daily = save.daily.clone();
+        self.save = SaveGame {
+            keys: Vec::new(),
+            ..save.clone()
+        };
+        self.replaying = true;
+        for code in save.keys {
+            self.handle_input(KeyEvent::from(code));
         }
+        self.replaying = false;
+        self.add_message(
+            MessageKind::System,
+            "Picked up where you left off.".to_string(),
+        );
+    }
+
+    fn save_path(&self) -> Option<PathBuf> {
+        self.data_dir.as_ref().map(|dir| dir.join("save.txt"))
+    }
+
+    /// Whether there's a saved run to continue.
+    pub fn has_save(&self) -> bool {
+        self.save_path().is_some_and(|path| path.exists())
+    }
+
+    /// Writes the run to the save file, over whatever was there.
+    fn save_run(&mut self) {
+        let Some(path) = self.save_path() else {
+            self.notice = Some("Nowhere to save to.".to_string());
+            return;
+        };
+        self.notice = Some(match self.save.write(&path) {
+            Ok(()) => "Saved.".to_string(),
+            Err(err) => format!("Couldn't save: {}", err),
+        });
     }
 
-    pub fn add_message(&mut self, message: String) {
-        self.messages.push(message);
-        // Keep only last 5 messages
-        if self.messages.len() > 5 {
-            self.messages.remove(0);
+    /// Swaps in a fresh alley, keeping everything that lives between runs.
+    fn begin_run(&mut self, seed: u64, setup: &RunSetup) {
+        let high_scores = std::mem::take(&mut self.high_scores);
+        let daily_scores = std::mem::take(&mut self.daily_scores);
+        let profile = std::mem::take(&mut self.profile);
+        let data_dir = self.data_dir.take();
+        *self = GameState::with_setup(self.width, self.height, seed, setup);
+        self.high_scores = high_scores;
+        self.daily_scores = daily_scores;
+        self.profile = profile;
+        self.data_dir = data_dir;
+    }
+
+    /// Whether this run can still go on a score table.
+    pub fn can_record_score(&self) -> bool {
+        !self.score_recorded && self.daily.as_ref().is_none_or(|daily| daily.scored)
+    }
+
+    /// What this run is worth so far.
+    pub fn run_result(&self) -> RunResult {
+        RunResult {
+            won: self.escaped,
+            turns: self.turn_count,
+            hp_left: self.player.health,
+            enemies_defeated: self.stats.enemies_defeated(),
+            rarity_points: self.stats.rarity_points,
+            depth: self.depth,
+            difficulty: self.difficulty,
+            score_percent: self.rules.score_percent(),
         }
     }
 
+    /// Adds to the run's message log. The HUD shows the latest few and L
+    /// opens the whole thing.
+    pub fn add_message(&mut self, kind: MessageKind, message: String) {
+        self.messages.push(kind, message, self.turn_count);
+    }
+
     pub fn handle_input(&mut self, key: KeyEvent) {
+        let played_out = self.mode.plays_out();
         match self.mode {
-            GameMode::Exploring => self.handle_exploring_input(key),
+            GameMode::Exploring | GameMode::LevelComplete => self.handle_exploring_input(key),
             GameMode::Combat(enemy_idx) => self.handle_combat_input(key, enemy_idx),
-            GameMode::LevelComplete => self.handle_level_complete_input(key),
+            GameMode::Crafting(selected) => self.handle_crafting_input(key, selected),
+            GameMode::LevelUp(selected) => self.handle_level_up_input(key, selected),
+            GameMode::Shop { selling, selected } => self.handle_shop_input(key, selling, selected),
+            GameMode::Dialogue {
+                npc,
+                node,
+                selected,
+            } => self.handle_dialogue_input(key, npc, node, selected),
+            GameMode::QuestLog => self.handle_quest_log_input(key),
+            GameMode::Victory | GameMode::GameOver => self.handle_end_screen_input(key),
+            GameMode::Title(selected) => self.handle_title_input(key, selected),
+            GameMode::Unlocks(selected) => self.handle_unlocks_input(key, selected),
+            GameMode::CharacterSelect(selected) => {
+                self.handle_character_select_input(key, selected)
+            }
+            GameMode::CustomDifficulty(selected) => {
+                self.handle_custom_difficulty_input(key, selected)
+            }
+            GameMode::NameEntry => self.handle_name_entry_input(key),
+            GameMode::HighScores { from_title } => self.handle_high_scores_input(key, from_title),
+            GameMode::DailyScores { from_title } => self.handle_daily_scores_input(key, from_title),
+            GameMode::MessageLog {
+                scroll,
+                filter,
+                combat,
+            } => self.handle_message_log_input(key, scroll, filter, combat),
+            GameMode::Achievements { from_title } => {
+                self.handle_achievements_input(key, from_title)
+            }
             _ => {}
         }
+
+        if self.mode == GameMode::GameOver && !self.rules.permadeath {
+            self.get_back_up();
+        }
+        // Opening and closing a log doesn't need saving, and nor does
+        // anything done in it
+        if played_out && self.mode.plays_out() {
+            self.save.keys.push(key.code);
+        }
+        if matches!(self.mode, GameMode::Victory | GameMode::GameOver) && self.cred_earned.is_none()
+        {
+            self.finish_run();
+        }
     }
 
-    fn handle_exploring_input(&mut self, key: KeyEvent) {
-        let old_pos = self.player.position;
-        let mut new_pos = old_pos;
+    /// Without permadeath a beating isn't the end: you come to at the alley
+    /// entrance with half your health, and someone's been through your pockets.
+    fn get_back_up(&mut self) {
+        self.player.health = (self.player.max_health / 2).max(1);
+        self.player.infection = 0;
+        self.player.position = Position::new(self.width / 2, self.height - 3);
+        self.player.inventory.cash = 0;
+        self.stats.cause_of_death = None;
+        self.stats.knockdowns += 1;
+        self.mode = GameMode::Exploring;
+        self.add_message(
+            MessageKind::Combat,
+            "You come to at the alley entrance. Your caps are gone.".to_string(),
+        );
+    }
 
-        match key.code {
-            KeyCode::Char('w') | KeyCode::Up => new_pos.y -= 1,
-            KeyCode::Char('s') | KeyCode::Down => new_pos.y += 1,
-            KeyCode::Char('a') | KeyCode::Left => new_pos.x -= 1,
-            KeyCode::Char('d') | KeyCode::Right => new_pos.x += 1,
-            KeyCode::Char('e') => {
-                // Use consumable
-                self.use_consumable();
-                self.turn_count += 1;
-                self.player.inventory.update_turn();
-                return;
-            }
-            _ => return,
+    /// Banks the run in the profile and writes it up, once, as it ends.
+    fn finish_run(&mut self) {
+        if self.replaying {
+            return;
         }
+        let run = self.run_result();
+        self.cred_earned = Some(self.profile.record_run(run.won, run.score()));
+        self.write_morgue();
 
-        // Boundary check
-        if new_pos.x >= 1
-            && new_pos.x < self.width - 1
-            && new_pos.y >= 1
-            && new_pos.y < self.height - 1
+        // A run that's over can't be continued
+        if let Some(path) = self.save_path()
+            && SaveGame::read(&path).is_some_and(|save| save.seed == self.seed)
         {
-            self.player.position = new_pos;
-            self.turn_count += 1;
+            let _ = fs::remove_file(path);
+        }
+    }
 
-            // Update inventory turn effects
-            self.player.inventory.update_turn();
+    /// Saves the run summary to a timestamped file in the morgue folder.
+    fn write_morgue(&mut self) {
+        let Some(dir) = &self.data_dir else {
+            return;
+        };
+        let now = SystemTime::now();
+        let text = morgue::morgue_text(self, now);
+        let written = morgue::write_morgue(&dir.join("morgue"), &text, now);
+        self.morgue = Some(written.map_err(|err| err.to_string()));
+    }
 
-            // Check for dumpster interaction
-            self.check_dumpster_scavenge();
+    /// The mode to drop back into after closing a menu.
+    fn exploration_mode(&self) -> GameMode {
+        if self.quests.main_complete() {
+            GameMode::LevelComplete
+        } else {
+            GameMode::Exploring
+        }
+    }
 
-            // Check for chain interaction
-            self.check_chain_interaction();
+    /// Getting around the alley, before and after the exit opens.
+    fn handle_exploring_input(&mut self, key: KeyEvent) {
+        let old_pos = self.player.position;
+
+        let (dx, dy, running) = match keymap::action(key.code) {
+            Some(Action::Walk(dx, dy)) => (dx, dy, false),
+            Some(Action::Run(dx, dy)) => (dx, dy, true),
+            Some(Action::UseItem) => {
+                self.use_consumable();
+                self.pass_turn(false);
+                return;
+            }
+            Some(Action::Repair) => {
+                // Patch up gear with scrap
+                self.repair_gear();
+                self.pass_turn(false);
+                return;
+            }
+            Some(Action::Craft) => {
+                self.mode = GameMode::Crafting(0);
+                return;
+            }
+            Some(Action::QuestLog) => {
+                self.mode = GameMode::QuestLog;
+                return;
+            }
+            Some(Action::Achievements) => {
+                self.mode = GameMode::Achievements { from_title: false };
+                return;
+            }
+            Some(Action::MessageLog) => {
+                self.open_message_log(None);
+                return;
+            }
+            Some(Action::PickUp) => {
+                self.swap_ground_item();
+                self.pass_turn(false);
+                return;
+            }
+            Some(Action::Sneak) => {
+                self.toggle_sneak();
+                return;
+            }
+            _ => return,
+        };
+
+        let first_step = Position::new(old_pos.x + dx, old_pos.y + dy);
+        if self.bump(first_step) || !self.is_walkable(first_step) {
+            return;
+        }
+
+        let running = running && self.start_run();
+        let new_pos = self.destination(dx, dy, if running { 2 } else { 1 });
+        if new_pos != old_pos {
+            self.player.position = new_pos;
+            self.pass_turn(running);
+            if !self.player.is_alive() {
+                return;
+            }
+
+            // Anyone you're escorting follows in your footsteps
+            self.move_followers(Position::new(new_pos.x - dx, new_pos.y - dy));
+
+            self.make_noise(if running {
+                NoiseSource::Running
+            } else if self.player.sneaking {
+                NoiseSource::Sneaking
+            } else {
+                NoiseSource::Walking
+            });
+
+            // Check for dumpster interaction
+            self.check_dumpster_scavenge();
+            if !self.player.is_alive() {
+                return;
+            }
+
+            // Pick up anything lying around
+            self.check_ground_items();
+
+            // Check whether you've made it out
+            self.check_exit_interaction();
 
             // Check for enemy encounters
             self.check_enemy_encounters();
@@ -421,44 +1867,13 @@ impl GameState {
     }
 
     fn handle_combat_input(&mut self, key: KeyEvent, enemy_idx: usize) {
-        match key.code {
-            KeyCode::Char(' ') | KeyCode::Enter => {
-                if enemy_idx < self.enemies.len() && self.enemies[enemy_idx].is_alive {
-                    let damage = self.player.total_attack();
-
-                    // Take damage first
-                    let enemy = &mut self.enemies[enemy_idx];
-                    enemy.take_damage(damage);
-                    let enemy_type = enemy.enemy_type.clone();
-                    let enemy_is_alive = enemy.is_alive;
-                    let enemy_damage = enemy.attack;
-
-                    // Add message about player attack
-                    self.add_message(format!("You attack {} for {} damage!", enemy_type, damage));
-
-                    if !enemy_is_alive {
-                        self.add_message(format!("{} defeated!", enemy_type));
-                        self.mode = GameMode::Exploring;
-                    } else {
-                        // Enemy counterattacks
-                        let defense_bonus = self.player.inventory.total_defense_bonus();
-                        self.player.take_damage(enemy_damage);
-
-                        let actual_damage = (enemy_damage - defense_bonus).max(1);
-                        self.add_message(format!(
-                            "{} attacks you for {} damage! (Reduced by {})",
-                            enemy_type, actual_damage, defense_bonus
-                        ));
-
-                        if !self.player.is_alive() {
-                            self.mode = GameMode::GameOver;
-                            self.add_message("You have been defeated!".to_string());
-                        }
-                    }
+        match keymap::action(key.code) {
+            Some(Action::Attack) => self.combat_round(enemy_idx),
+            Some(Action::Retreat) => {
+                self.add_message(MessageKind::Combat, "You retreat from combat!".to_string());
+                if !self.player.background.is_some_and(|b| b.free_retreat()) {
+                    self.player.survival.tire(RUN_COST);
                 }
-            }
-            KeyCode::Char('r') => {
-                self.add_message("You retreat from combat!".to_string());
                 // Move player away from enemy
                 let enemy = &self.enemies[enemy_idx];
                 let dx = self.player.position.x - enemy.position.x;
@@ -470,15 +1885,242 @@ impl GameState {
                 self.player.position = Position::new(new_x, new_y);
                 self.mode = GameMode::Exploring;
             }
-            KeyCode::Char('e') => {
+            Some(Action::UseItem) => {
                 // Use consumable during combat
                 self.use_consumable();
                 self.player.inventory.update_turn();
             }
+            Some(Action::MessageLog) => self.open_message_log(Some(enemy_idx)),
             _ => {}
         }
     }
 
+    fn combat_round(&mut self, enemy_idx: usize) {
+        if enemy_idx >= self.enemies.len() || !self.enemies[enemy_idx].is_alive {
+            return;
+        }
+
+        // Player swings first, and hard if the enemy never saw it coming
+        let mut attack = self.player.attack_profile();
+        if self.enemies[enemy_idx].awareness == Awareness::Unaware {
+            attack.accuracy += SNEAK_ATTACK_ACCURACY;
+            attack.min_damage *= 2;
+            attack.max_damage *= 2;
+            self.add_message(MessageKind::Combat, "Sneak attack!".to_string());
+        }
+        self.enemies[enemy_idx].awareness = Awareness::Hunting { turns_unsensed: 0 };
+        self.player.survival.tire(ATTACK_COST);
+        self.make_noise(NoiseSource::Fighting);
+        let defense = self.enemies[enemy_idx].defense_profile();
+        let result = resolve_attack(&mut self.rng, &attack, &defense);
+
+        let enemy = &mut self.enemies[enemy_idx];
+        let before = enemy.health;
+        enemy.take_damage(result.damage);
+        let dealt = before - enemy.health;
+        let enemy_type = enemy.enemy_type;
+        let enemy_is_alive = enemy.is_alive;
+        self.stats.dealt_damage(dealt);
+
+        let verb = match result.kind {
+            HitKind::Miss => "You miss",
+            HitKind::Glancing => "You graze",
+            HitKind::Hit => "You hit",
+            HitKind::Critical => "CRITICAL! You smash",
+        };
+        if result.is_hit() {
+            self.add_message(
+                MessageKind::Combat,
+                format!(
+                    "{} {} for {} ({})",
+                    verb,
+                    enemy_type,
+                    result.damage,
+                    result.breakdown()
+                ),
+            );
+
+            // Every hit takes a toll on the weapon
+            if let Some(broken) = self.player.inventory.wear_weapon() {
+                self.add_message(MessageKind::Combat, format!("Your {} breaks!", broken));
+            }
+        } else {
+            self.add_message(
+                MessageKind::Combat,
+                format!("{} {} ({})", verb, enemy_type, result.breakdown()),
+            );
+        }
+
+        if !enemy_is_alive {
+            let xp = enemy_type.xp_reward();
+            self.add_message(
+                MessageKind::Combat,
+                format!("{} defeated! (+{} XP)", enemy_type, xp),
+            );
+            self.mode = self.exploration_mode();
+            self.record_event(GameEvent::EnemyDefeated(enemy_type));
+            self.stats.defeated_enemy(enemy_type);
+            self.drop_loot(enemy_idx);
+            self.check_ground_items();
+            self.award_xp(xp);
+            return;
+        }
+
+        // Enemy counterattacks
+        let attack = self.enemies[enemy_idx].attack_profile();
+        let defense = self.player.defense_profile();
+        let result = resolve_attack(&mut self.rng, &attack, &defense);
+
+        if !result.is_hit() {
+            self.add_message(
+                MessageKind::Combat,
+                format!("{} misses you! ({})", enemy_type, result.breakdown()),
+            );
+            return;
+        }
+
+        self.hurt_player(result.damage, DeathCause::Killed(enemy_type));
+        let verb = match result.kind {
+            HitKind::Critical => "CRITS",
+            HitKind::Glancing => "grazes",
+            _ => "hits",
+        };
+        self.add_message(
+            MessageKind::Combat,
+            format!(
+                "{} {} you for {} ({})",
+                enemy_type,
+                verb,
+                result.damage,
+                result.breakdown()
+            ),
+        );
+
+        if let Some(broken) = self.player.inventory.wear_armor() {
+            self.add_message(MessageKind::Combat, format!("Your {} falls apart!", broken));
+        }
+
+        if !self.player.is_alive() {
+            self.mode = GameMode::GameOver;
+            self.add_message(MessageKind::Combat, "You have been defeated!".to_string());
+        }
+    }
+
+    fn drop_loot(&mut self, enemy_idx: usize) {
+        let enemy = &self.enemies[enemy_idx];
+        let position = enemy.position;
+        let enemy_type = enemy.enemy_type;
+
+        // Stingier difficulties sometimes leave them with empty pockets,
+        // and generous ones give a second look
+        let mut drop = None;
+        let mut luck = self.rules.loot as f64 / 100.0;
+        while drop.is_none() && luck > 0.0 {
+            if self.rng.gen_bool(luck.min(1.0)) {
+                drop = roll_loot(&mut self.rng, &enemy_type);
+            }
+            luck -= 1.0;
+        }
+        if let Some(item) = drop {
+            self.add_message(
+                MessageKind::Loot,
+                format!("The {} dropped {}!", enemy_type, item.name()),
+            );
+            self.ground_items.push(GroundItem { position, item });
+        }
+    }
+
+    /// Picks up anything underfoot that fits in an empty slot.
+    fn check_ground_items(&mut self) {
+        let player_pos = self.player.position;
+        let mut idx = 0;
+
+        while idx < self.ground_items.len() {
+            if self.ground_items[idx].position != player_pos {
+                idx += 1;
+                continue;
+            }
+
+            let ground = self.ground_items.remove(idx);
+            let description = ground.item.describe();
+            let found = ground.item.clone();
+            match self.player.inventory.try_add(ground.item) {
+                Ok(()) => {
+                    self.found_item(&found);
+                    self.add_message(MessageKind::Loot, format!("Picked up {}!", description));
+                }
+                Err(item) => {
+                    self.add_message(
+                        MessageKind::Loot,
+                        format!("{} is here. (G to swap)", item.name()),
+                    );
+                    self.ground_items.insert(
+                        idx,
+                        GroundItem {
+                            position: player_pos,
+                            item,
+                        },
+                    );
+                    idx += 1;
+                }
+            }
+        }
+    }
+
+    /// Counts something the player just picked up, for the stats and any
+    /// quest after it.
+    fn found_item(&mut self, item: &Item) {
+        self.stats.found_item(item);
+        self.record_event(GameEvent::ItemFound(item.name()));
+    }
+
+    /// Swaps the first item underfoot with whatever is in its slot.
+    fn swap_ground_item(&mut self) {
+        let player_pos = self.player.position;
+        let Some(idx) = self
+            .ground_items
+            .iter()
+            .position(|g| g.position == player_pos)
+        else {
+            self.add_message(MessageKind::Loot, "Nothing here to pick up.".to_string());
+            return;
+        };
+
+        let ground = self.ground_items.remove(idx);
+        let description = ground.item.describe();
+        let found = ground.item.clone();
+        match self.player.inventory.swap(ground.item) {
+            None => {
+                self.found_item(&found);
+                self.add_message(MessageKind::Loot, format!("Picked up {}!", description));
+            }
+            Some(Item::Material(kind)) => {
+                self.add_message(
+                    MessageKind::Loot,
+                    "Your pockets are full of junk!".to_string(),
+                );
+                self.ground_items.insert(
+                    idx,
+                    GroundItem {
+                        position: player_pos,
+                        item: Item::Material(kind),
+                    },
+                );
+            }
+            Some(old) => {
+                self.found_item(&found);
+                self.add_message(
+                    MessageKind::Loot,
+                    format!("Dropped {}, picked up {}", old.name(), description),
+                );
+                self.ground_items.push(GroundItem {
+                    position: player_pos,
+                    item: old,
+                });
+            }
+        }
+    }
+
     fn try_pickup_weapon(
         dumpster_slot: &mut Option<Weapon>,
         inventory_slot: &mut Option<Weapon>,
@@ -548,11 +2190,40 @@ impl GameState {
         }
     }
 
+    fn try_pickup_materials(
+        dumpster_materials: &mut Vec<MaterialKind>,
+        inventory: &mut Inventory,
+        found_items: &mut Vec<String>,
+    ) -> bool {
+        let mut picked_up = Vec::new();
+        let mut left_behind = Vec::new();
+        for kind in dumpster_materials.drain(..) {
+            if inventory.add_material(kind) {
+                picked_up.push(kind.to_string());
+            } else {
+                left_behind.push(kind);
+            }
+        }
+
+        if !picked_up.is_empty() {
+            found_items.push(format!("Found junk: {}", picked_up.join(", ")));
+        }
+        if !left_behind.is_empty() {
+            found_items.push("Your pockets are full of junk!".to_string());
+        }
+        *dumpster_materials = left_behind;
+
+        !picked_up.is_empty()
+    }
+
     fn check_dumpster_scavenge(&mut self) {
         let player_pos = self.player.position;
         let mut scavenged = false;
         let mut found_items = Vec::new();
         let mut items_picked_up = false;
+        let mut kind = DumpsterKind::Regular;
+        let mut hazard = None;
+        let mut found = Vec::new();
 
         for dumpster in &mut self.dumpsters {
             if dumpster.position.x == player_pos.x
@@ -560,12 +2231,25 @@ impl GameState {
                 && dumpster.has_items
             {
                 scavenged = true;
+                kind = dumpster.kind;
+
+                // Reaching in is a gamble with some bins
+                let loot = kind.loot();
+                let chance = self
+                    .player
+                    .background
+                    .map_or(loot.hazard_chance, |b| b.hazard_chance(loot.hazard_chance));
+                if self.rng.gen_bool(chance) {
+                    hazard = loot.hazard;
+                }
 
-                // Check for bolt cutters first
-                if dumpster.has_bolt_cutters {
-                    self.player.inventory.bolt_cutters.found = true;
-                    found_items.push("Bolt Cutters! (You can now cut the chain!)".to_string());
-                    dumpster.has_bolt_cutters = false;
+                // Check for tools first
+                if let Some(tool) = dumpster.tool
+                    && self.player.inventory.add_tool(tool)
+                {
+                    found_items.push(format!("{}! (Walk into a lock to use them)", tool));
+                    found.push(Item::Tool(tool));
+                    dumpster.tool = None;
                     items_picked_up = true;
                 }
 
@@ -575,6 +2259,7 @@ impl GameState {
                     &mut self.player.inventory.weapon,
                     &mut found_items,
                 ) {
+                    found.extend(self.player.inventory.weapon.clone().map(Item::Weapon));
                     items_picked_up = true;
                 }
 
@@ -584,6 +2269,7 @@ impl GameState {
                     &mut self.player.inventory.armor,
                     &mut found_items,
                 ) {
+                    found.extend(self.player.inventory.armor.clone().map(Item::Armor));
                     items_picked_up = true;
                 }
 
@@ -592,16 +2278,44 @@ impl GameState {
                     &mut dumpster.item_consumable,
                     &mut self.player.inventory.consumable,
                     &mut found_items,
+                ) {
+                    found.extend(
+                        self.player
+                            .inventory
+                            .consumable
+                            .clone()
+                            .map(Item::Consumable),
+                    );
+                    items_picked_up = true;
+                }
+
+                // Pocket whatever junk fits
+                if Self::try_pickup_materials(
+                    &mut dumpster.materials,
+                    &mut self.player.inventory,
+                    &mut found_items,
                 ) {
                     items_picked_up = true;
                 }
 
+                // Caps always fit
+                if dumpster.cash > 0 {
+                    found_items.push(format!("Found {} caps!", dumpster.cash));
+                    self.player.inventory.cash += dumpster.cash;
+                    dumpster.cash = 0;
+                    items_picked_up = true;
+                }
+
                 // Only mark as scavenged if at least one item was picked up or no items remain
                 let has_remaining_items = dumpster.item_weapon.is_some()
                     || dumpster.item_armor.is_some()
                     || dumpster.item_consumable.is_some()
-                    || dumpster.has_bolt_cutters;
+                    || !dumpster.materials.is_empty()
+                    || dumpster.tool.is_some();
 
+                if items_picked_up {
+                    dumpster.searched = true;
+                }
                 if items_picked_up || !has_remaining_items {
                     dumpster.has_items = false;
                 }
@@ -611,33 +2325,110 @@ impl GameState {
         }
 
         if scavenged && items_picked_up {
+            self.make_noise(NoiseSource::Rummaging);
             self.player.scavenged_items += 1;
-            self.add_message(format!(
-                "Scavenged dumpster! ({}/3)",
-                self.player.scavenged_items
-            ));
+            self.add_message(
+                MessageKind::Loot,
+                format!(
+                    "Scavenged the {}! ({} so far)",
+                    kind, self.player.scavenged_items
+                ),
+            );
 
             // Heal player a bit
-            self.player.heal(20);
-            self.add_message("Found some food! Health restored.".to_string());
+            let eye = self.player.progression.perk_rank(Perk::ScavengersEye);
+            self.player
+                .heal(self.rules.healing(self.player.healing(20 + 10 * eye)));
+            self.player.survival.eat(15, 0);
+            self.add_message(
+                MessageKind::Loot,
+                "Found some food! Health restored.".to_string(),
+            );
+
+            // A trained eye spots extra junk others would miss
+            let bonus = self.player.background.map_or(0, |b| b.bonus_junk());
+            for _ in 0..eye + bonus {
+                let kind = MaterialKind::random(&mut self.rng);
+                if self.player.inventory.add_material(kind) {
+                    self.add_message(
+                        MessageKind::Loot,
+                        format!("Your scavenger's eye spots {}!", kind),
+                    );
+                }
+            }
 
             // Add all found items messages
             for item_msg in found_items {
-                self.add_message(item_msg);
+                self.add_message(MessageKind::Loot, item_msg);
+            }
+
+            if let Some(hazard) = hazard {
+                self.suffer_hazard(hazard);
+                self.make_noise(NoiseSource::Hazard);
             }
 
-            // Check if all dumpsters are scavenged (ready for exit)
-            if self.player.scavenged_items >= 3 {
-                self.mode = GameMode::LevelComplete;
-                self.add_message("All dumpsters scavenged! Find the exit.".to_string());
+            for item in &found {
+                self.found_item(item);
             }
+            self.record_event(GameEvent::DumpsterScavenged);
         } else if scavenged && !items_picked_up {
             // Player found a dumpster but inventory was full
-            self.add_message("Your inventory is full! Drop items to pick up more.".to_string());
+            self.add_message(
+                MessageKind::Loot,
+                "Your inventory is full! Drop items to pick up more.".to_string(),
+            );
             for item_msg in found_items {
-                self.add_message(item_msg);
+                self.add_message(MessageKind::Loot, item_msg);
+            }
+        }
+    }
+
+    /// Something in the bin bit back.
+    fn suffer_hazard(&mut self, hazard: Hazard) {
+        match hazard {
+            Hazard::Burn(damage)
+            | Hazard::Cut(damage)
+            | Hazard::Crush(damage)
+            | Hazard::Shock(damage) => {
+                let before = self.player.health;
+                self.player.take_damage(damage);
+                let lost = before - self.player.health;
+                self.stats.took_damage(lost);
+                if self.player.is_alive() {
+                    self.record_event(GameEvent::Hurt);
+                }
+                self.add_message(
+                    MessageKind::Combat,
+                    format!("{}! (-{} HP)", hazard.describe(), lost),
+                );
+            }
+            Hazard::Infection(turns) => {
+                self.player.infection = self.player.infection.max(turns);
+                self.add_message(MessageKind::Combat, format!("{}.", hazard.describe()));
             }
         }
+
+        if !self.player.is_alive() {
+            self.stats.cause_of_death = Some(DeathCause::Hazard(hazard));
+            self.mode = GameMode::GameOver;
+            self.add_message(
+                MessageKind::Combat,
+                "You bled out in a dumpster.".to_string(),
+            );
+        }
+    }
+
+    /// Takes health off the player, keeping count, and notes what did it if
+    /// that was the end of them.
+    fn hurt_player(&mut self, amount: i32, cause: DeathCause) {
+        let before = self.player.health;
+        self.player.lose_health(amount);
+        self.stats.took_damage(before - self.player.health);
+        if self.player.is_alive() {
+            self.record_event(GameEvent::Hurt);
+        } else if self.stats.cause_of_death.is_none() {
+            self.stats.cause_of_death = Some(cause);
+        }
     }
 
     fn check_enemy_encounters(&mut self) {
@@ -647,10 +2438,23 @@ impl GameState {
                 && enemy.position.y == self.player.position.y
             {
                 self.mode = GameMode::Combat(idx);
-                self.add_message(format!(
-                    "Encountered {}! Space to attack, R to retreat.",
-                    enemy.enemy_type
-                ));
+                if enemy.awareness == Awareness::Unaware {
+                    self.add_message(
+                        MessageKind::Combat,
+                        format!(
+                            "You catch the {} off guard! Space to sneak attack, R to back off.",
+                            enemy.enemy_type
+                        ),
+                    );
+                } else {
+                    self.add_message(
+                        MessageKind::Combat,
+                        format!(
+                            "Encountered {}! Space to attack, R to retreat.",
+                            enemy.enemy_type
+                        ),
+                    );
+                }
                 break;
             }
         }
@@ -658,94 +2462,1319 @@ impl GameState {
 
     fn move_enemies(&mut self) {
         let player_pos = self.player.position;
+        let phase = self.clock().phase();
+
+        let sight = spot_range(phase.aggro_range(), self.player.sneaking);
+        let noises = std::mem::take(&mut self.noises);
 
         for enemy in &mut self.enemies {
             if !enemy.is_alive {
                 continue;
             }
 
-            // Simple AI: move towards player if within range
+            // Sharper eyes at night, and sharp ears any time
             let distance = enemy.position.distance_to(&player_pos);
-            if distance < 10.0 && distance > 1.0 {
-                let dx = (player_pos.x - enemy.position.x).signum();
-                let dy = (player_pos.y - enemy.position.y).signum();
+            let heard = noises
+                .iter()
+                .find(|n| enemy.position.distance_to(&n.position) <= n.radius)
+                .map(|n| n.position);
+            enemy.awareness = enemy.awareness.update(distance <= sight, heard, player_pos);
+
+            // Hunters close in on you, the suspicious go and check out the noise
+            let (target, chance) = match enemy.awareness {
+                Awareness::Unaware => continue,
+                Awareness::Suspicious { noise, .. } => (noise, phase.move_chance() / 2.0),
+                Awareness::Hunting { .. } if distance > 1.0 => (player_pos, phase.move_chance()),
+                Awareness::Hunting { .. } => continue,
+            };
+            if enemy.position == target {
+                continue;
+            }
+            let dx = (target.x - enemy.position.x).signum();
+            let dy = (target.y - enemy.position.y).signum();
 
-                // 50% chance to move each turn
-                let mut rng = rand::thread_rng();
-                if rng.gen_bool(0.5) {
-                    enemy.position.x = (enemy.position.x + dx).clamp(1, self.width - 2);
-                    enemy.position.y = (enemy.position.y + dy).clamp(1, self.height - 2);
-                }
+            if self.rng.gen_bool(chance) {
+                enemy.position.x = (enemy.position.x + dx).clamp(1, self.width - 2);
+                enemy.position.y = (enemy.position.y + dy).clamp(1, self.height - 2);
             }
         }
     }
 
-    fn check_chain_interaction(&mut self) {
-        if self.player.position.x == self.chain_position.x
-            && self.player.position.y == self.chain_position.y
-            && self.mode == GameMode::LevelComplete
-        {
-            if self.player.inventory.bolt_cutters.found {
+    /// Lets every enemy in earshot react to what the player just did.
+    fn make_noise(&mut self, source: NoiseSource) {
+        self.noises.push(Noise {
+            position: self.player.position,
+            radius: source.radius(),
+        });
+        if source.carries() {
+            self.spawns.hear(self.player.position);
+        }
+    }
+
+    fn toggle_sneak(&mut self) {
+        self.player.sneaking = !self.player.sneaking;
+        if self.player.sneaking {
+            self.add_message(
+                MessageKind::System,
+                "You keep low and move quietly.".to_string(),
+            );
+        } else {
+            self.add_message(MessageKind::System, "You stand up straight.".to_string());
+        }
+    }
+
+    fn check_exit_interaction(&mut self) {
+        let at_exit = self
+            .locks
+            .iter()
+            .any(|l| l.exit && l.open && l.position == self.player.position);
+        if at_exit {
+            if self.quests.main_complete() {
                 self.mode = GameMode::Victory;
-                self.add_message("You cut the chain and escape! Victory!".to_string());
+                self.escaped = true;
+                self.record_event(GameEvent::Escaped);
+                self.add_message(
+                    MessageKind::System,
+                    "You slip through the gate and escape! Victory!".to_string(),
+                );
             } else {
-                self.add_message("The exit is locked with a chain. Need bolt cutters!".to_string());
+                self.add_message(
+                    MessageKind::System,
+                    format!(
+                        "The way out's open, but you're not done here. Still to do: {}",
+                        self.quests.remaining_main().join(", ")
+                    ),
+                );
             }
         }
     }
 
-    fn handle_level_complete_input(&mut self, key: KeyEvent) {
-        // In level complete mode, player can still move around
-        let old_pos = self.player.position;
-        let mut new_pos = old_pos;
+    fn handle_crafting_input(&mut self, key: KeyEvent, selected: usize) {
+        match key.code {
+            KeyCode::Char('w') | KeyCode::Up => {
+                self.mode = GameMode::Crafting(selected.saturating_sub(1));
+            }
+            KeyCode::Char('s') | KeyCode::Down => {
+                self.mode = GameMode::Crafting((selected + 1).min(RECIPES.len() - 1));
+            }
+            KeyCode::Char(' ') | KeyCode::Enter => {
+                let recipe = &RECIPES[selected];
+                match recipe.craft(&mut self.player.inventory) {
+                    Ok(()) => {
+                        self.add_message(
+                            MessageKind::Loot,
+                            format!("Crafted {}!", recipe.product.name()),
+                        );
+                        self.record_event(GameEvent::Crafted(recipe.product.name().to_string()));
+                        self.pass_turn(false);
+                    }
+                    Err(err) => {
+                        self.add_message(
+                            MessageKind::Loot,
+                            format!("Can't craft {}: {}", recipe.product.name(), err),
+                        );
+                    }
+                }
+            }
+            KeyCode::Char('c') | KeyCode::Char('q') | KeyCode::Esc => {
+                self.mode = self.exploration_mode();
+            }
+            _ => {}
+        }
+    }
+
+    fn award_xp(&mut self, xp: u32) {
+        if self.player.gain_xp(xp) > 0 {
+            self.add_message(
+                MessageKind::System,
+                format!(
+                    "Level up! You are now level {}. Choose a perk.",
+                    self.player.progression.level
+                ),
+            );
+            self.mode = GameMode::LevelUp(0);
+        }
+    }
 
+    fn handle_level_up_input(&mut self, key: KeyEvent, selected: usize) {
         match key.code {
-            KeyCode::Char('w') | KeyCode::Up => new_pos.y -= 1,
-            KeyCode::Char('s') | KeyCode::Down => new_pos.y += 1,
-            KeyCode::Char('a') | KeyCode::Left => new_pos.x -= 1,
-            KeyCode::Char('d') | KeyCode::Right => new_pos.x += 1,
-            KeyCode::Char('e') => {
-                self.use_consumable();
+            KeyCode::Char('w') | KeyCode::Up => {
+                self.mode = GameMode::LevelUp(selected.saturating_sub(1));
+            }
+            KeyCode::Char('s') | KeyCode::Down => {
+                self.mode = GameMode::LevelUp((selected + 1).min(Perk::ALL.len() - 1));
+            }
+            KeyCode::Char(' ') | KeyCode::Enter => {
+                let perk = Perk::ALL[selected];
+                if self.player.progression.choose_perk(perk) {
+                    self.add_message(
+                        MessageKind::System,
+                        format!(
+                            "You picked {} (rank {})",
+                            perk,
+                            self.player.progression.perk_rank(perk)
+                        ),
+                    );
+                }
+                if self.player.progression.pending_perks == 0 {
+                    self.mode = self.exploration_mode();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn open_shop(&mut self) {
+        self.add_message(
+            MessageKind::System,
+            "Vendor: \"Psst. Got junk? I got caps.\"".to_string(),
+        );
+        self.mode = GameMode::Shop {
+            selling: false,
+            selected: 0,
+        };
+    }
+
+    fn handle_shop_input(&mut self, key: KeyEvent, selling: bool, selected: usize) {
+        let entries = if selling {
+            self.player.inventory.filled_slots().len()
+        } else {
+            self.vendor.stock.len()
+        };
+
+        match key.code {
+            KeyCode::Char('w') | KeyCode::Up => {
+                self.mode = GameMode::Shop {
+                    selling,
+                    selected: selected.saturating_sub(1),
+                };
+            }
+            KeyCode::Char('s') | KeyCode::Down => {
+                self.mode = GameMode::Shop {
+                    selling,
+                    selected: (selected + 1).min(entries.saturating_sub(1)),
+                };
+            }
+            KeyCode::Tab
+            | KeyCode::Char('a')
+            | KeyCode::Char('d')
+            | KeyCode::Left
+            | KeyCode::Right => {
+                self.mode = GameMode::Shop {
+                    selling: !selling,
+                    selected: 0,
+                };
+            }
+            KeyCode::Char(' ') | KeyCode::Enter => {
+                let result = if selling {
+                    match self.player.inventory.filled_slots().get(selected) {
+                        Some(slot) => self.vendor.sell(*slot, &mut self.player.inventory),
+                        None => Err(TradeError::NothingThere),
+                    }
+                } else {
+                    self.vendor.buy(selected, &mut self.player.inventory)
+                };
+
+                match result {
+                    Ok((name, price)) if selling => {
+                        self.add_message(
+                            MessageKind::Loot,
+                            format!("Sold {} for {} caps", name, price),
+                        );
+                    }
+                    Ok((name, price)) => {
+                        self.add_message(
+                            MessageKind::Loot,
+                            format!("Bought {} for {} caps", name, price),
+                        );
+                    }
+                    Err(err) => self.add_message(MessageKind::System, err.to_string()),
+                }
+
+                // Keep the cursor on the list as it shrinks
+                let remaining = if selling {
+                    self.player.inventory.filled_slots().len()
+                } else {
+                    self.vendor.stock.len()
+                };
+                self.mode = GameMode::Shop {
+                    selling,
+                    selected: selected.min(remaining.saturating_sub(1)),
+                };
+            }
+            KeyCode::Char('q') | KeyCode::Esc => {
+                self.add_message(
+                    MessageKind::System,
+                    "Vendor: \"Come back when you're rich.\"".to_string(),
+                );
+                self.mode = self.exploration_mode();
+            }
+            _ => {}
+        }
+    }
+
+    fn start_dialogue(&mut self, npc_idx: usize) {
+        self.mode = GameMode::Dialogue {
+            npc: npc_idx,
+            node: 0,
+            selected: 0,
+        };
+        self.enter_dialogue_node(npc_idx, 0);
+    }
+
+    /// Moves the conversation to `node` and runs its effects.
+    fn enter_dialogue_node(&mut self, npc_idx: usize, node: usize) {
+        self.mode = GameMode::Dialogue {
+            npc: npc_idx,
+            node,
+            selected: 0,
+        };
+        let effects = self.npcs[npc_idx].dialogue.nodes[node].effects.clone();
+        if self.apply_dialogue_effects(npc_idx, &effects) {
+            self.npc_turns_hostile(npc_idx);
+        }
+    }
+
+    fn handle_dialogue_input(
+        &mut self,
+        key: KeyEvent,
+        npc_idx: usize,
+        node: usize,
+        selected: usize,
+    ) {
+        let options: Vec<usize> = self.npcs[npc_idx]
+            .visible_options(node, &self.player.inventory, self.reputation, &self.flags)
+            .into_iter()
+            .map(|(idx, _)| idx)
+            .collect();
+
+        let choice = match key.code {
+            KeyCode::Char('w') | KeyCode::Up => {
+                self.mode = GameMode::Dialogue {
+                    npc: npc_idx,
+                    node,
+                    selected: selected.saturating_sub(1),
+                };
+                return;
+            }
+            KeyCode::Char('s') | KeyCode::Down => {
+                self.mode = GameMode::Dialogue {
+                    npc: npc_idx,
+                    node,
+                    selected: (selected + 1).min(options.len().saturating_sub(1)),
+                };
+                return;
+            }
+            KeyCode::Char(' ') | KeyCode::Enter => selected,
+            KeyCode::Char(c @ '1'..='9') => c as usize - '1' as usize,
+            KeyCode::Char('q') | KeyCode::Esc => {
+                self.mode = self.exploration_mode();
+                return;
+            }
+            _ => return,
+        };
+
+        let Some(&option_idx) = options.get(choice) else {
+            return;
+        };
+        let option = self.npcs[npc_idx].dialogue.nodes[node].options[option_idx].clone();
+
+        if self.apply_dialogue_effects(npc_idx, &option.effects) {
+            self.npc_turns_hostile(npc_idx);
+            return;
+        }
+        match option.target {
+            Some(next) => self.enter_dialogue_node(npc_idx, next),
+            None => self.mode = self.exploration_mode(),
+        }
+    }
+
+    /// Runs dialogue effects in order. Returns true if one of them starts a fight.
+    fn apply_dialogue_effects(&mut self, npc_idx: usize, effects: &[DialogueEffect]) -> bool {
+        let name = self.npcs[npc_idx].name.clone();
+        let mut fight = false;
+
+        for effect in effects {
+            match effect {
+                DialogueEffect::Give(spec) => {
+                    let Some(item) = spec.create() else {
+                        continue;
+                    };
+                    let item_name = item.name();
+                    match self.player.inventory.try_add(item) {
+                        Ok(()) => self.add_message(
+                            MessageKind::Loot,
+                            format!("{} gives you {}", name, item_name),
+                        ),
+                        Err(item) => {
+                            self.ground_items.push(GroundItem {
+                                position: self.player.position,
+                                item,
+                            });
+                            self.add_message(MessageKind::Loot, format!(
+                                "{} gives you {}, but your hands are full. It's at your feet (G to swap)",
+                                name, item_name
+                            ));
+                        }
+                    }
+                }
+                DialogueEffect::TakeCash(amount) => {
+                    let paid = (*amount).min(self.player.inventory.cash);
+                    self.player.inventory.cash -= paid;
+                    self.add_message(
+                        MessageKind::Loot,
+                        format!("You hand {} {} caps", name, paid),
+                    );
+                }
+                DialogueEffect::Take(slot) => {
+                    if let Some(item) = self.player.inventory.take(*slot) {
+                        self.add_message(
+                            MessageKind::Loot,
+                            format!("You hand {} your {}", name, item.name()),
+                        );
+                    }
+                }
+                DialogueEffect::Reputation(change) => {
+                    self.reputation += change;
+                    self.add_message(
+                        MessageKind::System,
+                        format!("Reputation {:+} (now {})", change, self.reputation),
+                    );
+                }
+                DialogueEffect::SetFlag(flag) => {
+                    self.flags.insert(flag.clone());
+                }
+                DialogueEffect::StartQuest(id) => {
+                    if let Some(idx) = self.quests.start(id) {
+                        self.add_message(
+                            MessageKind::System,
+                            format!(
+                                "New quest: {} (J for quest log)",
+                                self.quests.quests[idx].title
+                            ),
+                        );
+                    }
+                }
+                DialogueEffect::Fight => fight = true,
+            }
+        }
+
+        fight
+    }
+
+    /// The NPC stops talking and starts swinging.
+    fn npc_turns_hostile(&mut self, npc_idx: usize) {
+        let npc = self.npcs.remove(npc_idx);
+        self.record_event(GameEvent::NpcLost(npc.name.clone()));
+        self.enemies.push(npc.turn_hostile().toughened(&self.rules));
+        self.mode = GameMode::Combat(self.enemies.len() - 1);
+        self.add_message(
+            MessageKind::Combat,
+            format!("{} wants a fight! Space to attack, R to retreat.", npc.name),
+        );
+    }
+
+    fn open_message_log(&mut self, combat: Option<usize>) {
+        self.mode = GameMode::MessageLog {
+            scroll: 0,
+            filter: None,
+            combat,
+        };
+    }
+
+    fn handle_message_log_input(
+        &mut self,
+        key: KeyEvent,
+        scroll: usize,
+        filter: Option<MessageKind>,
+        combat: Option<usize>,
+    ) {
+        let max_scroll = self.messages.max_scroll(filter);
+        let (scroll, filter) = match key.code {
+            KeyCode::Up | KeyCode::Char('w') => ((scroll + 1).min(max_scroll), filter),
+            KeyCode::Down | KeyCode::Char('s') => (scroll.saturating_sub(1), filter),
+            KeyCode::PageUp => ((scroll + LOG_PAGE).min(max_scroll), filter),
+            KeyCode::PageDown => (scroll.saturating_sub(LOG_PAGE), filter),
+            KeyCode::Home => (max_scroll, filter),
+            KeyCode::End => (0, filter),
+            KeyCode::Tab | KeyCode::Char('f') => (0, MessageKind::next_filter(filter)),
+            KeyCode::Char('l') | KeyCode::Char('q') | KeyCode::Esc => {
+                self.mode = match combat {
+                    Some(enemy_idx) => GameMode::Combat(enemy_idx),
+                    None => self.exploration_mode(),
+                };
+                return;
+            }
+            _ => return,
+        };
+        self.mode = GameMode::MessageLog {
+            scroll,
+            filter,
+            combat,
+        };
+    }
+
+    fn handle_quest_log_input(&mut self, key: KeyEvent) {
+        if let KeyCode::Char('j') | KeyCode::Char('q') | KeyCode::Esc = key.code {
+            self.mode = self.exploration_mode();
+        }
+    }
+
+    /// The end screens wait on the player to record the run, look at the
+    /// table or go again. Q is handled by the main loop.
+    fn handle_end_screen_input(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Enter if self.can_record_score() => {
+                self.name_entry.clear();
+                self.mode = GameMode::NameEntry;
+            }
+            KeyCode::Char('h') if self.daily.is_some() => {
+                self.mode = GameMode::DailyScores { from_title: false }
+            }
+            KeyCode::Char('h') => self.mode = GameMode::HighScores { from_title: false },
+            KeyCode::Char('n') => self.mode = GameMode::CharacterSelect(0),
+            _ => {}
+        }
+    }
+
+    /// Keys for whatever scene is on top of the game.
+    pub fn handle_scene_input(&mut self, key: KeyEvent) {
+        match self.scene {
+            Scene::Game => self.handle_input(key),
+            Scene::Help => {
+                if let KeyCode::Char('?') | KeyCode::Char('q') | KeyCode::Esc | KeyCode::Enter =
+                    key.code
+                {
+                    self.scene = Scene::Game;
+                }
+            }
+            Scene::Paused(selected) => self.handle_pause_input(key, selected),
+            Scene::Settings { selected, .. } => self.handle_settings_input(key, selected),
+            Scene::Confirm { leave, .. } => self.handle_confirm_input(key, leave),
+        }
+    }
+
+    /// Leaves the run, asking first if the settings say to.
+    pub fn ask_to_leave(&mut self, leave: Leave, from_pause: bool) {
+        if self.profile.settings.confirm_quit {
+            self.scene = Scene::Confirm { leave, from_pause };
+        } else {
+            self.leave_run(leave);
+        }
+    }
+
+    /// Saves the run first if autosave is on, then heads for the title
+    /// menu or out of the game.
+    fn leave_run(&mut self, leave: Leave) {
+        if self.profile.settings.autosave {
+            self.save_run();
+        }
+        self.scene = Scene::Game;
+        self.mode = match leave {
+            Leave::ToMenu if self.has_save() => GameMode::Title(1),
+            Leave::ToMenu => GameMode::Title(0),
+            Leave::Quit => GameMode::Quit,
+        };
+    }
+
+    fn handle_pause_input(&mut self, key: KeyEvent, selected: usize) {
+        match key.code {
+            KeyCode::Up | KeyCode::Char('w') => {
+                self.scene = Scene::Paused(selected.saturating_sub(1));
+            }
+            KeyCode::Down | KeyCode::Char('s') => {
+                self.scene = Scene::Paused((selected + 1).min(PAUSE_OPTIONS.len() - 1));
+            }
+            KeyCode::Enter | KeyCode::Char(' ') => match selected {
+                0 => {
+                    self.notice = None;
+                    self.scene = Scene::Game;
+                }
+                1 => self.save_run(),
+                2 => {
+                    self.notice = None;
+                    self.scene = Scene::Settings {
+                        selected: 0,
+                        from_pause: true,
+                    };
+                }
+                _ => {
+                    self.notice = None;
+                    self.ask_to_leave(Leave::ToMenu, true);
+                }
+            },
+            KeyCode::Char('q') | KeyCode::Esc => {
+                self.notice = None;
+                self.scene = Scene::Game;
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_settings_input(&mut self, key: KeyEvent, selected: usize) {
+        let from_pause = matches!(
+            self.scene,
+            Scene::Settings {
+                from_pause: true,
+                ..
+            }
+        );
+        let forward = match key.code {
+            KeyCode::Up | KeyCode::Char('w') => {
+                self.scene = Scene::Settings {
+                    selected: selected.saturating_sub(1),
+                    from_pause,
+                };
+                return;
+            }
+            KeyCode::Down | KeyCode::Char('s') => {
+                self.scene = Scene::Settings {
+                    selected: (selected + 1).min(SETTINGS_ROWS - 1),
+                    from_pause,
+                };
+                return;
+            }
+            KeyCode::Left | KeyCode::Char('a') => false,
+            KeyCode::Right | KeyCode::Char('d') | KeyCode::Enter | KeyCode::Char(' ') => true,
+            KeyCode::Char('q') | KeyCode::Esc => {
+                self.scene = self.scene.back();
                 return;
             }
             _ => return,
+        };
+        match selected {
+            0 => self.cycle_difficulty(forward),
+            idx => self.profile.toggle_setting(idx - 1),
+        }
+    }
+
+    fn handle_confirm_input(&mut self, key: KeyEvent, leave: Leave) {
+        match key.code {
+            KeyCode::Char('y') | KeyCode::Enter => self.leave_run(leave),
+            KeyCode::Char('n') | KeyCode::Char('q') | KeyCode::Esc => {
+                self.scene = self.scene.back();
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_title_input(&mut self, key: KeyEvent, selected: usize) {
+        match key.code {
+            KeyCode::Up | KeyCode::Char('w') => {
+                self.mode = GameMode::Title(selected.saturating_sub(1));
+            }
+            KeyCode::Down | KeyCode::Char('s') => {
+                self.mode = GameMode::Title((selected + 1).min(TITLE_OPTIONS.len() - 1));
+            }
+            KeyCode::Enter | KeyCode::Char(' ') => {
+                self.notice = None;
+                match selected {
+                    0 => self.mode = GameMode::CharacterSelect(0),
+                    1 => self.continue_run(),
+                    2 => self.mode = GameMode::DailyScores { from_title: true },
+                    3 => self.mode = GameMode::Unlocks(0),
+                    4 => self.mode = GameMode::HighScores { from_title: true },
+                    5 => self.mode = GameMode::Achievements { from_title: true },
+                    6 => {
+                        self.scene = Scene::Settings {
+                            selected: 0,
+                            from_pause: false,
+                        }
+                    }
+                    _ => self.mode = GameMode::Quit,
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_unlocks_input(&mut self, key: KeyEvent, selected: usize) {
+        match key.code {
+            KeyCode::Up | KeyCode::Char('w') => {
+                self.mode = GameMode::Unlocks(selected.saturating_sub(1));
+            }
+            KeyCode::Down | KeyCode::Char('s') => {
+                self.mode = GameMode::Unlocks((selected + 1).min(Unlock::ALL.len() - 1));
+            }
+            KeyCode::Enter | KeyCode::Char(' ') => {
+                let unlock = Unlock::ALL[selected];
+                if self.profile.owns(unlock) && unlock.kind() == UnlockKind::Alley {
+                    self.profile.toggle_alley(unlock);
+                    self.notice = None;
+                } else {
+                    self.notice = Some(match self.profile.buy(unlock) {
+                        Ok(()) => format!("Unlocked {}!", unlock.name()),
+                        Err(err) => err.to_string(),
+                    });
+                }
+            }
+            KeyCode::Char('q') | KeyCode::Esc => {
+                self.notice = None;
+                self.mode = GameMode::Title(3);
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_character_select_input(&mut self, key: KeyEvent, selected: usize) {
+        match key.code {
+            KeyCode::Up | KeyCode::Char('w') => {
+                self.mode = GameMode::CharacterSelect(selected.saturating_sub(1));
+            }
+            KeyCode::Down | KeyCode::Char('s') => {
+                self.mode =
+                    GameMode::CharacterSelect((selected + 1).min(Background::ALL.len() - 1));
+            }
+            KeyCode::Enter | KeyCode::Char(' ') => {
+                let background = Background::ALL[selected];
+                match background.required_unlock() {
+                    Some(unlock) if !self.profile.owns(unlock) => {
+                        self.notice = Some(format!("Unlock {} first.", unlock.name()));
+                    }
+                    _ => {
+                        self.notice = None;
+                        self.new_run(background);
+                    }
+                }
+            }
+            KeyCode::Left | KeyCode::Char('a') => self.cycle_difficulty(false),
+            KeyCode::Right | KeyCode::Char('d') => self.cycle_difficulty(true),
+            KeyCode::Char('c') if self.profile.difficulty == Difficulty::Custom => {
+                self.notice = None;
+                self.mode = GameMode::CustomDifficulty(0);
+            }
+            KeyCode::Char('q') | KeyCode::Esc => {
+                self.notice = None;
+                self.mode = GameMode::Title(0);
+            }
+            _ => {}
+        }
+    }
+
+    /// Steps the difficulty for new runs to the next or previous one.
+    fn cycle_difficulty(&mut self, forward: bool) {
+        let step = if forward {
+            1
+        } else {
+            Difficulty::ALL.len() - 1
+        };
+        let idx = Difficulty::ALL
+            .iter()
+            .position(|d| *d == self.profile.difficulty)
+            .unwrap_or(0);
+        self.profile
+            .set_difficulty(Difficulty::ALL[(idx + step) % Difficulty::ALL.len()]);
+    }
+
+    fn handle_custom_difficulty_input(&mut self, key: KeyEvent, selected: usize) {
+        let last = DifficultySettings::LABELS.len() - 1;
+        match key.code {
+            KeyCode::Up | KeyCode::Char('w') => {
+                self.mode = GameMode::CustomDifficulty(selected.saturating_sub(1));
+            }
+            KeyCode::Down | KeyCode::Char('s') => {
+                self.mode = GameMode::CustomDifficulty((selected + 1).min(last));
+            }
+            KeyCode::Left | KeyCode::Char('a') => self.profile.adjust_custom(selected, false),
+            KeyCode::Right | KeyCode::Char('d') | KeyCode::Enter | KeyCode::Char(' ') => {
+                self.profile.adjust_custom(selected, true)
+            }
+            KeyCode::Char('q') | KeyCode::Esc => self.mode = GameMode::CharacterSelect(0),
+            _ => {}
+        }
+    }
+
+    fn handle_name_entry_input(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Enter => {
+                let name = match self.name_entry.trim() {
+                    "" => "Punk",
+                    name => name,
+                };
+                let entry = HighScore::new(name, &self.run_result());
+                self.score_recorded = true;
+                if let Some(daily) = &self.daily {
+                    self.daily_scores.record(&daily.date, entry);
+                    self.mode = GameMode::DailyScores { from_title: false };
+                } else {
+                    self.high_scores.record(entry);
+                    self.mode = GameMode::HighScores { from_title: false };
+                }
+            }
+            KeyCode::Esc => self.mode = self.end_mode(),
+            KeyCode::Backspace => {
+                self.name_entry.pop();
+            }
+            KeyCode::Char(c)
+                if is_name_char(c) && self.name_entry.chars().count() < MAX_NAME_LEN =>
+            {
+                self.name_entry.push(c);
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_high_scores_input(&mut self, key: KeyEvent, from_title: bool) {
+        if let KeyCode::Char('h') | KeyCode::Char('q') | KeyCode::Esc | KeyCode::Enter = key.code {
+            self.mode = if from_title {
+                GameMode::Title(4)
+            } else {
+                self.end_mode()
+            };
+        }
+    }
+
+    fn handle_daily_scores_input(&mut self, key: KeyEvent, from_title: bool) {
+        match key.code {
+            KeyCode::Enter | KeyCode::Char(' ') => {
+                self.notice = None;
+                self.start_daily_run();
+            }
+            KeyCode::Char('x') => self.export_daily(),
+            KeyCode::Char('i') => self.import_daily(),
+            KeyCode::Char('h') | KeyCode::Char('q') | KeyCode::Esc => {
+                self.notice = None;
+                self.mode = if from_title {
+                    GameMode::Title(2)
+                } else {
+                    self.end_mode()
+                };
+            }
+            _ => {}
+        }
+    }
+
+    /// Writes today's daily results to the shared daily folder.
+    fn export_daily(&mut self) {
+        let Some(dir) = &self.data_dir else {
+            self.notice = Some("Nowhere to export to.".to_string());
+            return;
+        };
+        let date = daily::date(SystemTime::now());
+        let name = match self.name_entry.trim() {
+            "" => "Punk",
+            name => name,
+        };
+        self.notice = Some(
+            match self.daily_scores.export(&dir.join("daily"), &date, name) {
+                Ok(path) => format!("Exported to {}", path.display()),
+                Err(err) => format!("Couldn't export: {}", err),
+            },
+        );
+    }
+
+    /// Pulls in everyone else's daily results from the shared daily folder.
+    fn import_daily(&mut self) {
+        let Some(dir) = &self.data_dir else {
+            self.notice = Some("Nowhere to import from.".to_string());
+            return;
+        };
+        self.notice = Some(match self.daily_scores.import(&dir.join("daily")) {
+            Ok(0) => "No new results to import.".to_string(),
+            Ok(added) => format!("Imported {} results.", added),
+            Err(err) => format!("Couldn't import: {}", err),
+        });
+    }
+
+    fn handle_achievements_input(&mut self, key: KeyEvent, from_title: bool) {
+        if let KeyCode::Char('v') | KeyCode::Char('q') | KeyCode::Esc | KeyCode::Enter = key.code {
+            self.mode = if from_title {
+                GameMode::Title(5)
+            } else {
+                self.exploration_mode()
+            };
         }
+    }
 
-        // Boundary check
-        if new_pos.x >= 1
-            && new_pos.x < self.width - 1
-            && new_pos.y >= 1
-            && new_pos.y < self.height - 1
+    /// The end screen this run finished on.
+    fn end_mode(&self) -> GameMode {
+        if self.escaped {
+            GameMode::Victory
+        } else {
+            GameMode::GameOver
+        }
+    }
+
+    /// Tells the quest log what happened and hands out any rewards.
+    fn record_event(&mut self, event: GameEvent) {
+        for update in self.quests.record(&event) {
+            match update {
+                QuestUpdate::Progress { quest, objective } => {
+                    let objective = &self.quests.quests[quest].objectives[objective];
+                    if objective.is_complete() {
+                        self.add_message(
+                            MessageKind::System,
+                            format!("Objective done: {}", objective),
+                        );
+                    }
+                }
+                QuestUpdate::Completed(idx) => {
+                    let quest = self.quests.quests[idx].clone();
+                    self.add_message(
+                        MessageKind::System,
+                        format!("Quest complete: {}!", quest.title),
+                    );
+
+                    for spec in &quest.reward.items {
+                        let Some(item) = spec.create() else {
+                            continue;
+                        };
+                        let name = item.name();
+                        if let Err(item) = self.player.inventory.try_add(item) {
+                            self.ground_items.push(GroundItem {
+                                position: self.player.position,
+                                item,
+                            });
+                        }
+                        self.add_message(MessageKind::Loot, format!("Reward: {}", name));
+                    }
+                    self.reputation += quest.reward.reputation;
+                    if quest.reward.xp > 0 {
+                        self.award_xp(quest.reward.xp);
+                    }
+
+                    if quest.main && self.quests.main_complete() && self.mode == GameMode::Exploring
+                    {
+                        self.mode = GameMode::LevelComplete;
+                        self.add_message(
+                            MessageKind::System,
+                            "The way out is clear! Find the exit.".to_string(),
+                        );
+                    }
+                }
+                QuestUpdate::Failed(idx) => {
+                    let title = self.quests.quests[idx].title;
+                    self.add_message(MessageKind::System, format!("Quest failed: {}", title));
+                }
+            }
+        }
+
+        self.check_achievements(&event);
+    }
+
+    /// Unlocks anything `event` just earned and says so.
+    fn check_achievements(&mut self, event: &GameEvent) {
+        if self.replaying {
+            return;
+        }
+        for achievement in Achievement::ALL {
+            if !self.profile.has(achievement)
+                && achievement.earned_by(event, self)
+                && self.profile.unlock(achievement)
+            {
+                self.add_message(
+                    MessageKind::System,
+                    format!("Achievement unlocked: {}!", achievement.name()),
+                );
+            }
+        }
+    }
+
+    /// Escorted NPCs step into the tile the player just left.
+    fn move_followers(&mut self, old_pos: Position) {
+        let followers: Vec<String> = self
+            .quests
+            .escorting()
+            .into_iter()
+            .map(String::from)
+            .collect();
+
+        for name in followers {
+            if let Some(npc) = self.npcs.iter_mut().find(|n| n.name == name) {
+                npc.position = old_pos;
+                self.record_event(GameEvent::NpcMoved(name, old_pos));
+            }
+        }
+    }
+
+    /// Handles walking into the vendor or an NPC. Returns true if it did.
+    fn bump(&mut self, target: Position) -> bool {
+        // Bumping into the vendor opens the shop
+        if target == self.vendor.position {
+            self.open_shop();
+            return true;
+        }
+
+        // Bumping into anyone else starts a conversation
+        if let Some(npc_idx) = self.npcs.iter().position(|n| n.position == target) {
+            self.start_dialogue(npc_idx);
+            return true;
+        }
+
+        // Bumping into a lock has a go at it with your best tool
+        if let Some(lock_idx) = self
+            .locks
+            .iter()
+            .position(|l| !l.open && l.position == target)
         {
-            self.player.position = new_pos;
-            self.check_chain_interaction();
+            self.try_lock(lock_idx);
+            return true;
+        }
+        false
+    }
+
+    /// Spends a turn working on a lock with the best tool you've got.
+    fn try_lock(&mut self, idx: usize) {
+        let tools = self.player.inventory.usable_tools();
+        let lock = &self.locks[idx];
+        let (name, kind) = (lock.name, lock.kind);
+
+        let Some((tool, chance)) = lock.best_tool(&tools) else {
+            let needed: Vec<&str> = Tool::ALL
+                .iter()
+                .filter(|tool| tool.chance_against(kind).is_some())
+                .map(|tool| tool.name())
+                .collect();
+            self.add_message(
+                MessageKind::System,
+                format!(
+                    "The {} is held shut with a {}. You'd need {}.",
+                    name,
+                    kind,
+                    needed.join(" or ")
+                ),
+            );
+            return;
+        };
+
+        self.make_noise(tool.noise());
+        // Prying with a weapon takes it out of the weapon
+        if tool == Tool::Crowbar
+            && !self.player.inventory.has_tool(Tool::Crowbar)
+            && let Some(broken) = self.player.inventory.wear_weapon()
+        {
+            self.add_message(MessageKind::Loot, format!("Your {} breaks!", broken));
+        }
+
+        if self.rng.gen_range(0..100) < chance {
+            let lock = &mut self.locks[idx];
+            lock.open = true;
+            let position = lock.position;
+            let contents = std::mem::take(&mut lock.contents);
+            self.add_message(
+                MessageKind::Loot,
+                format!("You {} through the {} on the {}!", tool.verb(), kind, name),
+            );
+            for item in contents {
+                self.add_message(MessageKind::Loot, format!("Inside: {}", item.describe()));
+                self.ground_items.push(GroundItem { position, item });
+            }
+            self.record_event(GameEvent::LockOpened(name.to_string()));
+        } else {
+            self.add_message(
+                MessageKind::System,
+                format!(
+                    "You try to {} the {} on the {}, but it holds.",
+                    tool.verb(),
+                    kind,
+                    name
+                ),
+            );
+            if self.rng.gen_range(0..100) < tool.break_chance() {
+                self.player.inventory.remove_tool(tool);
+                self.add_message(MessageKind::Loot, format!("Your {} snap!", tool));
+            }
+        }
+
+        self.pass_turn(false);
+    }
+
+    fn is_walkable(&self, pos: Position) -> bool {
+        pos.x >= 1
+            && pos.x < self.width - 1
+            && pos.y >= 1
+            && pos.y < self.height - 1
+            && pos != self.vendor.position
+            && self.npcs.iter().all(|n| n.position != pos)
+            && self.locks.iter().all(|l| l.open || l.position != pos)
+    }
+
+    /// Anything a running player should stop at rather than dash past.
+    fn is_worth_stopping_at(&self, pos: Position) -> bool {
+        self.locks.iter().any(|l| l.position == pos)
+            || self.enemies.iter().any(|e| e.is_alive && e.position == pos)
+            || self
+                .dumpsters
+                .iter()
+                .any(|d| d.has_items && d.position == pos)
+            || self.ground_items.iter().any(|g| g.position == pos)
+    }
+
+    /// Where the player ends up after moving up to `steps` tiles.
+    fn destination(&self, dx: i32, dy: i32, steps: u32) -> Position {
+        let mut pos = self.player.position;
+        for _ in 0..steps {
+            let next = Position::new(pos.x + dx, pos.y + dy);
+            if !self.is_walkable(next) {
+                break;
+            }
+            pos = next;
+            if self.is_worth_stopping_at(pos) {
+                break;
+            }
+        }
+        pos
+    }
+
+    /// Pays the stamina for a run. Returns false if you're too winded.
+    fn start_run(&mut self) -> bool {
+        if self.player.survival.exert(RUN_COST) {
+            if self.player.sneaking {
+                self.player.sneaking = false;
+                self.add_message(MessageKind::System, "You break cover and run.".to_string());
+            }
+            true
+        } else {
+            self.add_message(MessageKind::System, "Too winded to run!".to_string());
+            false
+        }
+    }
+
+    /// Ends a turn outside of combat: boosts wear off and hunger sets in.
+    fn pass_turn(&mut self, running: bool) {
+        self.turn_count += 1;
+        self.player.inventory.update_turn();
+
+        let was_hungry = self.player.survival.is_hungry();
+        let was_starving = self.player.survival.is_starving();
+        self.player.survival.tick(self.turn_count, running);
+
+        if self.player.survival.is_starving() {
+            if !was_starving {
+                self.add_message(
+                    MessageKind::Combat,
+                    "You're starving! Eat something before it's too late.".to_string(),
+                );
+            }
+            self.hurt_player(1, DeathCause::Starvation);
+            if !self.player.is_alive() {
+                self.mode = GameMode::GameOver;
+                self.add_message(
+                    MessageKind::Combat,
+                    "You starved behind the burger place.".to_string(),
+                );
+            }
+        } else if self.player.survival.is_hungry() && !was_hungry {
+            self.add_message(
+                MessageKind::System,
+                "Your stomach growls. Find something to eat.".to_string(),
+            );
+        }
+
+        if self.player.infection > 0 && self.player.is_alive() {
+            self.player.infection -= 1;
+            self.hurt_player(1, DeathCause::Infection);
+            if !self.player.is_alive() {
+                self.mode = GameMode::GameOver;
+                self.add_message(MessageKind::Combat, "The infection got you.".to_string());
+            } else if self.player.infection == 0 {
+                self.add_message(
+                    MessageKind::System,
+                    "The infection finally clears up.".to_string(),
+                );
+            }
+        }
+
+        self.advance_trash_run();
+        self.reinforce();
+
+        let clock = self.clock();
+        if clock.is_on_the_hour() {
+            self.on_new_hour(clock);
+        }
+    }
+
+    pub fn clock(&self) -> Clock {
+        Clock::at_turn(self.turn_count)
+    }
+
+    /// Whether the player can make out what's on a tile in the current light.
+    pub fn is_visible(&self, pos: Position) -> bool {
+        match self.clock().phase().sight_radius() {
+            Some(radius) => self.player.position.distance_to(&pos) <= radius,
+            None => true,
+        }
+    }
+
+    fn on_new_hour(&mut self, clock: Clock) {
+        let phase = clock.phase();
+        if phase != Clock::at_turn(self.turn_count.saturating_sub(1)).phase() {
+            self.add_message(MessageKind::System, phase.announcement().to_string());
+        }
+
+        if clock.is_trash_time() {
+            self.start_trash_run();
+        }
+    }
+
+    /// Sends someone out the back door to refill every dumpster that's
+    /// been picked clean.
+    fn start_trash_run(&mut self) {
+        let mut empty: Vec<usize> = (0..self.dumpsters.len())
+            .filter(|&idx| !self.dumpsters[idx].has_items)
+            .collect();
+
+        match &mut self.trash_run {
+            Some(run) => {
+                empty.retain(|idx| !run.targets.contains(idx));
+                run.targets.extend(empty);
+            }
+            None if !empty.is_empty() => {
+                // Nearest the door first
+                let door = self.back_door;
+                empty.sort_by_key(|&idx| {
+                    let pos = self.dumpsters[idx].position;
+                    (pos.x - door.x).abs() + (pos.y - door.y).abs()
+                });
+                self.trash_run = Some(TrashRun::new(door, empty));
+                self.add_message(
+                    MessageKind::System,
+                    "The back door bangs open. Kevin's hauling out fresh trash.".to_string(),
+                );
+            }
+            None => {}
+        }
+    }
+
+    fn advance_trash_run(&mut self) {
+        let Some(run) = &mut self.trash_run else {
+            return;
+        };
+        let positions: Vec<Position> = self.dumpsters.iter().map(|d| d.position).collect();
+
+        match run.step(&positions) {
+            TrashRunStep::Walking => {}
+            TrashRunStep::Restocked(idx) => {
+                if !self.dumpsters[idx].has_items {
+                    self.dumpsters[idx].restock(&mut self.rng);
+                    self.add_message(
+                        MessageKind::System,
+                        "Kevin heaves a fresh bag into a dumpster.".to_string(),
+                    );
+                }
+            }
+            TrashRunStep::Done => self.trash_run = None,
+        }
+    }
+
+    /// Brings in whatever the spawn director sends this turn, out of sight
+    /// at the edge of the alley.
+    fn reinforce(&mut self) {
+        let phase = self.clock().phase();
+        let alive_threat = self
+            .enemies
+            .iter()
+            .filter(|e| e.is_alive)
+            .map(|e| e.enemy_type.threat())
+            .sum();
+        let player = self.player.position;
+        let sight = phase.sight_radius().unwrap_or(8.0);
+        let spawns = self
+            .spawns
+            .plan(self.turn_count, phase, alive_threat, &mut self.rng, |pos| {
+                player.distance_to(&pos) > sight
+            });
+
+        let mut arrived = false;
+        for (pos, enemy_type) in spawns {
+            // Alley mouths sit in the wall; step them onto the map
+            let pos = Position::new(
+                pos.x.clamp(1, self.width - 2),
+                pos.y.clamp(1, self.height - 2),
+            );
+            if self.is_walkable(pos) && !self.is_worth_stopping_at(pos) {
+                self.enemies
+                    .push(Enemy::new_of_type(enemy_type, pos.x, pos.y).toughened(&self.rules));
+                arrived = true;
+            }
+        }
+        if arrived {
+            self.add_message(
+                MessageKind::System,
+                "You hear something moving out in the alley.".to_string(),
+            );
+        }
+    }
+
+    fn repair_gear(&mut self) {
+        match self.player.inventory.repair() {
+            RepairOutcome::Repaired {
+                item,
+                material,
+                amount,
+            } => {
+                self.add_message(
+                    MessageKind::Loot,
+                    format!(
+                        "Patched up {} with {} (+{} durability)",
+                        item, material, amount
+                    ),
+                );
+            }
+            RepairOutcome::NothingToRepair => {
+                self.add_message(
+                    MessageKind::Loot,
+                    "Your gear doesn't need fixing.".to_string(),
+                );
+            }
+            RepairOutcome::NoScrap => {
+                self.add_message(MessageKind::Loot, "No scrap to repair with!".to_string());
+            }
         }
     }
 
     fn use_consumable(&mut self) {
         use crate::items::ConsumableEffect;
 
+        let nutrition = self
+            .player
+            .inventory
+            .consumable
+            .as_ref()
+            .and_then(|c| c.nutrition());
+
         if let Some((effect, name)) = self.player.inventory.use_consumable() {
+            self.stats.items_used.push(name.clone());
             match effect {
                 ConsumableEffect::Heal(amount) => {
+                    let amount = self.rules.healing(self.player.healing(amount));
                     self.player.heal(amount);
-                    self.add_message(format!("Used {}! Healed {} HP", name, amount));
+                    self.add_message(
+                        MessageKind::Loot,
+                        format!("Used {}! Healed {} HP", name, amount),
+                    );
+                    // A clean dressing stops the rot
+                    if self.player.infection > 0 && name.contains("Bandage") {
+                        self.player.infection = 0;
+                        self.add_message(
+                            MessageKind::Loot,
+                            "The infection is cleaned up.".to_string(),
+                        );
+                    }
                 }
                 ConsumableEffect::DamageBoost(amount, duration) => {
-                    self.add_message(format!(
-                        "Used {}! +{} damage for {} turns",
-                        name, amount, duration
-                    ));
+                    self.add_message(
+                        MessageKind::Loot,
+                        format!("Used {}! +{} damage for {} turns", name, amount, duration),
+                    );
                 }
                 ConsumableEffect::DefenseBoost(amount, duration) => {
-                    self.add_message(format!(
-                        "Used {}! +{} defense for {} turns",
-                        name, amount, duration
-                    ));
+                    self.add_message(
+                        MessageKind::Loot,
+                        format!("Used {}! +{} defense for {} turns", name, amount, duration),
+                    );
                 }
             }
+
+            if let Some((hunger, stamina)) = nutrition {
+                self.player.survival.eat(hunger, stamina);
+                self.add_message(
+                    MessageKind::Loot,
+                    format!("That hit the spot (+{} food, +{} stamina)", hunger, stamina),
+                );
+            }
         } else {
-            self.add_message("No consumable to use!".to_string());
+            self.add_message(MessageKind::Loot, "No consumable to use!".to_string());
         }
     }
 }
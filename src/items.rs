@@ -1,4 +1,7 @@
+use crate::locks::LockKind;
+use crate::stealth::NoiseSource;
 use rand::Rng;
+use std::collections::BTreeMap;
 use std::fmt;
 
 #[derive(Debug, Clone, PartialEq)]
@@ -8,6 +11,17 @@ pub enum ItemRarity {
     Rare,
 }
 
+impl ItemRarity {
+    /// How many hits an item of this rarity can take before it breaks.
+    pub fn max_durability(&self) -> i32 {
+        match self {
+            ItemRarity::Common => 20,
+            ItemRarity::Uncommon => 30,
+            ItemRarity::Rare => 45,
+        }
+    }
+}
+
 impl fmt::Display for ItemRarity {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
@@ -18,98 +32,223 @@ impl fmt::Display for ItemRarity {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Durability {
+    pub current: i32,
+    pub max: i32,
+}
+
+impl Durability {
+    pub fn new(max: i32) -> Self {
+        Durability { current: max, max }
+    }
+
+    pub fn is_broken(&self) -> bool {
+        self.current <= 0
+    }
+
+    pub fn is_damaged(&self) -> bool {
+        self.current < self.max
+    }
+
+    /// Wears the item down by `amount`. Returns true if this broke it.
+    pub fn wear(&mut self, amount: i32) -> bool {
+        let was_broken = self.is_broken();
+        self.current = (self.current - amount).max(0);
+        !was_broken && self.is_broken()
+    }
+
+    /// Restores up to `amount` points and returns how many were actually restored.
+    pub fn repair(&mut self, amount: i32) -> i32 {
+        let before = self.current;
+        self.current = (self.current + amount).min(self.max);
+        self.current - before
+    }
+
+    /// Scales a stat bonus by condition: full strength when pristine,
+    /// dropping towards half as the item falls apart.
+    pub fn scale(&self, bonus: i32) -> i32 {
+        if self.is_broken() || bonus <= 0 {
+            return 0;
+        }
+        (bonus * (self.max + self.current) / (2 * self.max)).max(1)
+    }
+
+    pub fn condition(&self) -> &'static str {
+        let percent = self.current * 100 / self.max.max(1);
+        if percent >= 75 {
+            "Good"
+        } else if percent >= 40 {
+            "Worn"
+        } else {
+            "Battered"
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Weapon {
     pub name: String,
     pub damage_bonus: i32,
-    #[allow(dead_code)]
     pub rarity: ItemRarity,
     #[allow(dead_code)]
     pub description: String,
+    pub durability: Durability,
 }
 
-impl Weapon {
-    pub fn random_generate() -> Self {
-        let mut rng = rand::thread_rng();
-        let roll = rng.gen_range(0..100);
+const WEAPON_TYPES: [(&str, i32, &str); 9] = [
+    ("Broken Bottle", 2, "Sharp glass from the dumpster"),
+    ("Rusty Chain", 3, "Heavy and intimidating"),
+    ("Spiked Bat", 4, "With nails driven through it"),
+    ("Baseball Bat", 3, "Somebody's little league dreams"),
+    ("Bike Lock", 3, "U-lock makes a good weapon"),
+    ("Crowbar", 5, "Classic street tool"),
+    ("Switchblade", 3, "Flicks open with a satisfying click"),
+    ("Brass Knuckles", 2, "Adds weight to your punch"),
+    ("Metal Pipe", 4, "Found behind the dumpster"),
+];
 
-        let (rarity, multiplier) = if roll < 60 {
-            (ItemRarity::Common, 1)
-        } else if roll < 90 {
-            (ItemRarity::Uncommon, 2)
-        } else {
-            (ItemRarity::Rare, 3)
-        };
+/// Heavier kit that only turns up in the bins once it's been unlocked.
+const SALVAGE_WEAPON_TYPES: [(&str, i32, &str); 2] = [
+    ("Fire Axe", 6, "Pried off the wall of a burned-out diner"),
+    ("Nail Gun", 5, "Still has half a strip in it"),
+];
 
-        let weapon_types = [
-            ("Broken Bottle", 2, "Sharp glass from the dumpster"),
-            ("Rusty Chain", 3, "Heavy and intimidating"),
-            ("Spiked Bat", 4, "With nails driven through it"),
-            ("Bike Lock", 3, "U-lock makes a good weapon"),
-            ("Crowbar", 5, "Classic street tool"),
-            ("Switchblade", 3, "Flicks open with a satisfying click"),
-            ("Brass Knuckles", 2, "Adds weight to your punch"),
-            ("Metal Pipe", 4, "Found behind the dumpster"),
-        ];
-
-        let idx = rng.gen_range(0..weapon_types.len());
-        let (name, base_damage, desc) = weapon_types[idx];
-        let damage_bonus = base_damage * multiplier;
+/// Which item tables dumpsters roll from. Profile unlocks add to it.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ItemPool {
+    pub salvage_weapons: bool,
+    pub salvage_armor: bool,
+}
 
+fn roll_rarity(rng: &mut impl Rng) -> (ItemRarity, i32) {
+    let roll = rng.gen_range(0..100);
+
+    if roll < 60 {
+        (ItemRarity::Common, 1)
+    } else if roll < 90 {
+        (ItemRarity::Uncommon, 2)
+    } else {
+        (ItemRarity::Rare, 3)
+    }
+}
+
+impl Weapon {
+    pub fn new(name: &str, damage_bonus: i32, rarity: ItemRarity, description: &str) -> Self {
         Weapon {
             name: name.to_string(),
             damage_bonus,
+            durability: Durability::new(rarity.max_durability()),
             rarity,
-            description: desc.to_string(),
+            description: description.to_string(),
         }
     }
+
+    #[cfg(test)]
+    pub fn random_generate() -> Self {
+        Self::random_from(&mut rand::thread_rng(), ItemPool::default())
+    }
+
+    /// A random weapon from the base table plus whatever `pool` adds.
+    pub fn random_from(rng: &mut impl Rng, pool: ItemPool) -> Self {
+        let (rarity, multiplier) = roll_rarity(rng);
+
+        let extra: &[_] = if pool.salvage_weapons {
+            &SALVAGE_WEAPON_TYPES
+        } else {
+            &[]
+        };
+        let types: Vec<_> = WEAPON_TYPES.iter().chain(extra).collect();
+        let (name, base_damage, desc) = types[rng.gen_range(0..types.len())];
+
+        Weapon::new(name, base_damage * multiplier, rarity, desc)
+    }
+
+    /// A common copy of a weapon from the item table, looked up by name.
+    pub fn named(name: &str) -> Option<Self> {
+        WEAPON_TYPES
+            .iter()
+            .chain(&SALVAGE_WEAPON_TYPES)
+            .find(|(n, _, _)| n.eq_ignore_ascii_case(name))
+            .map(|(n, damage, desc)| Weapon::new(n, *damage, ItemRarity::Common, desc))
+    }
+
+    /// Damage bonus after accounting for wear.
+    pub fn effective_damage(&self) -> i32 {
+        self.durability.scale(self.damage_bonus)
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct Armor {
     pub name: String,
     pub defense_bonus: i32,
-    #[allow(dead_code)]
     pub rarity: ItemRarity,
     #[allow(dead_code)]
     pub description: String,
+    pub durability: Durability,
 }
 
-impl Armor {
-    pub fn random_generate() -> Self {
-        let mut rng = rand::thread_rng();
-        let roll = rng.gen_range(0..100);
-
-        let (rarity, multiplier) = if roll < 60 {
-            (ItemRarity::Common, 1)
-        } else if roll < 90 {
-            (ItemRarity::Uncommon, 2)
-        } else {
-            (ItemRarity::Rare, 3)
-        };
+const ARMOR_TYPES: [(&str, i32, &str); 8] = [
+    ("Leather Jacket", 2, "Worn but protective"),
+    ("Studded Vest", 3, "Metal studs add defense"),
+    ("Chain Mail Shirt", 4, "Surprisingly effective"),
+    ("Motorcycle Helmet", 2, "Protects your head"),
+    ("Kevlar Vest", 5, "Military surplus find"),
+    ("Padded Hoodie", 2, "Extra layers help"),
+    ("Steel-Toe Boots", 1, "Good for kicking and protection"),
+    ("Riot Shield", 4, "Liberated from authorities"),
+];
 
-        let armor_types = [
-            ("Leather Jacket", 2, "Worn but protective"),
-            ("Studded Vest", 3, "Metal studs add defense"),
-            ("Chain Mail Shirt", 4, "Surprisingly effective"),
-            ("Motorcycle Helmet", 2, "Protects your head"),
-            ("Kevlar Vest", 5, "Military surplus find"),
-            ("Padded Hoodie", 2, "Extra layers help"),
-            ("Steel-Toe Boots", 1, "Good for kicking and protection"),
-            ("Riot Shield", 4, "Liberated from authorities"),
-        ];
-
-        let idx = rng.gen_range(0..armor_types.len());
-        let (name, base_defense, desc) = armor_types[idx];
-        let defense_bonus = base_defense * multiplier;
+const SALVAGE_ARMOR_TYPES: [(&str, i32, &str); 2] = [
+    ("Hockey Pads", 3, "Smells like a locker room"),
+    ("Bomb Suit Vest", 6, "Heavy, but nothing gets through"),
+];
 
+impl Armor {
+    pub fn new(name: &str, defense_bonus: i32, rarity: ItemRarity, description: &str) -> Self {
         Armor {
             name: name.to_string(),
             defense_bonus,
+            durability: Durability::new(rarity.max_durability()),
             rarity,
-            description: desc.to_string(),
+            description: description.to_string(),
         }
     }
+
+    #[cfg(test)]
+    pub fn random_generate() -> Self {
+        Self::random_from(&mut rand::thread_rng(), ItemPool::default())
+    }
+
+    /// A random armor piece from the base table plus whatever `pool` adds.
+    pub fn random_from(rng: &mut impl Rng, pool: ItemPool) -> Self {
+        let (rarity, multiplier) = roll_rarity(rng);
+
+        let extra: &[_] = if pool.salvage_armor {
+            &SALVAGE_ARMOR_TYPES
+        } else {
+            &[]
+        };
+        let types: Vec<_> = ARMOR_TYPES.iter().chain(extra).collect();
+        let (name, base_defense, desc) = types[rng.gen_range(0..types.len())];
+
+        Armor::new(name, base_defense * multiplier, rarity, desc)
+    }
+
+    /// A common copy of an armor piece from the item table, looked up by name.
+    pub fn named(name: &str) -> Option<Self> {
+        ARMOR_TYPES
+            .iter()
+            .chain(&SALVAGE_ARMOR_TYPES)
+            .find(|(n, _, _)| n.eq_ignore_ascii_case(name))
+            .map(|(n, defense, desc)| Armor::new(n, *defense, ItemRarity::Common, desc))
+    }
+
+    /// Defense bonus after accounting for wear.
+    pub fn effective_defense(&self) -> i32 {
+        self.durability.scale(self.defense_bonus)
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -127,80 +266,396 @@ pub struct Consumable {
     pub description: String,
 }
 
-impl Consumable {
-    pub fn random_generate() -> Self {
-        let mut rng = rand::thread_rng();
-        let consumable_types = [
-            (
-                "Burger Leftovers",
-                ConsumableEffect::Heal(15),
-                "Still edible... probably",
-            ),
-            (
-                "Energy Drink",
-                ConsumableEffect::DamageBoost(5, 3),
-                "Gives you wings (and jitters)",
-            ),
-            (
-                "Painkillers",
-                ConsumableEffect::DefenseBoost(3, 5),
-                "Takes the edge off",
-            ),
-            (
-                "Mystery Meat",
-                ConsumableEffect::Heal(25),
-                "Don't ask what it is",
-            ),
-            (
-                "Expired Soda",
-                ConsumableEffect::Heal(10),
-                "Flat but refreshing",
-            ),
-            (
-                "Protein Bar",
-                ConsumableEffect::Heal(20),
-                "Found in someone's backpack",
-            ),
-            (
-                "Adrenaline Shot",
-                ConsumableEffect::DamageBoost(8, 2),
-                "Fight or flight activated",
-            ),
-            (
-                "Bandages",
-                ConsumableEffect::Heal(30),
-                "Clean-ish medical supplies",
-            ),
-        ];
-
-        let idx = rng.gen_range(0..consumable_types.len());
-        let (name, effect, desc) = &consumable_types[idx];
+const CONSUMABLE_TYPES: [(&str, ConsumableEffect, &str); 8] = [
+    (
+        "Burger Leftovers",
+        ConsumableEffect::Heal(15),
+        "Still edible... probably",
+    ),
+    (
+        "Energy Drink",
+        ConsumableEffect::DamageBoost(5, 3),
+        "Gives you wings (and jitters)",
+    ),
+    (
+        "Painkillers",
+        ConsumableEffect::DefenseBoost(3, 5),
+        "Takes the edge off",
+    ),
+    (
+        "Mystery Meat",
+        ConsumableEffect::Heal(25),
+        "Don't ask what it is",
+    ),
+    (
+        "Expired Soda",
+        ConsumableEffect::Heal(10),
+        "Flat but refreshing",
+    ),
+    (
+        "Protein Bar",
+        ConsumableEffect::Heal(20),
+        "Found in someone's backpack",
+    ),
+    (
+        "Adrenaline Shot",
+        ConsumableEffect::DamageBoost(8, 2),
+        "Fight or flight activated",
+    ),
+    (
+        "Bandages",
+        ConsumableEffect::Heal(30),
+        "Clean-ish medical supplies",
+    ),
+];
 
+/// Things only a rat would carry around.
+const GROSS_CONSUMABLE_TYPES: [(&str, ConsumableEffect, &str); 3] = [
+    (
+        "Gnawed Jerky",
+        ConsumableEffect::Heal(8),
+        "Pulled from a rat's nest",
+    ),
+    (
+        "Rat Tail",
+        ConsumableEffect::Heal(3),
+        "Crunchy. Don't think about it",
+    ),
+    (
+        "Fermented Fries",
+        ConsumableEffect::DamageBoost(2, 3),
+        "Fizzing slightly",
+    ),
+];
+
+impl Consumable {
+    pub fn new(name: &str, effect: ConsumableEffect, description: &str) -> Self {
         Consumable {
             name: name.to_string(),
-            effect: effect.clone(),
-            description: desc.to_string(),
+            effect,
+            description: description.to_string(),
         }
     }
+
+    #[cfg(test)]
+    pub fn random_generate() -> Self {
+        Self::random(&mut rand::thread_rng())
+    }
+
+    pub fn random(rng: &mut impl Rng) -> Self {
+        let idx = rng.gen_range(0..CONSUMABLE_TYPES.len());
+        let (name, effect, desc) = &CONSUMABLE_TYPES[idx];
+
+        Consumable::new(name, effect.clone(), desc)
+    }
+
+    /// Looks up a consumable (gross ones included) by name.
+    pub fn named(name: &str) -> Option<Self> {
+        CONSUMABLE_TYPES
+            .iter()
+            .chain(GROSS_CONSUMABLE_TYPES.iter())
+            .find(|(n, _, _)| n.eq_ignore_ascii_case(name))
+            .map(|(n, effect, desc)| Consumable::new(n, effect.clone(), desc))
+    }
+
+    pub fn random_gross(rng: &mut impl Rng) -> Self {
+        let idx = rng.gen_range(0..GROSS_CONSUMABLE_TYPES.len());
+        let (name, effect, desc) = &GROSS_CONSUMABLE_TYPES[idx];
+
+        Consumable::new(name, effect.clone(), desc)
+    }
+
+    /// How much hunger and stamina this restores, if it's something you can eat.
+    pub fn nutrition(&self) -> Option<(i32, i32)> {
+        FOOD_VALUES
+            .iter()
+            .find(|(n, _, _)| n.eq_ignore_ascii_case(&self.name))
+            .map(|(_, hunger, stamina)| (*hunger, *stamina))
+    }
+}
+
+/// Food and drink: (name, hunger restored, stamina restored).
+const FOOD_VALUES: [(&str, i32, i32); 8] = [
+    ("Burger Leftovers", 35, 0),
+    ("Mystery Meat", 40, 0),
+    ("Protein Bar", 25, 25),
+    ("Expired Soda", 10, 20),
+    ("Energy Drink", 5, 40),
+    ("Gnawed Jerky", 20, 0),
+    ("Rat Tail", 10, 0),
+    ("Fermented Fries", 25, 0),
+];
+
+/// Crafting materials and repair junk. These stack in your pockets
+/// instead of taking up an equipment slot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum MaterialKind {
+    DuctTape,
+    Nails,
+    ScrapMetal,
+    Cloth,
+    Bottle,
+    ZipTies,
+    Superglue,
+    Wire,
+}
+
+impl MaterialKind {
+    pub const ALL: [MaterialKind; 8] = [
+        MaterialKind::DuctTape,
+        MaterialKind::Nails,
+        MaterialKind::ScrapMetal,
+        MaterialKind::Cloth,
+        MaterialKind::Bottle,
+        MaterialKind::ZipTies,
+        MaterialKind::Superglue,
+        MaterialKind::Wire,
+    ];
+
+    pub fn random(rng: &mut impl Rng) -> Self {
+        Self::ALL[rng.gen_range(0..Self::ALL.len())]
+    }
+
+    /// Parses a material from its full or short name, e.g. "Duct Tape" or "tape".
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| {
+            kind.to_string().eq_ignore_ascii_case(name)
+                || kind.short_name().eq_ignore_ascii_case(name)
+        })
+    }
+
+    /// Durability restored when used to patch gear, if it can be used that way.
+    pub fn repair_amount(&self) -> Option<i32> {
+        match self {
+            MaterialKind::DuctTape => Some(10),
+            MaterialKind::ZipTies => Some(6),
+            MaterialKind::Superglue => Some(8),
+            MaterialKind::Wire => Some(12),
+            _ => None,
+        }
+    }
+
+    pub fn value(&self) -> u32 {
+        match self {
+            MaterialKind::DuctTape | MaterialKind::Wire => 4,
+            MaterialKind::ScrapMetal | MaterialKind::Superglue => 3,
+            _ => 2,
+        }
+    }
+
+    /// Compact name for the HUD.
+    pub fn short_name(&self) -> &'static str {
+        match self {
+            MaterialKind::DuctTape => "Tape",
+            MaterialKind::Nails => "Nails",
+            MaterialKind::ScrapMetal => "Metal",
+            MaterialKind::Cloth => "Cloth",
+            MaterialKind::Bottle => "Bottle",
+            MaterialKind::ZipTies => "Ties",
+            MaterialKind::Superglue => "Glue",
+            MaterialKind::Wire => "Wire",
+        }
+    }
+}
+
+impl fmt::Display for MaterialKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MaterialKind::DuctTape => write!(f, "Duct Tape"),
+            MaterialKind::Nails => write!(f, "Nails"),
+            MaterialKind::ScrapMetal => write!(f, "Scrap Metal"),
+            MaterialKind::Cloth => write!(f, "Cloth"),
+            MaterialKind::Bottle => write!(f, "Bottle"),
+            MaterialKind::ZipTies => write!(f, "Zip Ties"),
+            MaterialKind::Superglue => write!(f, "Superglue"),
+            MaterialKind::Wire => write!(f, "Wire Coil"),
+        }
+    }
+}
+
+/// Tools for getting through locks. You only ever need one of each.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Tool {
+    BoltCutters,
+    /// Carried like the others, or pried with an equipped Crowbar weapon.
+    Crowbar,
+    LockPicks,
+}
+
+impl Tool {
+    pub const ALL: [Tool; 3] = [Tool::BoltCutters, Tool::Crowbar, Tool::LockPicks];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Tool::BoltCutters => "Bolt Cutters",
+            Tool::Crowbar => "Crowbar",
+            Tool::LockPicks => "Lock Picks",
+        }
+    }
+
+    pub fn value(&self) -> u32 {
+        match self {
+            Tool::BoltCutters => 30,
+            Tool::Crowbar => 10,
+            Tool::LockPicks => 12,
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Tool::ALL
+            .into_iter()
+            .find(|tool| tool.name().eq_ignore_ascii_case(name))
+    }
+
+    /// Percent chance per attempt of getting through a lock, or None if
+    /// this tool is no use on it.
+    pub fn chance_against(&self, lock: LockKind) -> Option<u32> {
+        match (self, lock) {
+            (Tool::BoltCutters, LockKind::Chain) => Some(100),
+            (Tool::BoltCutters, LockKind::Padlock) => Some(90),
+            (Tool::BoltCutters, LockKind::Fence) => Some(80),
+            (Tool::Crowbar, LockKind::Chain) => Some(20),
+            (Tool::Crowbar, LockKind::Padlock) => Some(60),
+            (Tool::Crowbar, LockKind::Fence) => Some(35),
+            (Tool::LockPicks, LockKind::Padlock) => Some(55),
+            (Tool::LockPicks, _) => None,
+        }
+    }
+
+    /// Picking is quiet, cutting less so, and prying is a racket.
+    pub fn noise(&self) -> NoiseSource {
+        match self {
+            Tool::BoltCutters => NoiseSource::Cutting,
+            Tool::Crowbar => NoiseSource::Prying,
+            Tool::LockPicks => NoiseSource::Picking,
+        }
+    }
+
+    pub fn verb(&self) -> &'static str {
+        match self {
+            Tool::BoltCutters => "cut",
+            Tool::Crowbar => "pry",
+            Tool::LockPicks => "pick",
+        }
+    }
+
+    /// Chance in percent that a failed attempt breaks the tool.
+    pub fn break_chance(&self) -> u32 {
+        match self {
+            Tool::LockPicks => 30,
+            _ => 0,
+        }
+    }
+}
+
+impl fmt::Display for Tool {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
 }
 
+/// Any single item, for when it doesn't matter which slot it goes in:
+/// loot on the ground, rewards, trades.
 #[derive(Debug, Clone)]
-pub struct BoltCutters {
-    pub found: bool,
+pub enum Item {
+    Weapon(Weapon),
+    Armor(Armor),
+    Consumable(Consumable),
+    Material(MaterialKind),
+    Tool(Tool),
+    /// A pile of bottlecaps, the alley's only currency.
+    Cash(u32),
 }
 
-impl BoltCutters {
-    pub fn new() -> Self {
-        BoltCutters { found: false }
+impl Item {
+    pub fn name(&self) -> String {
+        match self {
+            Item::Weapon(weapon) => weapon.name.clone(),
+            Item::Armor(armor) => armor.name.clone(),
+            Item::Consumable(consumable) => consumable.name.clone(),
+            Item::Material(kind) => kind.to_string(),
+            Item::Tool(tool) => tool.to_string(),
+            Item::Cash(amount) => format!("{} caps", amount),
+        }
+    }
+
+    /// Name plus the stat that matters, for pickup messages.
+    pub fn describe(&self) -> String {
+        match self {
+            Item::Weapon(weapon) => {
+                format!("{} (+{} damage)", weapon.name, weapon.effective_damage())
+            }
+            Item::Armor(armor) => {
+                format!("{} (+{} defense)", armor.name, armor.effective_defense())
+            }
+            Item::Consumable(consumable) => consumable.name.clone(),
+            Item::Material(kind) => kind.to_string(),
+            Item::Tool(tool) => tool.to_string(),
+            Item::Cash(amount) => format!("{} caps", amount),
+        }
     }
+
+    /// What the item is worth in caps, before any vendor markup.
+    pub fn value(&self) -> u32 {
+        match self {
+            Item::Weapon(weapon) => 4 * weapon.effective_damage().max(0) as u32 + 2,
+            Item::Armor(armor) => 5 * armor.effective_defense().max(0) as u32 + 2,
+            Item::Consumable(consumable) => match consumable.effect {
+                ConsumableEffect::Heal(amount) => amount as u32 / 2 + 2,
+                ConsumableEffect::DamageBoost(amount, turns)
+                | ConsumableEffect::DefenseBoost(amount, turns) => (amount as u32 * turns) / 2 + 4,
+            },
+            Item::Material(kind) => kind.value(),
+            Item::Tool(tool) => tool.value(),
+            Item::Cash(amount) => *amount,
+        }
+    }
+
+    pub fn glyph(&self) -> char {
+        match self {
+            Item::Weapon(_) => ')',
+            Item::Armor(_) => '[',
+            Item::Consumable(_) => '!',
+            Item::Material(_) => '*',
+            Item::Tool(_) => '&',
+            Item::Cash(_) => '$',
+        }
+    }
+}
+
+/// Where an item lives in your inventory.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Slot {
+    Weapon,
+    Armor,
+    Consumable,
+    Material(MaterialKind),
+    Tool(Tool),
 }
 
+#[derive(Debug, Clone, PartialEq)]
+pub enum RepairOutcome {
+    Repaired {
+        item: String,
+        material: MaterialKind,
+        amount: i32,
+    },
+    NothingToRepair,
+    NoScrap,
+}
+
+/// How many pieces of junk fit in your pockets.
+pub const JUNK_CAPACITY: u32 = 12;
+
 #[derive(Debug, Clone)]
 pub struct Inventory {
     pub weapon: Option<Weapon>,
     pub armor: Option<Armor>,
     pub consumable: Option<Consumable>,
-    pub bolt_cutters: BoltCutters,
+    pub materials: BTreeMap<MaterialKind, u32>,
+    pub cash: u32,
+    /// Lock-opening tools, kept on a key ring rather than in a slot.
+    pub tools: Vec<Tool>,
     pub temp_damage_boost: i32,
     pub temp_defense_boost: i32,
     pub boost_turns_remaining: u32,
@@ -212,7 +667,9 @@ impl Inventory {
             weapon: None,
             armor: None,
             consumable: None,
-            bolt_cutters: BoltCutters::new(),
+            materials: BTreeMap::new(),
+            cash: 0,
+            tools: Vec::new(),
             temp_damage_boost: 0,
             temp_defense_boost: 0,
             boost_turns_remaining: 0,
@@ -220,15 +677,237 @@ impl Inventory {
     }
 
     pub fn total_damage_bonus(&self) -> i32 {
-        let weapon_bonus = self.weapon.as_ref().map_or(0, |w| w.damage_bonus);
+        let weapon_bonus = self.weapon.as_ref().map_or(0, |w| w.effective_damage());
         weapon_bonus + self.temp_damage_boost
     }
 
     pub fn total_defense_bonus(&self) -> i32 {
-        let armor_bonus = self.armor.as_ref().map_or(0, |a| a.defense_bonus);
+        let armor_bonus = self.armor.as_ref().map_or(0, |a| a.effective_defense());
         armor_bonus + self.temp_defense_boost
     }
 
+    /// Puts an item into its slot (or pockets). Hands it back if there's no room.
+    pub fn try_add(&mut self, item: Item) -> Result<(), Item> {
+        match item {
+            Item::Weapon(weapon) if self.weapon.is_none() => self.weapon = Some(weapon),
+            Item::Armor(armor) if self.armor.is_none() => self.armor = Some(armor),
+            Item::Consumable(consumable) if self.consumable.is_none() => {
+                self.consumable = Some(consumable)
+            }
+            Item::Material(kind) if self.add_material(kind) => {}
+            Item::Tool(tool) if self.add_tool(tool) => {}
+            Item::Cash(amount) => self.cash += amount,
+            item => return Err(item),
+        }
+        Ok(())
+    }
+
+    /// Every slot that currently holds something, in display order.
+    pub fn filled_slots(&self) -> Vec<Slot> {
+        let mut slots = Vec::new();
+        if self.weapon.is_some() {
+            slots.push(Slot::Weapon);
+        }
+        if self.armor.is_some() {
+            slots.push(Slot::Armor);
+        }
+        if self.consumable.is_some() {
+            slots.push(Slot::Consumable);
+        }
+        slots.extend(self.materials.keys().map(|kind| Slot::Material(*kind)));
+        slots.extend(self.tools.iter().map(|tool| Slot::Tool(*tool)));
+        slots
+    }
+
+    /// A copy of what's in a slot, without removing it.
+    pub fn peek(&self, slot: Slot) -> Option<Item> {
+        match slot {
+            Slot::Weapon => self.weapon.clone().map(Item::Weapon),
+            Slot::Armor => self.armor.clone().map(Item::Armor),
+            Slot::Consumable => self.consumable.clone().map(Item::Consumable),
+            Slot::Material(kind) => (self.material_count(kind) > 0).then_some(Item::Material(kind)),
+            Slot::Tool(tool) => self.has_tool(tool).then_some(Item::Tool(tool)),
+        }
+    }
+
+    /// Removes one item from a slot.
+    pub fn take(&mut self, slot: Slot) -> Option<Item> {
+        match slot {
+            Slot::Weapon => self.weapon.take().map(Item::Weapon),
+            Slot::Armor => self.armor.take().map(Item::Armor),
+            Slot::Consumable => self.consumable.take().map(Item::Consumable),
+            Slot::Material(kind) => self
+                .remove_materials(kind, 1)
+                .then_some(Item::Material(kind)),
+            Slot::Tool(tool) => self.remove_tool(tool).then_some(Item::Tool(tool)),
+        }
+    }
+
+    /// Equips an item, returning whatever was in that slot before.
+    /// Junk can't be swapped; it's returned if your pockets are full.
+    pub fn swap(&mut self, item: Item) -> Option<Item> {
+        match item {
+            Item::Weapon(weapon) => self.weapon.replace(weapon).map(Item::Weapon),
+            Item::Armor(armor) => self.armor.replace(armor).map(Item::Armor),
+            Item::Consumable(consumable) => {
+                self.consumable.replace(consumable).map(Item::Consumable)
+            }
+            Item::Material(kind) => {
+                if self.add_material(kind) {
+                    None
+                } else {
+                    Some(Item::Material(kind))
+                }
+            }
+            Item::Tool(tool) => {
+                if self.add_tool(tool) {
+                    None
+                } else {
+                    Some(Item::Tool(tool))
+                }
+            }
+            Item::Cash(amount) => {
+                self.cash += amount;
+                None
+            }
+        }
+    }
+
+    pub fn has_tool(&self, tool: Tool) -> bool {
+        self.tools.contains(&tool)
+    }
+
+    /// Adds a tool to the key ring. Returns false if you already have one.
+    pub fn add_tool(&mut self, tool: Tool) -> bool {
+        if self.has_tool(tool) {
+            return false;
+        }
+        self.tools.push(tool);
+        self.tools.sort();
+        true
+    }
+
+    pub fn remove_tool(&mut self, tool: Tool) -> bool {
+        let had = self.has_tool(tool);
+        self.tools.retain(|t| *t != tool);
+        had
+    }
+
+    /// Everything you could try on a lock, including an equipped crowbar.
+    pub fn usable_tools(&self) -> Vec<Tool> {
+        let mut tools = self.tools.clone();
+        if self.weapon.as_ref().is_some_and(|w| w.name == "Crowbar")
+            && !tools.contains(&Tool::Crowbar)
+        {
+            tools.push(Tool::Crowbar);
+        }
+        tools
+    }
+
+    pub fn material_count(&self, kind: MaterialKind) -> u32 {
+        self.materials.get(&kind).copied().unwrap_or(0)
+    }
+
+    pub fn total_materials(&self) -> u32 {
+        self.materials.values().sum()
+    }
+
+    pub fn has_room_for_material(&self) -> bool {
+        self.total_materials() < JUNK_CAPACITY
+    }
+
+    /// Pockets one piece of junk. Returns false if there's no room.
+    pub fn add_material(&mut self, kind: MaterialKind) -> bool {
+        if !self.has_room_for_material() {
+            return false;
+        }
+        *self.materials.entry(kind).or_insert(0) += 1;
+        true
+    }
+
+    /// Removes `count` of a material. Returns false (and removes nothing)
+    /// if there isn't enough.
+    pub fn remove_materials(&mut self, kind: MaterialKind, count: u32) -> bool {
+        let have = self.material_count(kind);
+        if have < count {
+            return false;
+        }
+        if have == count {
+            self.materials.remove(&kind);
+        } else {
+            self.materials.insert(kind, have - count);
+        }
+        true
+    }
+
+    /// Wears down the equipped weapon after landing a hit.
+    /// Returns the weapon's name if it broke and was discarded.
+    pub fn wear_weapon(&mut self) -> Option<String> {
+        let broke = self.weapon.as_mut().is_some_and(|w| w.durability.wear(1));
+        if broke {
+            self.weapon.take().map(|w| w.name)
+        } else {
+            None
+        }
+    }
+
+    /// Wears down the equipped armor after absorbing a hit.
+    /// Returns the armor's name if it broke and was discarded.
+    pub fn wear_armor(&mut self) -> Option<String> {
+        let broke = self.armor.as_mut().is_some_and(|a| a.durability.wear(1));
+        if broke {
+            self.armor.take().map(|a| a.name)
+        } else {
+            None
+        }
+    }
+
+    /// Uses one piece of repair junk on whichever equipped item is in worse shape.
+    pub fn repair(&mut self) -> RepairOutcome {
+        let weapon_ratio = self
+            .weapon
+            .as_ref()
+            .filter(|w| w.durability.is_damaged())
+            .map(|w| w.durability.current * 100 / w.durability.max);
+        let armor_ratio = self
+            .armor
+            .as_ref()
+            .filter(|a| a.durability.is_damaged())
+            .map(|a| a.durability.current * 100 / a.durability.max);
+
+        let repair_weapon = match (weapon_ratio, armor_ratio) {
+            (None, None) => return RepairOutcome::NothingToRepair,
+            (Some(w), Some(a)) => w <= a,
+            (Some(_), None) => true,
+            (None, Some(_)) => false,
+        };
+
+        let Some((material, repair_amount)) = self
+            .materials
+            .keys()
+            .find_map(|kind| kind.repair_amount().map(|amount| (*kind, amount)))
+        else {
+            return RepairOutcome::NoScrap;
+        };
+        self.remove_materials(material, 1);
+
+        let (item, amount) = if repair_weapon {
+            let weapon = self.weapon.as_mut().expect("weapon checked above");
+            let amount = weapon.durability.repair(repair_amount);
+            (weapon.name.clone(), amount)
+        } else {
+            let armor = self.armor.as_mut().expect("armor checked above");
+            let amount = armor.durability.repair(repair_amount);
+            (armor.name.clone(), amount)
+        };
+
+        RepairOutcome::Repaired {
+            item,
+            material,
+            amount,
+        }
+    }
+
     pub fn update_turn(&mut self) {
         if self.boost_turns_remaining > 0 {
             self.boost_turns_remaining -= 1;
@@ -298,7 +977,25 @@ mod tests {
         assert!(inventory.weapon.is_none());
         assert!(inventory.armor.is_none());
         assert!(inventory.consumable.is_none());
-        assert!(!inventory.bolt_cutters.found);
+        assert!(inventory.tools.is_empty());
+    }
+
+    #[test]
+    fn test_tools_go_on_the_key_ring() {
+        let mut inventory = Inventory::new();
+        assert!(inventory.try_add(Item::Tool(Tool::LockPicks)).is_ok());
+        assert!(inventory.try_add(Item::Tool(Tool::LockPicks)).is_err());
+        assert_eq!(inventory.filled_slots(), vec![Slot::Tool(Tool::LockPicks)]);
+
+        // An equipped crowbar doubles as a pry bar
+        inventory.weapon = Weapon::named("Crowbar");
+        assert_eq!(
+            inventory.usable_tools(),
+            vec![Tool::LockPicks, Tool::Crowbar]
+        );
+
+        assert!(inventory.take(Slot::Tool(Tool::LockPicks)).is_some());
+        assert!(!inventory.has_tool(Tool::LockPicks));
     }
 
     #[test]
@@ -312,6 +1009,7 @@ mod tests {
             damage_bonus: 5,
             rarity: ItemRarity::Common,
             description: "Test".to_string(),
+            durability: Durability::new(20),
         });
 
         inventory.armor = Some(Armor {
@@ -319,6 +1017,7 @@ mod tests {
             defense_bonus: 3,
             rarity: ItemRarity::Common,
             description: "Test".to_string(),
+            durability: Durability::new(20),
         });
 
         assert_eq!(inventory.total_damage_bonus(), 5);
@@ -355,4 +1054,150 @@ mod tests {
         assert_eq!(inventory.boost_turns_remaining, 0);
         assert_eq!(inventory.temp_damage_boost, 0);
     }
+
+    #[test]
+    fn test_durability_wear_and_scaling() {
+        let mut durability = Durability::new(10);
+        assert_eq!(durability.scale(6), 6);
+
+        assert!(!durability.wear(5));
+        assert_eq!(durability.current, 5);
+        assert_eq!(durability.scale(6), 4);
+        assert_eq!(durability.condition(), "Worn");
+
+        assert!(durability.wear(5));
+        assert!(durability.is_broken());
+        assert_eq!(durability.scale(6), 0);
+
+        // Wearing an already broken item doesn't report breaking again
+        assert!(!durability.wear(1));
+    }
+
+    #[test]
+    fn test_weapon_breaks_and_is_discarded() {
+        let mut inventory = Inventory::new();
+        let mut weapon = Weapon::random_generate();
+        weapon.durability = Durability {
+            current: 1,
+            max: 20,
+        };
+        let name = weapon.name.clone();
+        inventory.weapon = Some(weapon);
+
+        assert_eq!(inventory.wear_weapon(), Some(name));
+        assert!(inventory.weapon.is_none());
+        assert_eq!(inventory.total_damage_bonus(), 0);
+    }
+
+    #[test]
+    fn test_repair_uses_scrap_on_worst_item() {
+        let mut inventory = Inventory::new();
+        assert_eq!(inventory.repair(), RepairOutcome::NothingToRepair);
+
+        let mut weapon = Weapon::random_generate();
+        weapon.durability = Durability {
+            current: 15,
+            max: 20,
+        };
+        let mut armor = Armor::random_generate();
+        armor.durability = Durability {
+            current: 5,
+            max: 20,
+        };
+        let armor_name = armor.name.clone();
+        inventory.weapon = Some(weapon);
+        inventory.armor = Some(armor);
+
+        // Nails can't fix anything
+        inventory.add_material(MaterialKind::Nails);
+        assert_eq!(inventory.repair(), RepairOutcome::NoScrap);
+
+        inventory.add_material(MaterialKind::DuctTape);
+        assert_eq!(
+            inventory.repair(),
+            RepairOutcome::Repaired {
+                item: armor_name,
+                material: MaterialKind::DuctTape,
+                amount: 10,
+            }
+        );
+        assert_eq!(inventory.material_count(MaterialKind::DuctTape), 0);
+        assert_eq!(inventory.material_count(MaterialKind::Nails), 1);
+        assert_eq!(inventory.armor.as_ref().unwrap().durability.current, 15);
+    }
+
+    #[test]
+    fn test_material_stacking() {
+        let mut inventory = Inventory::new();
+        assert!(inventory.add_material(MaterialKind::Nails));
+        assert!(inventory.add_material(MaterialKind::Nails));
+        assert_eq!(inventory.material_count(MaterialKind::Nails), 2);
+
+        assert!(!inventory.remove_materials(MaterialKind::Nails, 3));
+        assert!(inventory.remove_materials(MaterialKind::Nails, 2));
+        assert!(!inventory.materials.contains_key(&MaterialKind::Nails));
+
+        for _ in 0..JUNK_CAPACITY {
+            assert!(inventory.add_material(MaterialKind::Cloth));
+        }
+        assert!(!inventory.add_material(MaterialKind::Cloth));
+    }
+
+    #[test]
+    fn test_try_add_and_swap() {
+        let mut inventory = Inventory::new();
+        let first = Weapon::new("Crowbar", 5, ItemRarity::Common, "Test");
+        let second = Weapon::new("Metal Pipe", 4, ItemRarity::Common, "Test");
+
+        assert!(inventory.try_add(Item::Weapon(first)).is_ok());
+        let rejected = inventory.try_add(Item::Weapon(second)).unwrap_err();
+        assert_eq!(rejected.name(), "Metal Pipe");
+
+        let dropped = inventory.swap(rejected).unwrap();
+        assert_eq!(dropped.name(), "Crowbar");
+        assert_eq!(inventory.weapon.as_ref().unwrap().name, "Metal Pipe");
+
+        assert!(
+            inventory
+                .try_add(Item::Material(MaterialKind::Nails))
+                .is_ok()
+        );
+        assert_eq!(inventory.material_count(MaterialKind::Nails), 1);
+    }
+
+    #[test]
+    fn test_cash_and_slots() {
+        let mut inventory = Inventory::new();
+        assert!(inventory.try_add(Item::Cash(7)).is_ok());
+        assert!(inventory.try_add(Item::Cash(3)).is_ok());
+        assert_eq!(inventory.cash, 10);
+
+        inventory.add_material(MaterialKind::Wire);
+        inventory.consumable = Some(Consumable::random_generate());
+        assert_eq!(
+            inventory.filled_slots(),
+            vec![Slot::Consumable, Slot::Material(MaterialKind::Wire)]
+        );
+
+        let wire = inventory.take(Slot::Material(MaterialKind::Wire)).unwrap();
+        assert_eq!(wire.value(), 4);
+        assert!(inventory.take(Slot::Material(MaterialKind::Wire)).is_none());
+        assert!(inventory.peek(Slot::Consumable).is_some());
+    }
+
+    #[test]
+    fn test_named_lookups() {
+        assert_eq!(Weapon::named("crowbar").unwrap().damage_bonus, 5);
+        assert_eq!(Armor::named("Kevlar Vest").unwrap().defense_bonus, 5);
+        assert!(Consumable::named("Rat Tail").is_some());
+        assert!(Weapon::named("Lightsaber").is_none());
+        assert_eq!(
+            MaterialKind::from_name("duct tape"),
+            Some(MaterialKind::DuctTape)
+        );
+        assert_eq!(
+            MaterialKind::from_name("Tape"),
+            Some(MaterialKind::DuctTape)
+        );
+    }
 }
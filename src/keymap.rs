@@ -0,0 +1,172 @@
+use crate::game::GameMode;
+use crossterm::event::KeyCode;
+
+/// Something a key does out in the alley.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Action {
+    Walk(i32, i32),
+    Run(i32, i32),
+    Sneak,
+    UseItem,
+    Repair,
+    Craft,
+    PickUp,
+    QuestLog,
+    MessageLog,
+    Achievements,
+    Attack,
+    Retreat,
+    Help,
+    Pause,
+    Quit,
+}
+
+impl Action {
+    /// What the help screen calls it. Actions sharing a label are listed
+    /// together.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Action::Walk(..) => "Move",
+            Action::Run(..) => "Run two tiles (costs stamina)",
+            Action::Sneak => "Sneak on/off",
+            Action::UseItem => "Use your consumable",
+            Action::Repair => "Repair worn gear with scrap",
+            Action::Craft => "Crafting",
+            Action::PickUp => "Swap for the item underfoot",
+            Action::QuestLog => "Quest log",
+            Action::MessageLog => "Message log",
+            Action::Achievements => "Achievements",
+            Action::Attack => "Attack",
+            Action::Retreat => "Retreat (costs stamina)",
+            Action::Help => "This help",
+            Action::Pause => "Pause menu (save, settings)",
+            Action::Quit => "Quit the game",
+        }
+    }
+
+    /// Whether it does anything in `mode`.
+    pub fn works_in(&self, mode: GameMode) -> bool {
+        let exploring = matches!(mode, GameMode::Exploring | GameMode::LevelComplete);
+        let combat = matches!(mode, GameMode::Combat(_));
+        match self {
+            Action::Walk(..)
+            | Action::Run(..)
+            | Action::Repair
+            | Action::Craft
+            | Action::PickUp
+            | Action::QuestLog
+            | Action::Sneak
+            | Action::Achievements => exploring,
+            Action::UseItem | Action::MessageLog => exploring || combat,
+            Action::Attack | Action::Retreat => combat,
+            Action::Help | Action::Pause | Action::Quit => mode.in_run(),
+        }
+    }
+}
+
+/// Every key the alley listens for. The input handlers and the help screen
+/// both read from here, so they can't disagree.
+pub const KEYMAP: [(KeyCode, Action); 26] = [
+    (KeyCode::Char('w'), Action::Walk(0, -1)),
+    (KeyCode::Char('a'), Action::Walk(-1, 0)),
+    (KeyCode::Char('s'), Action::Walk(0, 1)),
+    (KeyCode::Char('d'), Action::Walk(1, 0)),
+    (KeyCode::Up, Action::Walk(0, -1)),
+    (KeyCode::Left, Action::Walk(-1, 0)),
+    (KeyCode::Down, Action::Walk(0, 1)),
+    (KeyCode::Right, Action::Walk(1, 0)),
+    (KeyCode::Char('W'), Action::Run(0, -1)),
+    (KeyCode::Char('A'), Action::Run(-1, 0)),
+    (KeyCode::Char('S'), Action::Run(0, 1)),
+    (KeyCode::Char('D'), Action::Run(1, 0)),
+    (KeyCode::Char(' '), Action::Attack),
+    (KeyCode::Enter, Action::Attack),
+    (KeyCode::Char('r'), Action::Retreat),
+    (KeyCode::Char('z'), Action::Sneak),
+    (KeyCode::Char('e'), Action::UseItem),
+    (KeyCode::Char('f'), Action::Repair),
+    (KeyCode::Char('c'), Action::Craft),
+    (KeyCode::Char('g'), Action::PickUp),
+    (KeyCode::Char('j'), Action::QuestLog),
+    (KeyCode::Char('l'), Action::MessageLog),
+    (KeyCode::Char('v'), Action::Achievements),
+    (KeyCode::Char('?'), Action::Help),
+    (KeyCode::Esc, Action::Pause),
+    (KeyCode::Char('q'), Action::Quit),
+];
+
+/// What `code` does in the alley, if anything.
+pub fn action(code: KeyCode) -> Option<Action> {
+    KEYMAP
+        .iter()
+        .find(|(key, _)| *key == code)
+        .map(|(_, action)| *action)
+}
+
+/// The keys that do something in `mode` and what they do, one line per
+/// label in keymap order, e.g. `("E", "Use your consumable")`.
+pub fn bindings(mode: GameMode) -> Vec<(String, &'static str)> {
+    let mut lines: Vec<(Vec<KeyCode>, &'static str)> = Vec::new();
+    for (key, action) in KEYMAP.iter().filter(|(_, a)| a.works_in(mode)) {
+        match lines.iter_mut().find(|(_, label)| *label == action.label()) {
+            Some((keys, _)) => keys.push(*key),
+            None => lines.push((vec![*key], action.label())),
+        }
+    }
+    lines
+        .into_iter()
+        .map(|(keys, label)| (keys_text(&keys), label))
+        .collect()
+}
+
+/// Keys joined with slashes, with a shared Shift pulled out front.
+fn keys_text(keys: &[KeyCode]) -> String {
+    let shifted = keys
+        .iter()
+        .all(|key| matches!(key, KeyCode::Char(c) if c.is_ascii_uppercase()));
+    let names: Vec<String> = keys.iter().map(|&key| key_name(key)).collect();
+    if shifted {
+        format!("Shift+{}", names.join("/"))
+    } else {
+        names.join("/")
+    }
+}
+
+/// How a key is written on screen.
+pub fn key_name(code: KeyCode) -> String {
+    match code {
+        KeyCode::Char(' ') => "Space".to_string(),
+        KeyCode::Char(c) => c.to_ascii_uppercase().to_string(),
+        KeyCode::Enter => "Enter".to_string(),
+        KeyCode::Esc => "Esc".to_string(),
+        KeyCode::Up => "↑".to_string(),
+        KeyCode::Down => "↓".to_string(),
+        KeyCode::Left => "←".to_string(),
+        KeyCode::Right => "→".to_string(),
+        other => format!("{:?}", other),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_help_lists_what_works_here() {
+        let exploring = bindings(GameMode::Exploring);
+        assert_eq!(exploring[0], ("W/A/S/D/↑/←/↓/→".to_string(), "Move"));
+        assert_eq!(exploring[1].0, "Shift+W/A/S/D");
+        assert!(exploring.iter().all(|(_, label)| *label != "Attack"));
+
+        let combat = bindings(GameMode::Combat(0));
+        assert_eq!(combat[0], ("Space/Enter".to_string(), "Attack"));
+        assert!(combat.iter().all(|(_, label)| *label != "Move"));
+
+        // The alley's still listening once the exit's open
+        let sneak = Action::Sneak.label();
+        assert!(exploring.iter().any(|(_, label)| *label == sneak));
+        let done = bindings(GameMode::LevelComplete);
+        assert!(done.iter().any(|(_, label)| *label == sneak));
+        assert!(bindings(GameMode::Title(0)).is_empty());
+    }
+}
@@ -0,0 +1,101 @@
+use crate::game::Position;
+use crate::items::{Item, Tool};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LockKind {
+    /// A chain wrapped through a gate.
+    Chain,
+    /// A padlocked door or hatch.
+    Padlock,
+    /// A chain-link fence you'd have to cut or pry a hole in.
+    Fence,
+}
+
+impl LockKind {
+    pub fn glyph(&self) -> char {
+        match self {
+            LockKind::Chain => '╳',
+            LockKind::Padlock => '+',
+            LockKind::Fence => '╫',
+        }
+    }
+}
+
+impl fmt::Display for LockKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LockKind::Chain => write!(f, "chain"),
+            LockKind::Padlock => write!(f, "padlock"),
+            LockKind::Fence => write!(f, "fence"),
+        }
+    }
+}
+
+/// Something locked that blocks its tile until you get it open: the exit
+/// gate, a storage locker, a fenced-off corner.
+#[derive(Debug, Clone)]
+pub struct Lock {
+    pub name: &'static str,
+    pub kind: LockKind,
+    pub position: Position,
+    pub open: bool,
+    /// Getting through this one (with the main quest done) wins the level.
+    pub exit: bool,
+    /// Dropped on the tile when it opens.
+    pub contents: Vec<Item>,
+}
+
+impl Lock {
+    pub fn new(name: &'static str, kind: LockKind, x: i32, y: i32) -> Self {
+        Lock {
+            name,
+            kind,
+            position: Position::new(x, y),
+            open: false,
+            exit: false,
+            contents: Vec::new(),
+        }
+    }
+
+    pub fn exit(mut self) -> Self {
+        self.exit = true;
+        self
+    }
+
+    pub fn containing(mut self, contents: Vec<Item>) -> Self {
+        self.contents = contents;
+        self
+    }
+
+    /// The tool with the best odds against this lock, if any of them work.
+    pub fn best_tool(&self, tools: &[Tool]) -> Option<(Tool, u32)> {
+        tools
+            .iter()
+            .filter_map(|tool| tool.chance_against(self.kind).map(|chance| (*tool, chance)))
+            .max_by_key(|(_, chance)| *chance)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_best_tool_for_the_job() {
+        let padlock = Lock::new("Locker", LockKind::Padlock, 1, 1);
+        assert_eq!(padlock.best_tool(&[]), None);
+        assert_eq!(
+            padlock.best_tool(&[Tool::LockPicks, Tool::Crowbar]),
+            Some((Tool::Crowbar, 60))
+        );
+
+        // Picks are no use on a chain
+        let gate = Lock::new("Gate", LockKind::Chain, 1, 1).exit();
+        assert_eq!(gate.best_tool(&[Tool::LockPicks]), None);
+        assert_eq!(
+            gate.best_tool(&[Tool::LockPicks, Tool::BoltCutters]),
+            Some((Tool::BoltCutters, 100))
+        );
+    }
+}
@@ -0,0 +1,124 @@
+use crate::game::{EnemyType, Position};
+use crate::items::{Armor, Consumable, Item, ItemPool, MaterialKind, Weapon};
+use rand::Rng;
+
+/// One row of a loot table: what kind of thing drops.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LootEntry {
+    Nothing,
+    Weapon,
+    Armor,
+    Consumable,
+    /// Something only a rat would carry.
+    Gross,
+    Junk,
+    Cash,
+}
+
+/// Weighted drop table for each enemy archetype. Weights add up to 100.
+pub fn loot_table(enemy_type: &EnemyType) -> &'static [(u32, LootEntry)] {
+    match enemy_type {
+        EnemyType::Rat => &[
+            (60, LootEntry::Nothing),
+            (25, LootEntry::Gross),
+            (10, LootEntry::Junk),
+            (5, LootEntry::Cash),
+        ],
+        EnemyType::FerralCat => &[
+            (55, LootEntry::Nothing),
+            (15, LootEntry::Gross),
+            (30, LootEntry::Junk),
+        ],
+        EnemyType::RivalPunk => &[
+            (10, LootEntry::Nothing),
+            (25, LootEntry::Weapon),
+            (15, LootEntry::Armor),
+            (20, LootEntry::Consumable),
+            (10, LootEntry::Junk),
+            (20, LootEntry::Cash),
+        ],
+    }
+}
+
+/// How many caps an enemy might be carrying.
+fn cash_range(enemy_type: &EnemyType) -> (u32, u32) {
+    match enemy_type {
+        EnemyType::Rat => (1, 3),
+        EnemyType::FerralCat => (1, 2),
+        EnemyType::RivalPunk => (5, 20),
+    }
+}
+
+/// Picks one entry from a weighted table, or None if there's nothing to
+/// pick from.
+pub fn roll_entry<T: Copy>(rng: &mut impl Rng, table: &[(u32, T)]) -> Option<T> {
+    let total: u32 = table.iter().map(|(weight, _)| weight).sum();
+    if total == 0 {
+        return None;
+    }
+    let mut roll = rng.gen_range(0..total);
+    for (weight, entry) in table {
+        if roll < *weight {
+            return Some(*entry);
+        }
+        roll -= weight;
+    }
+    None
+}
+
+/// Rolls what an enemy leaves behind, if anything.
+pub fn roll_loot(rng: &mut impl Rng, enemy_type: &EnemyType) -> Option<Item> {
+    match roll_entry(rng, loot_table(enemy_type))? {
+        LootEntry::Nothing => None,
+        LootEntry::Weapon => Some(Item::Weapon(Weapon::random_from(rng, ItemPool::default()))),
+        LootEntry::Armor => Some(Item::Armor(Armor::random_from(rng, ItemPool::default()))),
+        LootEntry::Consumable => Some(Item::Consumable(Consumable::random(rng))),
+        LootEntry::Gross => Some(Item::Consumable(Consumable::random_gross(rng))),
+        LootEntry::Junk => Some(Item::Material(MaterialKind::random(rng))),
+        LootEntry::Cash => {
+            let (min, max) = cash_range(enemy_type);
+            Some(Item::Cash(rng.gen_range(min..=max)))
+        }
+    }
+}
+
+/// An item lying on the ground, waiting to be picked up.
+#[derive(Debug, Clone)]
+pub struct GroundItem {
+    pub position: Position,
+    pub item: Item,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    #[test]
+    fn test_loot_tables_add_up() {
+        for enemy_type in [EnemyType::Rat, EnemyType::FerralCat, EnemyType::RivalPunk] {
+            let total: u32 = loot_table(&enemy_type).iter().map(|(w, _)| w).sum();
+            assert_eq!(total, 100);
+        }
+    }
+
+    #[test]
+    fn test_rats_never_drop_gear() {
+        let mut rng = StdRng::seed_from_u64(3);
+        for _ in 0..200 {
+            let entry = roll_entry(&mut rng, loot_table(&EnemyType::Rat));
+            assert!(!matches!(entry, Some(LootEntry::Weapon | LootEntry::Armor)));
+        }
+    }
+
+    #[test]
+    fn test_roll_entry_respects_weights() {
+        let mut rng = StdRng::seed_from_u64(11);
+        let table = [(0, LootEntry::Nothing), (100, LootEntry::Junk)];
+        for _ in 0..50 {
+            assert_eq!(roll_entry(&mut rng, &table), Some(LootEntry::Junk));
+        }
+        assert_eq!(roll_entry::<LootEntry>(&mut rng, &[]), None);
+    }
+}
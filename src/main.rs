@@ -1,12 +1,48 @@
+mod achievements;
+mod backgrounds;
+mod clock;
+mod combat;
+mod crafting;
+mod daily;
+mod dialogue;
+mod difficulty;
+mod dumpster;
 mod game;
 mod items;
+mod keymap;
+mod locks;
+mod loot;
+mod messages;
+mod meta;
+mod morgue;
+mod npc;
+mod profile;
+mod progression;
+mod quests;
+mod restock;
+mod save;
+mod scene;
+mod score;
+mod settings;
+mod setup;
+mod spawn;
+mod stats;
+mod stealth;
+mod storage;
+mod survival;
 mod ui;
+mod vendor;
 
 use crossterm::event::{self, Event, KeyCode};
 use std::io::Result;
 use std::time::Duration;
 
+use daily::DailyScores;
 use game::{GameMode, GameState};
+use keymap::Action;
+use profile::Profile;
+use scene::{Leave, Scene};
+use score::HighScores;
 use ui::{Renderer, cleanup_terminal, setup_terminal};
 
 fn main() -> Result<()> {
@@ -17,6 +53,13 @@ fn main() -> Result<()> {
     let width = 60;
     let height = 20;
     let mut game = GameState::new(width, height);
+    let data_dir = storage::data_dir();
+    game.high_scores = HighScores::open(data_dir.join("scores.tsv"));
+    game.daily_scores = DailyScores::open(data_dir.join("daily.tsv"));
+    game.profile = Profile::open(data_dir.join("profile.txt"));
+    game.data_dir = Some(data_dir);
+    // Straight to Continue if there's a run waiting
+    game.mode = GameMode::Title(usize::from(game.has_save()));
 
     // Create renderer
     let renderer = Renderer::new(width as u16, height as u16);
@@ -39,32 +82,29 @@ fn game_loop(game: &mut GameState, renderer: &Renderer) -> Result<()> {
         if event::poll(Duration::from_millis(100))?
             && let Event::Key(key_event) = event::read()?
         {
-            match key_event.code {
-                KeyCode::Char('q') | KeyCode::Esc => {
-                    break;
-                }
-                _ => {
-                    game.handle_input(key_event);
-                }
+            // Pausing, help and leaving sit above the game; everything else
+            // is down to the current scene and mode
+            match game.scene {
+                Scene::Game if game.mode.in_run() => match keymap::action(key_event.code) {
+                    Some(Action::Pause) => game.scene = Scene::Paused(0),
+                    Some(Action::Quit) => game.ask_to_leave(Leave::Quit, false),
+                    Some(Action::Help) => game.scene = Scene::Help,
+                    _ => game.handle_input(key_event),
+                },
+                Scene::Game => match key_event.code {
+                    KeyCode::Char('q') | KeyCode::Esc if !game.mode.is_menu() => {
+                        break;
+                    }
+                    _ => {
+                        game.handle_input(key_event);
+                    }
+                },
+                _ => game.handle_scene_input(key_event),
             }
         }
 
-        // Check for game end conditions
-        match game.mode {
-            GameMode::Victory | GameMode::GameOver => {
-                renderer.render(game)?;
-
-                // Wait for Q to quit
-                loop {
-                    if event::poll(Duration::from_millis(100))?
-                        && let Event::Key(key_event) = event::read()?
-                        && matches!(key_event.code, KeyCode::Char('q') | KeyCode::Esc)
-                    {
-                        return Ok(());
-                    }
-                }
-            }
-            _ => {}
+        if game.mode == GameMode::Quit {
+            break;
         }
     }
 
@@ -0,0 +1,113 @@
+use std::fmt;
+
+/// How many lines the log screen shows at once.
+pub const LOG_PAGE: usize = 15;
+/// How many lines the HUD shows.
+pub const HUD_MESSAGES: usize = 5;
+
+/// What a message is about, for filtering and coloring.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MessageKind {
+    /// Fights, encounters and anything that hurts.
+    Combat,
+    /// Finding, trading, using and making things.
+    Loot,
+    /// Quests, time passing and everything else.
+    System,
+}
+
+impl MessageKind {
+    pub const ALL: [MessageKind; 3] = [MessageKind::Combat, MessageKind::Loot, MessageKind::System];
+
+    /// The filter after `filter` on the log screen, going All, Combat,
+    /// Loot, System and round again.
+    pub fn next_filter(filter: Option<MessageKind>) -> Option<MessageKind> {
+        match filter {
+            None => Some(MessageKind::Combat),
+            Some(MessageKind::Combat) => Some(MessageKind::Loot),
+            Some(MessageKind::Loot) => Some(MessageKind::System),
+            Some(MessageKind::System) => None,
+        }
+    }
+}
+
+impl fmt::Display for MessageKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MessageKind::Combat => write!(f, "Combat"),
+            MessageKind::Loot => write!(f, "Loot"),
+            MessageKind::System => write!(f, "System"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Message {
+    pub text: String,
+    pub kind: MessageKind,
+    /// The turn it happened on.
+    pub turn: u32,
+}
+
+/// Every message from the run, oldest first.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MessageLog {
+    entries: Vec<Message>,
+}
+
+impl MessageLog {
+    pub fn push(&mut self, kind: MessageKind, text: String, turn: u32) {
+        self.entries.push(Message { text, kind, turn });
+    }
+
+    /// The last `count` messages, oldest first.
+    pub fn recent(&self, count: usize) -> &[Message] {
+        &self.entries[self.entries.len().saturating_sub(count)..]
+    }
+
+    /// Every message of the `filter` kind, or all of them for None.
+    pub fn filtered(&self, filter: Option<MessageKind>) -> Vec<&Message> {
+        self.entries
+            .iter()
+            .filter(|message| filter.is_none_or(|kind| message.kind == kind))
+            .collect()
+    }
+
+    /// One page of `filter`'s messages, `scroll` lines up from the newest.
+    pub fn page(&self, filter: Option<MessageKind>, scroll: usize) -> Vec<&Message> {
+        let messages = self.filtered(filter);
+        let end = messages.len().saturating_sub(scroll);
+        messages[end.saturating_sub(LOG_PAGE)..end].to_vec()
+    }
+
+    /// How far up the log can scroll with `filter` on.
+    pub fn max_scroll(&self, filter: Option<MessageKind>) -> usize {
+        self.filtered(filter).len().saturating_sub(LOG_PAGE)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_log_keeps_everything() {
+        let mut log = MessageLog::default();
+        for turn in 0..40 {
+            let kind = MessageKind::ALL[turn as usize % 3];
+            log.push(kind, format!("Message {}", turn), turn);
+        }
+        assert_eq!(log.filtered(None).len(), 40);
+        assert_eq!(log.recent(HUD_MESSAGES)[0].text, "Message 35");
+
+        let combat = log.filtered(Some(MessageKind::Combat));
+        assert_eq!(combat.len(), 14);
+        assert!(combat.iter().all(|m| m.kind == MessageKind::Combat));
+
+        // Scrolled all the way up, the page starts at the oldest message
+        let top = log.page(None, log.max_scroll(None));
+        assert_eq!(top.len(), LOG_PAGE);
+        assert_eq!(top[0].text, "Message 0");
+        assert_eq!(log.page(None, 0).last().unwrap().text, "Message 39");
+    }
+}
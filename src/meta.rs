@@ -0,0 +1,157 @@
+use std::fmt;
+
+/// Cred banked for every this-many points a run scores.
+pub const SCORE_PER_CRED: u32 = 10;
+
+/// What an unlock changes about future runs.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UnlockKind {
+    StartingItem,
+    LootPool,
+    Character,
+    /// A harder alley you can pick to play in.
+    Alley,
+}
+
+impl fmt::Display for UnlockKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // Padded, so it lines up in the unlocks table
+        f.pad(match self {
+            UnlockKind::StartingItem => "Start",
+            UnlockKind::LootPool => "Loot",
+            UnlockKind::Character => "Body",
+            UnlockKind::Alley => "Alley",
+        })
+    }
+}
+
+/// Something bought with cred that sticks around from run to run.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Unlock {
+    PocketKnife,
+    HandMeDowns,
+    FirstAidKit,
+    SalvageWeapons,
+    SalvageArmor,
+    ThickSkin,
+    LoadingDock,
+    GraveyardShift,
+    SkatePunk,
+}
+
+impl Unlock {
+    pub const ALL: [Unlock; 9] = [
+        Unlock::PocketKnife,
+        Unlock::HandMeDowns,
+        Unlock::FirstAidKit,
+        Unlock::SalvageWeapons,
+        Unlock::SalvageArmor,
+        Unlock::ThickSkin,
+        Unlock::LoadingDock,
+        Unlock::GraveyardShift,
+        Unlock::SkatePunk,
+    ];
+
+    /// Stable name for the profile file.
+    pub fn id(&self) -> &'static str {
+        match self {
+            Unlock::PocketKnife => "pocket_knife",
+            Unlock::HandMeDowns => "hand_me_downs",
+            Unlock::FirstAidKit => "first_aid_kit",
+            Unlock::SalvageWeapons => "salvage_weapons",
+            Unlock::SalvageArmor => "salvage_armor",
+            Unlock::ThickSkin => "thick_skin",
+            Unlock::LoadingDock => "loading_dock",
+            Unlock::GraveyardShift => "graveyard_shift",
+            Unlock::SkatePunk => "skate_punk",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Unlock::ALL.into_iter().find(|u| u.id() == id)
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Unlock::PocketKnife => "Pocket Knife",
+            Unlock::HandMeDowns => "Hand-Me-Downs",
+            Unlock::FirstAidKit => "First Aid Kit",
+            Unlock::SalvageWeapons => "Salvage Weapons",
+            Unlock::SalvageArmor => "Salvage Armor",
+            Unlock::ThickSkin => "Thick Skin",
+            Unlock::LoadingDock => "The Loading Dock",
+            Unlock::GraveyardShift => "Graveyard Shift",
+            Unlock::SkatePunk => "Skate Punk",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Unlock::PocketKnife => "Start every run with a switchblade",
+            Unlock::HandMeDowns => "Start every run in a leather jacket",
+            Unlock::FirstAidKit => "Start every run with bandages",
+            Unlock::SalvageWeapons => "Fire axes and nail guns turn up in the bins",
+            Unlock::SalvageArmor => "Hockey pads and bomb suits turn up in the bins",
+            Unlock::ThickSkin => "+10 max HP",
+            Unlock::LoadingDock => "Busier alley, one more punk. Counts as depth 2",
+            Unlock::GraveyardShift => "Swarming alley, two more punks. Counts as depth 3",
+            Unlock::SkatePunk => "Play as a Skate Punk",
+        }
+    }
+
+    pub fn kind(&self) -> UnlockKind {
+        match self {
+            Unlock::PocketKnife | Unlock::HandMeDowns | Unlock::FirstAidKit => {
+                UnlockKind::StartingItem
+            }
+            Unlock::SalvageWeapons | Unlock::SalvageArmor => UnlockKind::LootPool,
+            Unlock::ThickSkin | Unlock::SkatePunk => UnlockKind::Character,
+            Unlock::LoadingDock | Unlock::GraveyardShift => UnlockKind::Alley,
+        }
+    }
+
+    /// What it costs in cred.
+    pub fn cost(&self) -> u32 {
+        match self {
+            Unlock::PocketKnife => 30,
+            Unlock::HandMeDowns => 40,
+            Unlock::FirstAidKit => 30,
+            Unlock::SalvageWeapons => 60,
+            Unlock::SalvageArmor => 60,
+            Unlock::ThickSkin => 80,
+            Unlock::LoadingDock => 100,
+            Unlock::GraveyardShift => 150,
+            Unlock::SkatePunk => 120,
+        }
+    }
+}
+
+/// Why an unlock couldn't be bought.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UnlockError {
+    AlreadyOwned,
+    /// How much more cred it needs.
+    TooPoor(u32),
+}
+
+impl fmt::Display for UnlockError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            UnlockError::AlreadyOwned => write!(f, "You already have that."),
+            UnlockError::TooPoor(short) => write!(f, "You need {} more cred.", short),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ids_round_trip() {
+        for unlock in Unlock::ALL {
+            assert_eq!(Unlock::from_id(unlock.id()), Some(unlock));
+            assert!(unlock.cost() > 0);
+        }
+    }
+}
@@ -0,0 +1,202 @@
+use crate::game::GameState;
+use crate::quests::QuestStatus;
+use std::fmt::Write;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// How much of the message log makes it into a morgue file.
+const MORGUE_MESSAGES: usize = 20;
+
+/// How a run ended, in one line.
+pub fn epitaph(game: &GameState) -> String {
+    match (game.escaped, game.stats.cause_of_death) {
+        (true, _) => format!("Escaped the alley on turn {}", game.turn_count),
+        (false, Some(cause)) => format!("{} on turn {}", cause, game.turn_count),
+        (false, None) => format!("Gave up on turn {}", game.turn_count),
+    }
+}
+
+/// The full write-up of a run, for pasting into chat.
+pub fn morgue_text(game: &GameState, now: SystemTime) -> String {
+    let stats = &game.stats;
+    let player = &game.player;
+    let inv = &player.inventory;
+    let run = game.run_result();
+    let list = |items: &[String]| {
+        if items.is_empty() {
+            "nothing".to_string()
+        } else {
+            items.join(", ")
+        }
+    };
+
+    let mut text = String::new();
+    let _ = writeln!(text, "RUST PUNK - run summary");
+    let _ = writeln!(text, "{} UTC", timestamp(now).replace('_', " "));
+    let _ = writeln!(text, "Seed: {}", game.seed);
+    if let Some(background) = player.background {
+        let _ = writeln!(text, "Background: {}", background);
+    }
+    let _ = writeln!(
+        text,
+        "Difficulty: {} ({}% score)",
+        game.difficulty,
+        game.rules.score_percent()
+    );
+    if stats.knockdowns > 0 {
+        let _ = writeln!(
+            text,
+            "Beaten down and got back up {} times",
+            stats.knockdowns
+        );
+    }
+    let _ = writeln!(text);
+    let _ = writeln!(text, "{}.", epitaph(game));
+    if let Some(killer) = stats.cause_of_death.and_then(|cause| cause.killer()) {
+        let _ = writeln!(text, "Killer: {}", killer);
+    }
+    let _ = writeln!(text, "Score: {}", run.score());
+    let _ = writeln!(
+        text,
+        "Level {} | HP {}/{} | Perks: {}",
+        player.progression.level,
+        player.health,
+        player.max_health,
+        player.progression.perk_summary()
+    );
+    let _ = writeln!(text, "Turns: {}", game.turn_count);
+    let _ = writeln!(text, "Depth: {}", game.depth);
+    let _ = writeln!(text, "Dumpsters scavenged: {}", player.scavenged_items);
+    let _ = writeln!(text);
+    let _ = writeln!(text, "Damage dealt: {}", stats.damage_dealt);
+    let _ = writeln!(text, "Damage taken: {}", stats.damage_taken);
+    let _ = writeln!(text, "Enemies defeated: {}", stats.kill_summary());
+    let _ = writeln!(text, "Items found: {}", list(&stats.items_found));
+    let _ = writeln!(text, "Items used: {}", list(&stats.items_used));
+    let _ = writeln!(text);
+    let _ = writeln!(text, "Final equipment:");
+    let weapon = match &inv.weapon {
+        Some(w) => format!(
+            "{} (+{}, {}/{})",
+            w.name, w.damage_bonus, w.durability.current, w.durability.max
+        ),
+        None => "[None]".to_string(),
+    };
+    let armor = match &inv.armor {
+        Some(a) => format!(
+            "{} (+{}, {}/{})",
+            a.name, a.defense_bonus, a.durability.current, a.durability.max
+        ),
+        None => "[None]".to_string(),
+    };
+    let _ = writeln!(text, "  Weapon: {}", weapon);
+    let _ = writeln!(text, "  Armor: {}", armor);
+    let _ = writeln!(
+        text,
+        "  Consumable: {}",
+        inv.consumable
+            .as_ref()
+            .map_or("[None]", |c| c.name.as_str())
+    );
+    let tools: Vec<String> = inv.tools.iter().map(|t| t.to_string()).collect();
+    let _ = writeln!(text, "  Tools: {}", list(&tools));
+    let junk: Vec<String> = inv
+        .materials
+        .iter()
+        .map(|(kind, count)| format!("{} x{}", kind, count))
+        .collect();
+    let _ = writeln!(text, "  Junk: {}", list(&junk));
+    let _ = writeln!(text, "  Caps: {}", inv.cash);
+    let _ = writeln!(text);
+    let _ = writeln!(text, "Quests:");
+    for quest in &game.quests.quests {
+        let status = match quest.status {
+            QuestStatus::Inactive => continue,
+            QuestStatus::Active => "unfinished",
+            QuestStatus::Complete => "done",
+            QuestStatus::Failed => "failed",
+        };
+        let _ = writeln!(text, "  {} - {}", quest.title, status);
+    }
+    let _ = writeln!(text);
+    let _ = writeln!(text, "Last messages:");
+    for message in game.messages.recent(MORGUE_MESSAGES) {
+        let _ = writeln!(text, "  [{:>4}] {}", message.turn, message.text);
+    }
+    text
+}
+
+/// Writes a morgue file into `dir`, named for when the run ended.
+pub fn write_morgue(dir: &Path, text: &str, now: SystemTime) -> io::Result<PathBuf> {
+    fs::create_dir_all(dir)?;
+    let path = dir.join(format!("morgue_{}.txt", timestamp(now)));
+    fs::write(&path, text)?;
+    Ok(path)
+}
+
+/// UTC date and time like `2024-03-01_13-05-09`, safe to use in a file name.
+pub fn timestamp(now: SystemTime) -> String {
+    let secs = now.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+    let (days, secs) = (secs / 86_400, secs % 86_400);
+
+    // Days since 1970 to a civil date (Howard Hinnant's algorithm)
+    let z = days as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02}_{:02}-{:02}-{:02}",
+        year,
+        month,
+        day,
+        secs / 3_600,
+        secs % 3_600 / 60,
+        secs % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{EnemyType, GameMode};
+    use crate::stats::DeathCause;
+    use std::time::Duration;
+
+    #[test]
+    fn test_timestamp() {
+        assert_eq!(timestamp(UNIX_EPOCH), "1970-01-01_00-00-00");
+        let leap_day = UNIX_EPOCH + Duration::from_secs(1_709_298_309);
+        assert_eq!(timestamp(leap_day), "2024-03-01_13-05-09");
+    }
+
+    #[test]
+    fn test_morgue_covers_the_run() {
+        let mut game = GameState::with_seed(60, 20, 77);
+        game.stats.defeated_enemy(EnemyType::Rat);
+        game.stats.items_used.push("Bandages".to_string());
+        game.player.health = 0;
+        game.stats.cause_of_death = Some(DeathCause::Killed(EnemyType::RivalPunk));
+        game.mode = GameMode::GameOver;
+
+        let text = morgue_text(&game, UNIX_EPOCH);
+        assert!(text.contains("Seed: 77"));
+        assert!(text.contains("Killed by a Rival Punk on turn 0."));
+        assert!(text.contains("Killer: Rival Punk"));
+        assert!(text.contains("Enemies defeated: 1 Rat"));
+        assert!(text.contains("Items used: Bandages"));
+
+        let dir = std::env::temp_dir().join(format!("rust_punk_morgue_{}", std::process::id()));
+        let path = write_morgue(&dir, &text, UNIX_EPOCH).unwrap();
+        assert!(path.ends_with("morgue_1970-01-01_00-00-00.txt"));
+        assert_eq!(fs::read_to_string(&path).unwrap(), text);
+        let _ = fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,112 @@
+use crate::dialogue::{Condition, DialogueOption, DialogueTree};
+use crate::game::{Enemy, EnemyType, Position};
+use crate::items::Inventory;
+use std::collections::HashSet;
+
+/// Someone in the alley who'd rather talk than fight. Bump into them to
+/// start a conversation.
+#[derive(Debug, Clone)]
+pub struct Npc {
+    pub name: String,
+    pub position: Position,
+    pub glyph: char,
+    pub dialogue: DialogueTree,
+    /// What they turn into if a conversation goes badly.
+    pub fights_as: EnemyType,
+}
+
+impl Npc {
+    /// Dale, on a smoke break by the back door.
+    pub fn fry_cook(x: i32, y: i32) -> Self {
+        Npc {
+            name: "Dale".to_string(),
+            position: Position::new(x, y),
+            glyph: 'D',
+            dialogue: DialogueTree::bundled("fry_cook"),
+            fights_as: EnemyType::RivalPunk,
+        }
+    }
+
+    /// Sarge, camped out at the bottom of the alley.
+    pub fn veteran(x: i32, y: i32) -> Self {
+        Npc {
+            name: "Sarge".to_string(),
+            position: Position::new(x, y),
+            glyph: 'S',
+            dialogue: DialogueTree::bundled("veteran"),
+            fights_as: EnemyType::RivalPunk,
+        }
+    }
+
+    /// The enemy this NPC becomes once you've picked a fight with them.
+    pub fn turn_hostile(&self) -> Enemy {
+        let mut enemy = Enemy::new_rival_punk(self.position.x, self.position.y);
+        enemy.enemy_type = self.fights_as;
+        enemy
+    }
+
+    /// Options the player can pick right now, with their index in the node.
+    pub fn visible_options<'a>(
+        &'a self,
+        node: usize,
+        inventory: &Inventory,
+        reputation: i32,
+        flags: &HashSet<String>,
+    ) -> Vec<(usize, &'a DialogueOption)> {
+        self.dialogue.nodes[node]
+            .options
+            .iter()
+            .enumerate()
+            .filter(|(_, option)| {
+                option
+                    .conditions
+                    .iter()
+                    .all(|c| condition_met(c, inventory, reputation, flags))
+            })
+            .collect()
+    }
+}
+
+pub fn condition_met(
+    condition: &Condition,
+    inventory: &Inventory,
+    reputation: i32,
+    flags: &HashSet<String>,
+) -> bool {
+    match condition {
+        Condition::Reputation(min) => reputation >= *min,
+        Condition::Cash(min) => inventory.cash >= *min,
+        Condition::Has(slot) => inventory.peek(*slot).is_some(),
+        Condition::Flag(flag) => flags.contains(flag),
+        Condition::NotFlag(flag) => !flags.contains(flag),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_options_filtered_by_conditions() {
+        let npc = Npc::fry_cook(0, 0);
+        let mut inventory = Inventory::new();
+        let mut flags = HashSet::new();
+
+        // [food] needs reputation or caps for anything but "Never mind."
+        let food = npc
+            .dialogue
+            .nodes
+            .iter()
+            .position(|n| n.id == "food")
+            .unwrap();
+        assert_eq!(npc.visible_options(food, &inventory, 0, &flags).len(), 1);
+
+        inventory.cash = 5;
+        assert_eq!(npc.visible_options(food, &inventory, 2, &flags).len(), 3);
+
+        // Asking for food again is hidden once Dale has fed you
+        assert_eq!(npc.visible_options(0, &inventory, 0, &flags).len(), 4);
+        flags.insert("dale_fed".to_string());
+        assert_eq!(npc.visible_options(0, &inventory, 0, &flags).len(), 3);
+    }
+}
@@ -0,0 +1,273 @@
+use crate::achievements::Achievement;
+use crate::difficulty::{Difficulty, DifficultySettings};
+use crate::meta::{SCORE_PER_CRED, Unlock, UnlockError, UnlockKind};
+use crate::settings::Settings;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+/// What a player has earned across all their runs, kept in a plain-text
+/// file with one `key value` pair per line.
+#[derive(Debug, Clone, Default)]
+pub struct Profile {
+    pub achievements: Vec<Achievement>,
+    /// Banked from every run's score, spent on unlocks.
+    pub cred: u32,
+    pub runs: u32,
+    pub wins: u32,
+    pub unlocks: Vec<Unlock>,
+    /// The harder alley new runs start in, if one is picked.
+    pub alley: Option<Unlock>,
+    /// What new runs are played on.
+    pub difficulty: Difficulty,
+    /// The rules for Custom difficulty.
+    pub custom: DifficultySettings,
+    /// The last date a scored daily run was started.
+    pub daily: Option<String>,
+    pub settings: Settings,
+    /// Where the profile lives. None keeps it in memory only.
+    path: Option<PathBuf>,
+    /// Why the profile couldn't be written, if it couldn't.
+    pub error: Option<String>,
+}
+
+impl Profile {
+    /// Reads the profile at `path`. Lines it doesn't understand are skipped.
+    pub fn open(path: PathBuf) -> Self {
+        let mut profile = Profile {
+            path: Some(path),
+            ..Default::default()
+        };
+        let text = profile
+            .path
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .unwrap_or_default();
+
+        for line in text.lines() {
+            let Some((key, value)) = line.split_once(' ') else {
+                continue;
+            };
+            let value = value.trim();
+            match key {
+                "achievement" => {
+                    if let Some(achievement) = Achievement::from_id(value)
+                        && !profile.has(achievement)
+                    {
+                        profile.achievements.push(achievement);
+                    }
+                }
+                "unlock" => {
+                    if let Some(unlock) = Unlock::from_id(value)
+                        && !profile.owns(unlock)
+                    {
+                        profile.unlocks.push(unlock);
+                    }
+                }
+                "alley" => profile.alley = Unlock::from_id(value),
+                "difficulty" => profile.difficulty = Difficulty::from_id(value).unwrap_or_default(),
+                "custom" => {
+                    profile.custom = DifficultySettings::from_text(value).unwrap_or_default()
+                }
+                "daily" => profile.daily = Some(value.to_string()),
+                "settings" => profile.settings = Settings::from_text(value).unwrap_or_default(),
+                "cred" => profile.cred = value.parse().unwrap_or(0),
+                "runs" => profile.runs = value.parse().unwrap_or(0),
+                "wins" => profile.wins = value.parse().unwrap_or(0),
+                _ => {}
+            }
+        }
+
+        // Can't play in an alley you don't have
+        if profile.alley.is_some_and(|alley| !profile.owns(alley)) {
+            profile.alley = None;
+        }
+        profile
+    }
+
+    pub fn has(&self, achievement: Achievement) -> bool {
+        self.achievements.contains(&achievement)
+    }
+
+    /// Marks an achievement as earned and saves. Returns false if it
+    /// already was.
+    pub fn unlock(&mut self, achievement: Achievement) -> bool {
+        if self.has(achievement) {
+            return false;
+        }
+        self.achievements.push(achievement);
+        self.save();
+        true
+    }
+
+    pub fn owns(&self, unlock: Unlock) -> bool {
+        self.unlocks.contains(&unlock)
+    }
+
+    /// Spends cred on an unlock and saves.
+    pub fn buy(&mut self, unlock: Unlock) -> Result<(), UnlockError> {
+        if self.owns(unlock) {
+            return Err(UnlockError::AlreadyOwned);
+        }
+        if self.cred < unlock.cost() {
+            return Err(UnlockError::TooPoor(unlock.cost() - self.cred));
+        }
+        self.cred -= unlock.cost();
+        self.unlocks.push(unlock);
+        self.save();
+        Ok(())
+    }
+
+    /// Picks an owned alley for new runs, or goes back to the usual one if
+    /// it was already picked.
+    pub fn toggle_alley(&mut self, alley: Unlock) {
+        if alley.kind() != UnlockKind::Alley || !self.owns(alley) {
+            return;
+        }
+        self.alley = if self.alley == Some(alley) {
+            None
+        } else {
+            Some(alley)
+        };
+        self.save();
+    }
+
+    /// Picks the difficulty for new runs and saves.
+    pub fn set_difficulty(&mut self, difficulty: Difficulty) {
+        self.difficulty = difficulty;
+        self.save();
+    }
+
+    /// Changes one of the custom difficulty settings and saves.
+    pub fn adjust_custom(&mut self, idx: usize, up: bool) {
+        self.custom.adjust(idx, up);
+        self.save();
+    }
+
+    /// Flips one of the settings and saves.
+    pub fn toggle_setting(&mut self, idx: usize) {
+        self.settings.toggle(idx);
+        self.save();
+    }
+
+    /// Uses up the scored daily attempt for `date`, if it's still there,
+    /// and saves. Returns whether it was.
+    pub fn start_daily(&mut self, date: &str) -> bool {
+        if self.daily.as_deref() == Some(date) {
+            return false;
+        }
+        self.daily = Some(date.to_string());
+        self.save();
+        true
+    }
+
+    /// Counts a finished run and banks cred for its score. Returns the cred
+    /// earned.
+    pub fn record_run(&mut self, won: bool, score: u32) -> u32 {
+        let earned = score / SCORE_PER_CRED;
+        self.runs += 1;
+        if won {
+            self.wins += 1;
+        }
+        self.cred += earned;
+        self.save();
+        earned
+    }
+
+    /// Writes the profile out, noting why if it can't.
+    fn save(&mut self) {
+        self.error = self.write().err().map(|err| err.to_string());
+    }
+
+    fn write(&self) -> io::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let mut text = format!(
+            "cred {}\nruns {}\nwins {}\ndifficulty {}\ncustom {}\nsettings {}\n",
+            self.cred,
+            self.runs,
+            self.wins,
+            self.difficulty.id(),
+            self.custom.to_text(),
+            self.settings.to_text()
+        );
+        if let Some(alley) = self.alley {
+            text.push_str(&format!("alley {}\n", alley.id()));
+        }
+        if let Some(daily) = &self.daily {
+            text.push_str(&format!("daily {}\n", daily));
+        }
+        for unlock in &self.unlocks {
+            text.push_str(&format!("unlock {}\n", unlock.id()));
+        }
+        for achievement in &self.achievements {
+            text.push_str(&format!("achievement {}\n", achievement.id()));
+        }
+        fs::write(path, text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profile_survives_a_round_trip() {
+        let path = std::env::temp_dir()
+            .join(format!("rust_punk_profile_{}", std::process::id()))
+            .join("profile.txt");
+        let mut profile = Profile::open(path.clone());
+        assert!(profile.unlock(Achievement::Escapee));
+        assert!(!profile.unlock(Achievement::Escapee));
+        assert!(profile.unlock(Achievement::ByAThread));
+        assert_eq!(profile.record_run(true, 1234), 123);
+        profile.buy(Unlock::LoadingDock).unwrap();
+        profile.toggle_alley(Unlock::LoadingDock);
+        profile.set_difficulty(Difficulty::Custom);
+        profile.adjust_custom(1, true);
+        profile.toggle_setting(0);
+        assert!(profile.start_daily("2024-03-01"));
+        assert_eq!(profile.error, None);
+
+        let reloaded = Profile::open(path.clone());
+        assert_eq!(
+            reloaded.achievements,
+            vec![Achievement::Escapee, Achievement::ByAThread]
+        );
+        assert_eq!(reloaded.cred, 23);
+        assert_eq!((reloaded.runs, reloaded.wins), (1, 1));
+        assert_eq!(reloaded.unlocks, vec![Unlock::LoadingDock]);
+        assert_eq!(reloaded.alley, Some(Unlock::LoadingDock));
+        assert_eq!(reloaded.difficulty, Difficulty::Custom);
+        assert_eq!(reloaded.custom.enemy_attack, 125);
+        assert!(!reloaded.settings.autosave);
+        assert!(!reloaded.clone().start_daily("2024-03-01"));
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn test_unlocks_cost_cred() {
+        let mut profile = Profile {
+            cred: 35,
+            ..Default::default()
+        };
+        assert_eq!(
+            profile.buy(Unlock::HandMeDowns),
+            Err(UnlockError::TooPoor(5))
+        );
+        assert_eq!(profile.buy(Unlock::PocketKnife), Ok(()));
+        assert_eq!(profile.cred, 5);
+        assert_eq!(
+            profile.buy(Unlock::PocketKnife),
+            Err(UnlockError::AlreadyOwned)
+        );
+
+        // Only owned alleys can be picked
+        profile.toggle_alley(Unlock::GraveyardShift);
+        assert_eq!(profile.alley, None);
+    }
+}
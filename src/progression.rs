@@ -0,0 +1,138 @@
+use std::fmt;
+
+/// Base stats gained on every level up.
+pub const LEVEL_UP_HEALTH: i32 = 10;
+pub const LEVEL_UP_ATTACK: i32 = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Perk {
+    ToughSkin,
+    DirtyFighting,
+    ScavengersEye,
+}
+
+impl Perk {
+    pub const ALL: [Perk; 3] = [Perk::ToughSkin, Perk::DirtyFighting, Perk::ScavengersEye];
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Perk::ToughSkin => "+2 defense per rank",
+            Perk::DirtyFighting => "+3 attack per rank",
+            Perk::ScavengersEye => "+10 healing and extra junk from dumpsters per rank",
+        }
+    }
+}
+
+impl fmt::Display for Perk {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Perk::ToughSkin => write!(f, "Tough Skin"),
+            Perk::DirtyFighting => write!(f, "Dirty Fighting"),
+            Perk::ScavengersEye => write!(f, "Scavenger's Eye"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Progression {
+    pub level: u32,
+    pub xp: u32,
+    pub perks: Vec<Perk>,
+    pub pending_perks: u32,
+}
+
+impl Progression {
+    pub fn new() -> Self {
+        Progression {
+            level: 1,
+            xp: 0,
+            perks: Vec::new(),
+            pending_perks: 0,
+        }
+    }
+
+    /// XP needed to go from the current level to the next.
+    pub fn xp_to_next_level(&self) -> u32 {
+        50 * self.level
+    }
+
+    /// Adds XP and returns how many levels were gained.
+    pub fn gain_xp(&mut self, amount: u32) -> u32 {
+        self.xp += amount;
+        let mut levels = 0;
+        while self.xp >= self.xp_to_next_level() {
+            self.xp -= self.xp_to_next_level();
+            self.level += 1;
+            self.pending_perks += 1;
+            levels += 1;
+        }
+        levels
+    }
+
+    /// Spends a pending perk pick. Returns false if there was nothing to spend.
+    pub fn choose_perk(&mut self, perk: Perk) -> bool {
+        if self.pending_perks == 0 {
+            return false;
+        }
+        self.pending_perks -= 1;
+        self.perks.push(perk);
+        true
+    }
+
+    pub fn perk_rank(&self, perk: Perk) -> i32 {
+        self.perks.iter().filter(|p| **p == perk).count() as i32
+    }
+
+    /// Perks with their ranks, e.g. "Tough Skin x2, Dirty Fighting".
+    pub fn perk_summary(&self) -> String {
+        let ranked: Vec<String> = Perk::ALL
+            .iter()
+            .filter_map(|perk| match self.perk_rank(*perk) {
+                0 => None,
+                1 => Some(perk.to_string()),
+                rank => Some(format!("{} x{}", perk, rank)),
+            })
+            .collect();
+        if ranked.is_empty() {
+            "None".to_string()
+        } else {
+            ranked.join(", ")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_level_thresholds() {
+        let mut progression = Progression::new();
+        assert_eq!(progression.gain_xp(40), 0);
+        assert_eq!(progression.level, 1);
+
+        // 50 to reach level 2, with the overflow carried over
+        assert_eq!(progression.gain_xp(20), 1);
+        assert_eq!(progression.level, 2);
+        assert_eq!(progression.xp, 10);
+        assert_eq!(progression.xp_to_next_level(), 100);
+
+        // A big reward can grant several levels at once
+        assert_eq!(progression.gain_xp(240), 2);
+        assert_eq!(progression.level, 4);
+        assert_eq!(progression.pending_perks, 3);
+    }
+
+    #[test]
+    fn test_perk_choice() {
+        let mut progression = Progression::new();
+        assert!(!progression.choose_perk(Perk::ToughSkin));
+
+        progression.gain_xp(150);
+        assert!(progression.choose_perk(Perk::ToughSkin));
+        assert!(progression.choose_perk(Perk::ToughSkin));
+        assert_eq!(progression.perk_rank(Perk::ToughSkin), 2);
+        assert_eq!(progression.perk_rank(Perk::DirtyFighting), 0);
+        assert_eq!(progression.perk_summary(), "Tough Skin x2");
+    }
+}
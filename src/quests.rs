@@ -0,0 +1,404 @@
+use crate::dialogue::ItemSpec;
+use crate::game::{EnemyType, Position};
+use std::fmt;
+
+/// Something that happened in the world that quests might care about.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GameEvent {
+    DumpsterScavenged,
+    EnemyDefeated(EnemyType),
+    /// The player picked something up, by name.
+    ItemFound(String),
+    /// A lock was opened, by name.
+    LockOpened(String),
+    /// An NPC finished a step at `position`.
+    NpcMoved(String, Position),
+    /// An NPC is gone for good (e.g. you picked a fight with them).
+    NpcLost(String),
+    /// Made something at the crafting screen, by name.
+    Crafted(String),
+    /// The player took damage and lived.
+    Hurt,
+    /// The player got out of the level.
+    Escaped,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ObjectiveKind {
+    Scavenge(u32),
+    Defeat(EnemyType, u32),
+    Retrieve(String),
+    /// Get through the named lock.
+    Unlock(String),
+    /// Get `npc` to within a step of `to`, described as `place`.
+    Escort {
+        npc: String,
+        to: Position,
+        place: String,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct Objective {
+    pub kind: ObjectiveKind,
+    pub progress: u32,
+}
+
+impl Objective {
+    pub fn new(kind: ObjectiveKind) -> Self {
+        Objective { kind, progress: 0 }
+    }
+
+    pub fn target(&self) -> u32 {
+        match self.kind {
+            ObjectiveKind::Scavenge(n) | ObjectiveKind::Defeat(_, n) => n,
+            ObjectiveKind::Retrieve(_)
+            | ObjectiveKind::Unlock(_)
+            | ObjectiveKind::Escort { .. } => 1,
+        }
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.progress >= self.target()
+    }
+
+    /// Advances the objective if the event counts towards it.
+    fn record(&mut self, event: &GameEvent) -> bool {
+        if self.is_complete() {
+            return false;
+        }
+
+        let counts = match (&self.kind, event) {
+            (ObjectiveKind::Scavenge(_), GameEvent::DumpsterScavenged) => true,
+            (ObjectiveKind::Defeat(wanted, _), GameEvent::EnemyDefeated(defeated)) => {
+                wanted == defeated
+            }
+            (ObjectiveKind::Retrieve(wanted), GameEvent::ItemFound(found)) => {
+                wanted.eq_ignore_ascii_case(found)
+            }
+            (ObjectiveKind::Unlock(wanted), GameEvent::LockOpened(opened)) => wanted == opened,
+            (ObjectiveKind::Escort { npc, to, .. }, GameEvent::NpcMoved(name, position)) => {
+                npc == name && position.distance_to(to) <= 1.5
+            }
+            _ => false,
+        };
+        if counts {
+            self.progress += 1;
+        }
+        counts
+    }
+}
+
+impl fmt::Display for Objective {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.kind {
+            ObjectiveKind::Scavenge(n) => write!(f, "Scavenge dumpsters ({}/{})", self.progress, n),
+            ObjectiveKind::Defeat(enemy_type, 1) => write!(f, "Defeat the {}", enemy_type),
+            ObjectiveKind::Defeat(enemy_type, n) => {
+                write!(f, "Defeat {}s ({}/{})", enemy_type, self.progress, n)
+            }
+            ObjectiveKind::Retrieve(item) => write!(f, "Find the {}", item),
+            ObjectiveKind::Unlock(lock) => write!(f, "Open the {}", lock),
+            ObjectiveKind::Escort { npc, place, .. } => write!(f, "Escort {} to {}", npc, place),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum QuestStatus {
+    /// Not offered yet; someone has to give it to you.
+    Inactive,
+    Active,
+    Complete,
+    Failed,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Reward {
+    pub xp: u32,
+    pub reputation: i32,
+    pub items: Vec<ItemSpec>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Quest {
+    pub id: &'static str,
+    pub title: &'static str,
+    pub description: &'static str,
+    /// Main quests have to be finished before the level's exit opens.
+    pub main: bool,
+    pub status: QuestStatus,
+    pub objectives: Vec<Objective>,
+    pub reward: Reward,
+}
+
+impl Quest {
+    fn is_done(&self) -> bool {
+        self.objectives.iter().all(Objective::is_complete)
+    }
+
+    /// Who this quest has tagging along behind you, if anyone.
+    fn escorting(&self) -> Option<&str> {
+        self.objectives.iter().find_map(|o| match &o.kind {
+            ObjectiveKind::Escort { npc, .. } if !o.is_complete() => Some(npc.as_str()),
+            _ => None,
+        })
+    }
+}
+
+/// What changed after an event, for the game to report and pay out.
+#[derive(Debug, Clone, PartialEq)]
+pub enum QuestUpdate {
+    Progress { quest: usize, objective: usize },
+    Completed(usize),
+    Failed(usize),
+}
+
+/// The quests for the alley behind the burger place. Each level declares
+/// its own list; the exit opens once every main quest is complete.
+pub fn alley_quests(back_door: Position) -> Vec<Quest> {
+    vec![
+        Quest {
+            id: "dumpster_rights",
+            title: "Dumpster Rights",
+            description: "Clean out the dumpsters and find a way through the chain.",
+            main: true,
+            status: QuestStatus::Active,
+            objectives: vec![
+                Objective::new(ObjectiveKind::Scavenge(3)),
+                Objective::new(ObjectiveKind::Unlock("Chained Gate".to_string())),
+            ],
+            reward: Reward::default(),
+        },
+        Quest {
+            id: "pest_control",
+            title: "Pest Control",
+            description: "The rats are getting bold. Thin them out.",
+            main: false,
+            status: QuestStatus::Active,
+            objectives: vec![Objective::new(ObjectiveKind::Defeat(EnemyType::Rat, 3))],
+            reward: Reward {
+                xp: 20,
+                reputation: 1,
+                items: vec![ItemSpec::Cash(10)],
+            },
+        },
+        Quest {
+            id: "top_dog",
+            title: "Top Dog",
+            description: "A rival punk is working your dumpsters. Show him whose alley this is.",
+            main: false,
+            status: QuestStatus::Active,
+            objectives: vec![Objective::new(ObjectiveKind::Defeat(
+                EnemyType::RivalPunk,
+                1,
+            ))],
+            reward: Reward {
+                xp: 25,
+                reputation: 2,
+                items: vec![ItemSpec::Weapon("Crowbar".to_string())],
+            },
+        },
+        Quest {
+            id: "sarge_escort",
+            title: "Hot Meal",
+            description: "Sarge's knees are shot. Walk him to the back door for some fries.",
+            main: false,
+            status: QuestStatus::Inactive,
+            objectives: vec![Objective::new(ObjectiveKind::Escort {
+                npc: "Sarge".to_string(),
+                to: back_door,
+                place: "the back door".to_string(),
+            })],
+            reward: Reward {
+                xp: 30,
+                reputation: 3,
+                items: vec![ItemSpec::Cash(15)],
+            },
+        },
+        Quest {
+            id: "cutters",
+            title: "Cut Loose",
+            description: "Somebody stashed a pair of bolt cutters in one of these bins.",
+            main: false,
+            status: QuestStatus::Active,
+            objectives: vec![Objective::new(ObjectiveKind::Retrieve(
+                "Bolt Cutters".to_string(),
+            ))],
+            reward: Reward {
+                xp: 15,
+                ..Reward::default()
+            },
+        },
+    ]
+}
+
+#[derive(Debug, Clone)]
+pub struct QuestLog {
+    pub quests: Vec<Quest>,
+}
+
+impl QuestLog {
+    pub fn new(quests: Vec<Quest>) -> Self {
+        QuestLog { quests }
+    }
+
+    /// Hands the player a quest that wasn't active yet.
+    pub fn start(&mut self, id: &str) -> Option<usize> {
+        let idx = self.quests.iter().position(|q| q.id == id)?;
+        if self.quests[idx].status != QuestStatus::Inactive {
+            return None;
+        }
+        self.quests[idx].status = QuestStatus::Active;
+        Some(idx)
+    }
+
+    pub fn record(&mut self, event: &GameEvent) -> Vec<QuestUpdate> {
+        let mut updates = Vec::new();
+
+        for (idx, quest) in self.quests.iter_mut().enumerate() {
+            if quest.status != QuestStatus::Active {
+                continue;
+            }
+
+            if let GameEvent::NpcLost(name) = event
+                && quest.escorting() == Some(name.as_str())
+            {
+                quest.status = QuestStatus::Failed;
+                updates.push(QuestUpdate::Failed(idx));
+                continue;
+            }
+
+            for (objective_idx, objective) in quest.objectives.iter_mut().enumerate() {
+                if objective.record(event) {
+                    updates.push(QuestUpdate::Progress {
+                        quest: idx,
+                        objective: objective_idx,
+                    });
+                }
+            }
+
+            if quest.is_done() {
+                quest.status = QuestStatus::Complete;
+                updates.push(QuestUpdate::Completed(idx));
+            }
+        }
+
+        updates
+    }
+
+    /// True once every main quest is finished and the exit can open.
+    pub fn main_complete(&self) -> bool {
+        self.quests
+            .iter()
+            .filter(|q| q.main)
+            .all(|q| q.status == QuestStatus::Complete)
+    }
+
+    /// Unfinished main objectives, for telling the player what's left.
+    pub fn remaining_main(&self) -> Vec<String> {
+        self.quests
+            .iter()
+            .filter(|q| q.main)
+            .flat_map(|q| q.objectives.iter())
+            .filter(|o| !o.is_complete())
+            .map(|o| o.to_string())
+            .collect()
+    }
+
+    /// NPCs currently following the player on an escort.
+    pub fn escorting(&self) -> Vec<&str> {
+        self.quests
+            .iter()
+            .filter(|q| q.status == QuestStatus::Active)
+            .filter_map(Quest::escorting)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn log() -> QuestLog {
+        QuestLog::new(alley_quests(Position::new(10, 2)))
+    }
+
+    #[test]
+    fn test_main_quest_needs_every_objective() {
+        let mut log = log();
+        for _ in 0..3 {
+            log.record(&GameEvent::DumpsterScavenged);
+        }
+        assert!(!log.main_complete());
+        assert_eq!(
+            log.remaining_main(),
+            vec!["Open the Chained Gate".to_string()]
+        );
+
+        let updates = log.record(&GameEvent::LockOpened("Chained Gate".to_string()));
+        assert!(updates.contains(&QuestUpdate::Completed(0)));
+        assert!(log.main_complete());
+    }
+
+    #[test]
+    fn test_retrieve_matches_item_names() {
+        let mut log = log();
+        let idx = log.quests.iter().position(|q| q.id == "cutters").unwrap();
+        assert_eq!(
+            log.quests[idx].objectives[0].to_string(),
+            "Find the Bolt Cutters"
+        );
+
+        assert!(
+            log.record(&GameEvent::ItemFound("Lock Picks".to_string()))
+                .is_empty()
+        );
+        let updates = log.record(&GameEvent::ItemFound("bolt cutters".to_string()));
+        assert!(updates.contains(&QuestUpdate::Completed(idx)));
+    }
+
+    #[test]
+    fn test_defeat_counts_only_matching_enemies() {
+        let mut log = log();
+        log.record(&GameEvent::EnemyDefeated(EnemyType::FerralCat));
+        log.record(&GameEvent::EnemyDefeated(EnemyType::Rat));
+        assert_eq!(log.quests[1].objectives[0].progress, 1);
+        assert_eq!(log.quests[1].status, QuestStatus::Active);
+    }
+
+    #[test]
+    fn test_escort_starts_inactive_and_can_fail() {
+        let mut log = log();
+        let arrived = GameEvent::NpcMoved("Sarge".to_string(), Position::new(11, 2));
+
+        // Walking Sarge over before he asks doesn't count
+        assert!(log.record(&arrived).is_empty());
+        assert!(log.escorting().is_empty());
+
+        let idx = log.start("sarge_escort").unwrap();
+        assert_eq!(log.escorting(), vec!["Sarge"]);
+        log.record(&GameEvent::NpcLost("Sarge".to_string()));
+        assert_eq!(log.quests[idx].status, QuestStatus::Failed);
+        assert!(log.start("sarge_escort").is_none());
+    }
+
+    #[test]
+    fn test_escort_completes_near_destination() {
+        let mut log = log();
+        let idx = log.start("sarge_escort").unwrap();
+
+        log.record(&GameEvent::NpcMoved(
+            "Sarge".to_string(),
+            Position::new(5, 8),
+        ));
+        assert_eq!(log.quests[idx].status, QuestStatus::Active);
+
+        let updates = log.record(&GameEvent::NpcMoved(
+            "Sarge".to_string(),
+            Position::new(11, 3),
+        ));
+        assert!(updates.contains(&QuestUpdate::Completed(idx)));
+        assert!(log.escorting().is_empty());
+    }
+}
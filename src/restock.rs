@@ -0,0 +1,85 @@
+use crate::game::Position;
+
+/// What happened on one step of a trash run.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TrashRunStep {
+    Walking,
+    /// Reached the dumpster at this index and filled it.
+    Restocked(usize),
+    /// Back inside; the run is over.
+    Done,
+}
+
+/// A burger place employee hauling bags out to the empty dumpsters and
+/// heading back inside once they're all filled.
+#[derive(Debug, Clone)]
+pub struct TrashRun {
+    pub position: Position,
+    door: Position,
+    /// Dumpsters still waiting for a bag, in the order they'll be visited.
+    pub targets: Vec<usize>,
+}
+
+impl TrashRun {
+    pub fn new(door: Position, targets: Vec<usize>) -> Self {
+        TrashRun {
+            position: door,
+            door,
+            targets,
+        }
+    }
+
+    /// Walks one tile towards the next dumpster, or back to the door when
+    /// there are none left. `dumpsters` holds every dumpster's position.
+    pub fn step(&mut self, dumpsters: &[Position]) -> TrashRunStep {
+        let goal = match self.targets.first() {
+            Some(&idx) => dumpsters[idx],
+            None => self.door,
+        };
+
+        if let Some(&idx) = self.targets.first()
+            && self.position.distance_to(&goal) <= 1.5
+        {
+            self.targets.remove(0);
+            return TrashRunStep::Restocked(idx);
+        }
+        if self.targets.is_empty() && self.position == goal {
+            return TrashRunStep::Done;
+        }
+
+        self.position.x += (goal.x - self.position.x).signum();
+        self.position.y += (goal.y - self.position.y).signum();
+        TrashRunStep::Walking
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trash_run_visits_each_dumpster_then_leaves() {
+        let dumpsters = [Position::new(10, 3), Position::new(4, 3)];
+        let mut run = TrashRun::new(Position::new(7, 2), vec![0, 1]);
+
+        let mut steps = Vec::new();
+        loop {
+            let step = run.step(&dumpsters);
+            steps.push(step);
+            if step == TrashRunStep::Done {
+                break;
+            }
+            assert!(steps.len() < 50, "trash run never finished");
+        }
+
+        let restocked: Vec<TrashRunStep> = steps
+            .into_iter()
+            .filter(|s| matches!(s, TrashRunStep::Restocked(_)))
+            .collect();
+        assert_eq!(
+            restocked,
+            vec![TrashRunStep::Restocked(0), TrashRunStep::Restocked(1)]
+        );
+        assert_eq!(run.position, Position::new(7, 2));
+    }
+}
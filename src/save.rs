@@ -0,0 +1,205 @@
+use crate::backgrounds::Background;
+use crate::daily::DailyRun;
+use crate::difficulty::{Difficulty, DifficultySettings};
+use crate::meta::Unlock;
+use crate::profile::Profile;
+use crate::setup::RunSetup;
+use crossterm::event::KeyCode;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Which build wrote a save: the game's version, then a number to bump for
+/// any change that would play the same keys out differently. Saves from
+/// anything else are turned away rather than loaded into a different run.
+const VERSION: &str = concat!(env!("CARGO_PKG_VERSION"), "/1");
+
+/// A run in progress, kept as how it began and every key pressed since.
+/// Runs play out the same from the same seed, so pressing the keys again
+/// puts you right back where you left off.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SaveGame {
+    pub seed: u64,
+    pub background: Option<Background>,
+    /// What the profile had unlocked and picked when the run began.
+    pub unlocks: Vec<Unlock>,
+    pub alley: Option<Unlock>,
+    pub difficulty: Difficulty,
+    pub custom: DifficultySettings,
+    pub daily: Option<DailyRun>,
+    pub keys: Vec<KeyCode>,
+}
+
+impl SaveGame {
+    /// A save for a run starting now from `seed` and the profile.
+    pub fn new(seed: u64, profile: &Profile, background: Option<Background>) -> Self {
+        SaveGame {
+            seed,
+            background,
+            unlocks: profile.unlocks.clone(),
+            alley: profile.alley,
+            difficulty: profile.difficulty,
+            custom: profile.custom,
+            daily: None,
+            keys: Vec::new(),
+        }
+    }
+
+    /// How the run was set up when it began.
+    pub fn setup(&self) -> RunSetup {
+        let mut setup =
+            RunSetup::from_unlocks(&self.unlocks, self.alley, self.difficulty, self.custom);
+        setup.background = self.background;
+        setup
+    }
+
+    /// Plain text with one `key value` pair per line, like the profile.
+    /// The keys all go on one line at the end.
+    pub fn to_text(&self) -> String {
+        let mut text = format!(
+            "version {}\nseed {}\ndifficulty {}\ncustom {}\n",
+            VERSION,
+            self.seed,
+            self.difficulty.id(),
+            self.custom.to_text()
+        );
+        if let Some(background) = self.background {
+            text.push_str(&format!("background {}\n", background.id()));
+        }
+        if let Some(alley) = self.alley {
+            text.push_str(&format!("alley {}\n", alley.id()));
+        }
+        for unlock in &self.unlocks {
+            text.push_str(&format!("unlock {}\n", unlock.id()));
+        }
+        if let Some(daily) = &self.daily {
+            text.push_str(&format!("daily {} {}\n", daily.date, daily.scored as u8));
+        }
+        let keys: Vec<String> = self.keys.iter().filter_map(|&key| key_text(key)).collect();
+        text.push_str(&format!("keys {}\n", keys.join(" ")));
+        text
+    }
+
+    /// Reads a save back. Without a seed, or from another version, there's
+    /// nothing to go on.
+    pub fn from_text(text: &str) -> Option<Self> {
+        let mut save = SaveGame::default();
+        let mut seed = None;
+        let mut version = None;
+        for line in text.lines() {
+            let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+            let value = value.trim();
+            match key {
+                "version" => version = Some(value),
+                "seed" => seed = value.parse().ok(),
+                "difficulty" => save.difficulty = Difficulty::from_id(value).unwrap_or_default(),
+                "custom" => save.custom = DifficultySettings::from_text(value).unwrap_or_default(),
+                "background" => save.background = Background::from_id(value),
+                "alley" => save.alley = Unlock::from_id(value),
+                "unlock" => save.unlocks.extend(Unlock::from_id(value)),
+                "daily" => {
+                    let (date, scored) = value.split_once(' ').unwrap_or((value, "0"));
+                    save.daily = Some(DailyRun {
+                        date: date.to_string(),
+                        scored: scored == "1",
+                    });
+                }
+                "keys" => save.keys = value.split_whitespace().filter_map(key_from_text).collect(),
+                _ => {}
+            }
+        }
+        if version != Some(VERSION) {
+            return None;
+        }
+        save.seed = seed?;
+        Some(save)
+    }
+
+    pub fn write(&self, path: &Path) -> io::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, self.to_text())
+    }
+
+    /// The save at `path`, if there is one and it makes sense.
+    pub fn read(path: &Path) -> Option<Self> {
+        Self::from_text(&fs::read_to_string(path).ok()?)
+    }
+}
+
+/// Keys with names, as written in save files. Everything else is the
+/// character itself.
+const KEY_NAMES: [(KeyCode, &str); 13] = [
+    (KeyCode::Char(' '), "Space"),
+    (KeyCode::Enter, "Enter"),
+    (KeyCode::Esc, "Esc"),
+    (KeyCode::Tab, "Tab"),
+    (KeyCode::Backspace, "Backspace"),
+    (KeyCode::Up, "Up"),
+    (KeyCode::Down, "Down"),
+    (KeyCode::Left, "Left"),
+    (KeyCode::Right, "Right"),
+    (KeyCode::Home, "Home"),
+    (KeyCode::End, "End"),
+    (KeyCode::PageUp, "PageUp"),
+    (KeyCode::PageDown, "PageDown"),
+];
+
+/// How a key is written in a save file, or None for keys the game never
+/// uses.
+fn key_text(code: KeyCode) -> Option<String> {
+    if let Some((_, name)) = KEY_NAMES.iter().find(|(key, _)| *key == code) {
+        return Some(name.to_string());
+    }
+    match code {
+        KeyCode::Char(c) => Some(c.to_string()),
+        _ => None,
+    }
+}
+
+fn key_from_text(text: &str) -> Option<KeyCode> {
+    if let Some((key, _)) = KEY_NAMES.iter().find(|(_, name)| *name == text) {
+        return Some(*key);
+    }
+    let mut chars = text.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Some(KeyCode::Char(c)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_save_round_trip() {
+        let mut profile = Profile::default();
+        profile.cred = 1000;
+        profile.buy(Unlock::PocketKnife).unwrap();
+        profile.set_difficulty(Difficulty::Hard);
+        let mut save = SaveGame::new(42, &profile, Some(Background::DumpsterDiver));
+        save.daily = Some(DailyRun {
+            date: "2024-03-01".to_string(),
+            scored: true,
+        });
+        save.keys = vec![
+            KeyCode::Char('w'),
+            KeyCode::Char(' '),
+            KeyCode::Char('W'),
+            KeyCode::PageUp,
+            KeyCode::Esc,
+        ];
+
+        let loaded = SaveGame::from_text(&save.to_text()).unwrap();
+        assert_eq!(loaded, save);
+        assert_eq!(loaded.setup().starting_items.len(), 1);
+        assert_eq!(loaded.setup().difficulty, Difficulty::Hard);
+        assert_eq!(SaveGame::from_text("keys w a s d\n"), None);
+
+        // Keys from another version would play out differently
+        let text = save.to_text().replace(VERSION, "0.0.0/1");
+        assert_eq!(SaveGame::from_text(&text), None);
+    }
+}
@@ -0,0 +1,50 @@
+/// What has the keys. Most of the time it's the game itself, in whatever
+/// `GameMode` it's in; the other scenes sit on top of it and hand back to
+/// it without touching the run underneath.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Scene {
+    #[default]
+    Game,
+    /// Keys, legend and tips, drawn over the game.
+    Help,
+    Paused(usize), // index of the highlighted option
+    Settings {
+        selected: usize,
+        from_pause: bool,
+    },
+    /// Making sure before leaving a run.
+    Confirm {
+        leave: Leave,
+        from_pause: bool,
+    },
+}
+
+/// Where a player leaving a run is going.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Leave {
+    ToMenu,
+    Quit,
+}
+
+/// Options on the pause menu.
+pub const PAUSE_OPTIONS: [&str; 4] = ["Resume", "Save", "Settings", "Quit to Menu"];
+
+/// Rows on the settings screen: the difficulty, then the profile's
+/// settings.
+pub const SETTINGS_ROWS: usize = 1 + crate::settings::Settings::LABELS.len();
+
+impl Scene {
+    /// Where Esc goes from here: settings and confirmations back to the
+    /// pause menu they came from, everything else back to the game.
+    pub fn back(&self) -> Scene {
+        match self {
+            Scene::Settings {
+                from_pause: true, ..
+            } => Scene::Paused(2),
+            Scene::Confirm {
+                from_pause: true, ..
+            } => Scene::Paused(3),
+            _ => Scene::Game,
+        }
+    }
+}
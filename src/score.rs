@@ -0,0 +1,257 @@
+use crate::difficulty::Difficulty;
+use std::cmp::Reverse;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+/// How many runs the high score table remembers.
+pub const MAX_HIGH_SCORES: usize = 10;
+/// Longest name you can put on the table.
+pub const MAX_NAME_LEN: usize = 12;
+/// Escaping in fewer turns than this earns a speed bonus.
+const PAR_TURNS: u32 = 300;
+
+/// Everything the score is worked out from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunResult {
+    pub won: bool,
+    pub turns: u32,
+    pub hp_left: i32,
+    pub enemies_defeated: u32,
+    pub rarity_points: u32,
+    /// How many levels deep the run got.
+    pub depth: u32,
+    pub difficulty: Difficulty,
+    /// Percent of the usual score the difficulty is worth.
+    pub score_percent: u32,
+}
+
+impl RunResult {
+    /// Kills, loot and depth always count. Escaping is worth a flat bonus
+    /// plus extra for health left and for being quick about it. The total
+    /// is then scaled by the difficulty.
+    pub fn score(&self) -> u32 {
+        let mut score =
+            self.enemies_defeated * 25 + self.rarity_points * 10 + self.depth.max(1) * 100;
+        if self.won {
+            score += 500;
+            score += self.hp_left.max(0) as u32 * 5;
+            score += PAR_TURNS.saturating_sub(self.turns) * 2;
+        } else {
+            // A long fight to the death still beats a quick one
+            score += self.turns / 10;
+        }
+        score * self.score_percent / 100
+    }
+}
+
+/// One line on the high score table.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HighScore {
+    pub name: String,
+    pub score: u32,
+    pub won: bool,
+    pub turns: u32,
+    pub enemies_defeated: u32,
+    pub depth: u32,
+    pub difficulty: Difficulty,
+}
+
+impl HighScore {
+    pub fn new(name: &str, run: &RunResult) -> Self {
+        HighScore {
+            name: name.to_string(),
+            score: run.score(),
+            won: run.won,
+            turns: run.turns,
+            enemies_defeated: run.enemies_defeated,
+            depth: run.depth,
+            difficulty: run.difficulty,
+        }
+    }
+
+    pub fn result(&self) -> &'static str {
+        if self.won { "Escaped" } else { "Died" }
+    }
+
+    /// Tab-separated, so the file pastes straight into a spreadsheet.
+    pub fn to_line(&self) -> String {
+        format!(
+            "{}\t{}\t{}\t{}\t{}\t{}\t{}",
+            self.score,
+            self.name,
+            self.result(),
+            self.turns,
+            self.enemies_defeated,
+            self.depth,
+            self.difficulty.id()
+        )
+    }
+
+    pub fn from_line(line: &str) -> Option<Self> {
+        let fields: Vec<&str> = line.split('\t').collect();
+        // Tables from before difficulties were all played on Normal
+        let (fields, difficulty) = match fields[..] {
+            [ref rest @ .., difficulty] if fields.len() == 7 => {
+                (rest, Difficulty::from_id(difficulty)?)
+            }
+            _ => (&fields[..], Difficulty::Normal),
+        };
+        let [score, name, result, turns, kills, depth] = fields[..] else {
+            return None;
+        };
+        Some(HighScore {
+            name: name.to_string(),
+            score: score.parse().ok()?,
+            won: result == "Escaped",
+            turns: turns.parse().ok()?,
+            enemies_defeated: kills.parse().ok()?,
+            depth: depth.parse().ok()?,
+            difficulty,
+        })
+    }
+}
+
+/// Keeps only letters, digits and a little punctuation, so a name can't
+/// break the file format.
+pub fn is_name_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, ' ' | '-' | '_' | '.' | '\'')
+}
+
+/// The best runs so far, best first, kept in a plain-text file between runs.
+#[derive(Debug, Clone, Default)]
+pub struct HighScores {
+    pub entries: Vec<HighScore>,
+    /// Where the table lives. None keeps it in memory only.
+    path: Option<PathBuf>,
+    /// Where the most recent run landed, if it made the table.
+    pub last_rank: Option<usize>,
+    /// Why the table couldn't be written, if it couldn't.
+    pub error: Option<String>,
+}
+
+impl HighScores {
+    /// Reads the table at `path`. A missing or mangled file just means
+    /// fewer scores.
+    pub fn open(path: PathBuf) -> Self {
+        let entries = fs::read_to_string(&path)
+            .map(|text| text.lines().filter_map(HighScore::from_line).collect())
+            .unwrap_or_default();
+        let mut scores = HighScores {
+            entries,
+            path: Some(path),
+            ..Default::default()
+        };
+        scores.sort();
+        scores
+    }
+
+    /// Whether `score` would make the table.
+    pub fn qualifies(&self, score: u32) -> bool {
+        self.entries.len() < MAX_HIGH_SCORES
+            || self.entries.last().is_some_and(|last| score > last.score)
+    }
+
+    /// Adds a run to the table and writes it out. Returns its rank
+    /// (0 is the top) if it made the cut.
+    pub fn record(&mut self, entry: HighScore) -> Option<usize> {
+        let rank = self
+            .entries
+            .iter()
+            .position(|existing| entry.score > existing.score)
+            .unwrap_or(self.entries.len());
+        if rank < MAX_HIGH_SCORES {
+            self.entries.insert(rank, entry);
+            self.entries.truncate(MAX_HIGH_SCORES);
+            self.last_rank = Some(rank);
+        } else {
+            self.last_rank = None;
+        }
+
+        self.error = self.save().err().map(|err| err.to_string());
+        self.last_rank
+    }
+
+    fn save(&self) -> io::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let mut text = String::from("score\tname\tresult\tturns\tkills\tdepth\tdifficulty\n");
+        for entry in &self.entries {
+            text.push_str(&entry.to_line());
+            text.push('\n');
+        }
+        fs::write(path, text)
+    }
+
+    fn sort(&mut self) {
+        self.entries.sort_by_key(|entry| Reverse(entry.score));
+        self.entries.truncate(MAX_HIGH_SCORES);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(won: bool, turns: u32, kills: u32) -> RunResult {
+        RunResult {
+            won,
+            turns,
+            hp_left: 40,
+            enemies_defeated: kills,
+            rarity_points: 4,
+            depth: 1,
+            difficulty: Difficulty::Normal,
+            score_percent: 100,
+        }
+    }
+
+    #[test]
+    fn test_escaping_quickly_scores_best() {
+        let quick = run(true, 100, 3).score();
+        let slow = run(true, 400, 3).score();
+        let dead = run(false, 100, 3).score();
+        assert!(quick > slow);
+        assert!(slow > dead);
+        assert!(run(false, 100, 5).score() > dead);
+    }
+
+    #[test]
+    fn test_table_keeps_the_best_runs() {
+        let mut scores = HighScores::default();
+        for kills in 0..MAX_HIGH_SCORES as u32 {
+            scores.record(HighScore::new("Punk", &run(false, 50, kills)));
+        }
+        assert_eq!(scores.entries.len(), MAX_HIGH_SCORES);
+        assert!(!scores.qualifies(0));
+
+        let rank = scores.record(HighScore::new("Ace", &run(true, 50, 1)));
+        assert_eq!(rank, Some(0));
+        assert_eq!(scores.entries.len(), MAX_HIGH_SCORES);
+        assert_eq!(scores.entries[0].name, "Ace");
+
+        assert_eq!(
+            scores.record(HighScore::new("Slug", &run(false, 1, 0))),
+            None
+        );
+    }
+
+    #[test]
+    fn test_table_survives_a_round_trip() {
+        let path = std::env::temp_dir()
+            .join(format!("rust_punk_scores_{}", std::process::id()))
+            .join("scores.tsv");
+        let mut scores = HighScores::open(path.clone());
+        scores.record(HighScore::new("Ace", &run(true, 80, 4)));
+        scores.record(HighScore::new("Slug", &run(false, 20, 0)));
+        assert_eq!(scores.error, None);
+
+        let reloaded = HighScores::open(path.clone());
+        assert_eq!(reloaded.entries, scores.entries);
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+}
@@ -0,0 +1,70 @@
+/// Preferences that aren't about any one run, kept in the profile.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Settings {
+    /// Save the run when leaving it instead of throwing it away.
+    pub autosave: bool,
+    /// Ask before leaving a run.
+    pub confirm_quit: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            autosave: true,
+            confirm_quit: true,
+        }
+    }
+}
+
+impl Settings {
+    /// Names of the settings, in the order `toggle` numbers them.
+    pub const LABELS: [&'static str; 2] = ["Autosave on quit", "Confirm quit"];
+
+    pub fn value(&self, idx: usize) -> bool {
+        match idx {
+            0 => self.autosave,
+            _ => self.confirm_quit,
+        }
+    }
+
+    pub fn toggle(&mut self, idx: usize) {
+        match idx {
+            0 => self.autosave = !self.autosave,
+            _ => self.confirm_quit = !self.confirm_quit,
+        }
+    }
+
+    /// Saved as a 1 or 0 for each, in `LABELS` order.
+    pub fn to_text(self) -> String {
+        format!("{} {}", self.autosave as u8, self.confirm_quit as u8)
+    }
+
+    pub fn from_text(text: &str) -> Option<Self> {
+        let values: Vec<bool> = text
+            .split_whitespace()
+            .map(|value| value.parse::<u8>().ok().map(|n| n != 0))
+            .collect::<Option<_>>()?;
+        let [autosave, confirm_quit] = values[..] else {
+            return None;
+        };
+        Some(Settings {
+            autosave,
+            confirm_quit,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_settings_round_trip() {
+        let mut settings = Settings::default();
+        settings.toggle(1);
+        assert!(settings.value(0));
+        assert!(!settings.confirm_quit);
+        assert_eq!(Settings::from_text(&settings.to_text()), Some(settings));
+        assert_eq!(Settings::from_text("1"), None);
+    }
+}
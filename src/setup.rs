@@ -0,0 +1,130 @@
+use crate::backgrounds::Background;
+use crate::difficulty::{Difficulty, DifficultySettings};
+use crate::items::{Armor, Consumable, Item, ItemPool, Weapon};
+use crate::meta::Unlock;
+use crate::profile::Profile;
+
+/// Everything that's decided before a run starts.
+#[derive(Debug, Clone)]
+pub struct RunSetup {
+    /// Put in the player's pockets on turn one.
+    pub starting_items: Vec<Item>,
+    pub pool: ItemPool,
+    pub bonus_health: i32,
+    /// Spawn budget and wave size, in percent of the usual.
+    pub threat_percent: u32,
+    pub extra_punks: u32,
+    /// How many levels deep the alley counts as.
+    pub depth: u32,
+    pub background: Option<Background>,
+    pub difficulty: Difficulty,
+    pub rules: DifficultySettings,
+}
+
+impl Default for RunSetup {
+    fn default() -> Self {
+        RunSetup {
+            starting_items: Vec::new(),
+            pool: ItemPool::default(),
+            bonus_health: 0,
+            threat_percent: 100,
+            extra_punks: 0,
+            depth: 1,
+            background: None,
+            difficulty: Difficulty::Normal,
+            rules: DifficultySettings::default(),
+        }
+    }
+}
+
+impl RunSetup {
+    /// A run with everything the profile has unlocked, in its chosen alley.
+    pub fn from_profile(profile: &Profile) -> Self {
+        Self::from_unlocks(
+            &profile.unlocks,
+            profile.alley,
+            profile.difficulty,
+            profile.custom,
+        )
+    }
+
+    /// A run with `unlocks`, in `alley` if one is picked, on `difficulty`
+    /// with `custom` standing in for Custom.
+    pub fn from_unlocks(
+        unlocks: &[Unlock],
+        alley: Option<Unlock>,
+        difficulty: Difficulty,
+        custom: DifficultySettings,
+    ) -> Self {
+        let mut setup = RunSetup::default();
+        for unlock in unlocks {
+            match unlock {
+                Unlock::PocketKnife => setup
+                    .starting_items
+                    .extend(Weapon::named("Switchblade").map(Item::Weapon)),
+                Unlock::HandMeDowns => setup
+                    .starting_items
+                    .extend(Armor::named("Leather Jacket").map(Item::Armor)),
+                Unlock::FirstAidKit => setup
+                    .starting_items
+                    .extend(Consumable::named("Bandages").map(Item::Consumable)),
+                Unlock::SalvageWeapons => setup.pool.salvage_weapons = true,
+                Unlock::SalvageArmor => setup.pool.salvage_armor = true,
+                Unlock::ThickSkin => setup.bonus_health += 10,
+                // Picked on the character screen instead
+                Unlock::SkatePunk => {}
+                // Only count if they're the alley picked
+                Unlock::LoadingDock | Unlock::GraveyardShift => {}
+            }
+        }
+
+        match alley {
+            Some(Unlock::LoadingDock) => {
+                setup.threat_percent = 150;
+                setup.extra_punks = 1;
+                setup.depth = 2;
+            }
+            Some(Unlock::GraveyardShift) => {
+                setup.threat_percent = 200;
+                setup.extra_punks = 2;
+                setup.depth = 3;
+            }
+            _ => {}
+        }
+
+        setup.difficulty = difficulty;
+        setup.rules = difficulty.settings(custom);
+        setup
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_setup_follows_the_profile() {
+        let mut profile = Profile::default();
+        assert_eq!(RunSetup::from_profile(&profile).depth, 1);
+
+        profile.cred = 1000;
+        for unlock in [
+            Unlock::PocketKnife,
+            Unlock::SalvageArmor,
+            Unlock::LoadingDock,
+        ] {
+            profile.buy(unlock).unwrap();
+        }
+        let setup = RunSetup::from_profile(&profile);
+        assert_eq!(setup.starting_items.len(), 1);
+        assert!(setup.pool.salvage_armor);
+        assert!(!setup.pool.salvage_weapons);
+        // Owning an alley isn't the same as playing in it
+        assert_eq!(setup.depth, 1);
+
+        profile.toggle_alley(Unlock::LoadingDock);
+        let setup = RunSetup::from_profile(&profile);
+        assert_eq!(setup.depth, 2);
+        assert_eq!(setup.extra_punks, 1);
+    }
+}
@@ -0,0 +1,259 @@
+use crate::clock::Phase;
+use crate::game::{EnemyType, Position};
+use crate::loot::roll_entry;
+use rand::Rng;
+
+/// Where new enemies crawl out from.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SpawnPointKind {
+    /// Rats and cats come up out of the sewers.
+    SewerGrate,
+    /// A gap in the alley wall that anything can wander through.
+    AlleyMouth,
+}
+
+impl SpawnPointKind {
+    /// What comes through here at this time of day.
+    pub fn spawn_table(&self, phase: Phase) -> &'static [(u32, EnemyType)] {
+        match self {
+            SpawnPointKind::SewerGrate => &[(80, EnemyType::Rat), (20, EnemyType::FerralCat)],
+            SpawnPointKind::AlleyMouth => phase.spawn_table(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpawnPoint {
+    pub kind: SpawnPointKind,
+    pub position: Position,
+}
+
+impl SpawnPoint {
+    pub fn new(kind: SpawnPointKind, x: i32, y: i32) -> Self {
+        SpawnPoint {
+            kind,
+            position: Position::new(x, y),
+        }
+    }
+}
+
+/// How hard a level pushes back.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpawnConfig {
+    /// Total threat (see `EnemyType::threat`) allowed on the map at once.
+    pub budget: u32,
+    /// Turns between reinforcement waves.
+    pub wave_interval: u32,
+    /// Most enemies a single wave can bring.
+    pub wave_size: u32,
+    /// How far a noise carries to a spawn point.
+    pub noise_reach: f32,
+    /// Turns a spawn point stays quiet after answering a noise.
+    pub noise_cooldown: u32,
+}
+
+impl SpawnConfig {
+    /// The alley behind the burger place.
+    pub fn alley() -> Self {
+        SpawnConfig {
+            budget: 10,
+            wave_interval: 40,
+            wave_size: 3,
+            noise_reach: 12.0,
+            noise_cooldown: 15,
+        }
+    }
+
+    /// Scales the budget and wave size by `percent`, for harder or easier
+    /// runs. Waves also come faster the higher it goes.
+    pub fn scaled(self, percent: u32) -> Self {
+        let scale = |n: u32| (n * percent / 100).max(1);
+        SpawnConfig {
+            budget: scale(self.budget),
+            wave_interval: (self.wave_interval * 100 / percent.max(1)).max(10),
+            wave_size: scale(self.wave_size),
+            ..self
+        }
+    }
+}
+
+/// Decides when and where reinforcements show up, so the alley never goes
+/// quiet for good.
+#[derive(Debug, Clone)]
+pub struct SpawnDirector {
+    pub points: Vec<SpawnPoint>,
+    pub config: SpawnConfig,
+    next_wave: u32,
+    /// A noise that hasn't been answered yet.
+    heard: Option<Position>,
+    /// Turn each point can next answer a noise.
+    quiet_until: Vec<u32>,
+}
+
+impl SpawnDirector {
+    pub fn new(points: Vec<SpawnPoint>, config: SpawnConfig) -> Self {
+        SpawnDirector {
+            quiet_until: vec![0; points.len()],
+            points,
+            config,
+            next_wave: config.wave_interval,
+            heard: None,
+        }
+    }
+
+    /// Something loud happened at `position`.
+    pub fn hear(&mut self, position: Position) {
+        self.heard = Some(position);
+    }
+
+    /// Works out who shows up this turn and where. `alive_threat` is what's
+    /// already on the map and `hidden` says whether a point is out of the
+    /// player's sight, since nothing spawns where you're looking.
+    pub fn plan(
+        &mut self,
+        turn: u32,
+        phase: Phase,
+        alive_threat: u32,
+        rng: &mut impl Rng,
+        hidden: impl Fn(Position) -> bool,
+    ) -> Vec<(Position, EnemyType)> {
+        let mut spawns = Vec::new();
+        let mut threat = alive_threat;
+        let candidates: Vec<usize> = (0..self.points.len())
+            .filter(|&idx| hidden(self.points[idx].position))
+            .collect();
+
+        // A noise draws out whatever's lurking at the nearest point in earshot
+        if let Some(noise) = self.heard.take() {
+            let nearest = candidates
+                .iter()
+                .copied()
+                .filter(|&idx| turn >= self.quiet_until[idx])
+                .filter(|&idx| {
+                    self.points[idx].position.distance_to(&noise) <= self.config.noise_reach
+                })
+                .min_by(|&a, &b| {
+                    let da = self.points[a].position.distance_to(&noise);
+                    let db = self.points[b].position.distance_to(&noise);
+                    da.total_cmp(&db)
+                });
+            if let Some(idx) = nearest
+                && let Some(spawn) = self.roll(idx, phase, &mut threat, rng)
+            {
+                self.quiet_until[idx] = turn + self.config.noise_cooldown;
+                spawns.push(spawn);
+            }
+        }
+
+        // Waves arrive on a timer, thinner when it's light out
+        if turn >= self.next_wave {
+            self.next_wave = turn + self.config.wave_interval;
+            if !candidates.is_empty() {
+                for _ in 0..self.config.wave_size {
+                    if !rng.gen_bool(phase.spawn_chance()) {
+                        continue;
+                    }
+                    let idx = candidates[rng.gen_range(0..candidates.len())];
+                    if let Some(spawn) = self.roll(idx, phase, &mut threat, rng) {
+                        spawns.push(spawn);
+                    }
+                }
+            }
+        }
+
+        spawns
+    }
+
+    /// Picks an enemy for a spawn point if the budget has room for it.
+    fn roll(
+        &self,
+        idx: usize,
+        phase: Phase,
+        threat: &mut u32,
+        rng: &mut impl Rng,
+    ) -> Option<(Position, EnemyType)> {
+        let point = &self.points[idx];
+        let enemy_type = roll_entry(rng, point.kind.spawn_table(phase))?;
+
+        if *threat + enemy_type.threat() > self.config.budget {
+            return None;
+        }
+        *threat += enemy_type.threat();
+        Some((point.position, enemy_type))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    fn director() -> SpawnDirector {
+        SpawnDirector::new(
+            vec![
+                SpawnPoint::new(SpawnPointKind::SewerGrate, 5, 5),
+                SpawnPoint::new(SpawnPointKind::AlleyMouth, 40, 15),
+            ],
+            SpawnConfig::alley(),
+        )
+    }
+
+    #[test]
+    fn test_waves_respect_the_budget() {
+        let mut director = director();
+        let mut rng = StdRng::seed_from_u64(3);
+        let wave_turn = director.config.wave_interval;
+
+        assert!(
+            director
+                .plan(1, Phase::Night, 0, &mut rng, |_| true)
+                .is_empty()
+        );
+
+        // Already at budget, so nothing comes
+        let budget = director.config.budget;
+        assert!(
+            director
+                .plan(wave_turn, Phase::Night, budget, &mut rng, |_| true)
+                .is_empty()
+        );
+
+        // The next wave has room
+        let mut total = 0;
+        for wave in 2..10 {
+            let spawns = director.plan(wave_turn * wave, Phase::Night, 0, &mut rng, |_| true);
+            let threat: u32 = spawns.iter().map(|(_, t)| t.threat()).sum();
+            assert!(threat <= budget);
+            total += spawns.len();
+        }
+        assert!(total > 0);
+    }
+
+    #[test]
+    fn test_noise_draws_from_nearest_point() {
+        let mut director = director();
+        let mut rng = StdRng::seed_from_u64(1);
+
+        director.hear(Position::new(7, 6));
+        let spawns = director.plan(1, Phase::Day, 0, &mut rng, |_| true);
+        assert_eq!(spawns.len(), 1);
+        assert_eq!(spawns[0].0, Position::new(5, 5));
+
+        // That grate needs a breather before it answers again
+        director.hear(Position::new(7, 6));
+        assert!(
+            director
+                .plan(2, Phase::Day, 0, &mut rng, |_| true)
+                .is_empty()
+        );
+
+        // Nothing crawls out where the player can see
+        director.hear(Position::new(38, 14));
+        assert!(
+            director
+                .plan(3, Phase::Day, 0, &mut rng, |_| false)
+                .is_empty()
+        );
+    }
+}
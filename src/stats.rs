@@ -0,0 +1,133 @@
+use crate::dumpster::Hazard;
+use crate::game::EnemyType;
+use crate::items::{Item, ItemRarity};
+use std::fmt;
+
+/// What finished the player off.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DeathCause {
+    Killed(EnemyType),
+    Hazard(Hazard),
+    Starvation,
+    Infection,
+}
+
+impl DeathCause {
+    pub fn killer(&self) -> Option<EnemyType> {
+        match self {
+            DeathCause::Killed(enemy_type) => Some(*enemy_type),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for DeathCause {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DeathCause::Killed(enemy_type) => write!(f, "Killed by a {}", enemy_type),
+            DeathCause::Hazard(Hazard::Burn(_)) => write!(f, "Burned in a grease bin"),
+            DeathCause::Hazard(Hazard::Cut(_)) => write!(f, "Cut open on broken glass"),
+            DeathCause::Hazard(Hazard::Crush(_)) => write!(f, "Crushed in a compactor"),
+            DeathCause::Hazard(Hazard::Shock(_)) => write!(f, "Electrocuted by e-waste"),
+            DeathCause::Hazard(Hazard::Infection(_)) | DeathCause::Infection => {
+                write!(f, "Died of an infected wound")
+            }
+            DeathCause::Starvation => write!(f, "Starved behind the burger place"),
+        }
+    }
+}
+
+/// Tallies kept over a run for the end screens, the score and the morgue file.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RunStats {
+    /// Kills by enemy type, in the order they were first killed.
+    pub kills: Vec<(EnemyType, u32)>,
+    /// Points for every weapon and armor picked up, by rarity.
+    pub rarity_points: u32,
+    pub damage_dealt: u32,
+    pub damage_taken: u32,
+    pub items_found: Vec<String>,
+    pub items_used: Vec<String>,
+    pub cause_of_death: Option<DeathCause>,
+    /// Times beaten down and back up again, without permadeath.
+    pub knockdowns: u32,
+}
+
+impl RunStats {
+    pub fn defeated_enemy(&mut self, enemy_type: EnemyType) {
+        match self.kills.iter_mut().find(|(kind, _)| *kind == enemy_type) {
+            Some((_, count)) => *count += 1,
+            None => self.kills.push((enemy_type, 1)),
+        }
+    }
+
+    pub fn enemies_defeated(&self) -> u32 {
+        self.kills.iter().map(|(_, count)| count).sum()
+    }
+
+    /// Like "2 Rat, 1 Feral Cat".
+    pub fn kill_summary(&self) -> String {
+        if self.kills.is_empty() {
+            return "nobody".to_string();
+        }
+        self.kills
+            .iter()
+            .map(|(enemy_type, count)| format!("{} {}", count, enemy_type))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    fn found_rarity(&mut self, rarity: &ItemRarity) {
+        self.rarity_points += match rarity {
+            ItemRarity::Common => 1,
+            ItemRarity::Uncommon => 3,
+            ItemRarity::Rare => 6,
+        };
+    }
+
+    /// Counts an item that just went into the player's pockets. Junk and
+    /// caps don't make the list.
+    pub fn found_item(&mut self, item: &Item) {
+        match item {
+            Item::Weapon(weapon) => self.found_rarity(&weapon.rarity),
+            Item::Armor(armor) => self.found_rarity(&armor.rarity),
+            Item::Material(_) | Item::Cash(_) => return,
+            _ => {}
+        }
+        self.items_found.push(item.name());
+    }
+
+    pub fn dealt_damage(&mut self, amount: i32) {
+        self.damage_dealt += amount.max(0) as u32;
+    }
+
+    pub fn took_damage(&mut self, amount: i32) {
+        self.damage_taken += amount.max(0) as u32;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::items::Consumable;
+
+    #[test]
+    fn test_kills_are_tallied_by_type() {
+        let mut stats = RunStats::default();
+        assert_eq!(stats.kill_summary(), "nobody");
+
+        stats.defeated_enemy(EnemyType::Rat);
+        stats.defeated_enemy(EnemyType::FerralCat);
+        stats.defeated_enemy(EnemyType::Rat);
+        assert_eq!(stats.enemies_defeated(), 3);
+        assert_eq!(stats.kill_summary(), "2 Rat, 1 Feral Cat");
+
+        stats.found_item(&Item::Cash(5));
+        stats.found_item(&Item::Consumable(Consumable::named("Bandages").unwrap()));
+        assert_eq!(stats.items_found, vec!["Bandages".to_string()]);
+        assert_eq!(
+            DeathCause::Killed(EnemyType::Rat).killer(),
+            Some(EnemyType::Rat)
+        );
+    }
+}
@@ -0,0 +1,157 @@
+use crate::game::Position;
+
+/// Turns a suspicious enemy keeps looking before it gives up.
+const SUSPICION_TURNS: u32 = 8;
+/// Turns a hunting enemy can go without sensing you before it loses track.
+const LOSE_TRACK_TURNS: u32 = 5;
+/// Extra accuracy for hitting an enemy that hasn't noticed you. Damage doubles too.
+pub const SNEAK_ATTACK_ACCURACY: i32 = 40;
+
+/// Something the player did that makes a racket.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NoiseSource {
+    Sneaking,
+    Walking,
+    Running,
+    Rummaging,
+    Fighting,
+    Hazard,
+    Picking,
+    Cutting,
+    Prying,
+}
+
+impl NoiseSource {
+    /// How many tiles away an enemy can hear it.
+    pub fn radius(&self) -> f32 {
+        match self {
+            NoiseSource::Sneaking => 1.5,
+            NoiseSource::Picking => 2.0,
+            NoiseSource::Walking => 4.0,
+            NoiseSource::Rummaging | NoiseSource::Cutting => 6.0,
+            NoiseSource::Running => 8.0,
+            NoiseSource::Hazard | NoiseSource::Prying => 9.0,
+            NoiseSource::Fighting => 10.0,
+        }
+    }
+
+    /// Loud enough to draw reinforcements out of the spawn points.
+    pub fn carries(&self) -> bool {
+        self.radius() > 8.0
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Noise {
+    pub position: Position,
+    pub radius: f32,
+}
+
+/// How far an enemy can spot you by sight. Keeping low halves it.
+pub fn spot_range(aggro_range: f32, sneaking: bool) -> f32 {
+    if sneaking {
+        aggro_range * 0.3
+    } else {
+        aggro_range * 0.6
+    }
+}
+
+/// What an enemy knows about you.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Awareness {
+    /// Minding its own business. Open to a sneak attack.
+    Unaware,
+    /// Heard something and is going to have a look.
+    Suspicious { noise: Position, turns_left: u32 },
+    /// Knows exactly where you are and is coming for you.
+    Hunting { turns_unsensed: u32 },
+}
+
+impl Awareness {
+    /// Moves one turn on given what the enemy saw and heard. `player` is
+    /// where it last guesses you are if it loses track.
+    pub fn update(self, sees_player: bool, heard: Option<Position>, player: Position) -> Self {
+        if sees_player {
+            return Awareness::Hunting { turns_unsensed: 0 };
+        }
+
+        match self {
+            Awareness::Unaware => match heard {
+                Some(noise) => Awareness::Suspicious {
+                    noise,
+                    turns_left: SUSPICION_TURNS,
+                },
+                None => Awareness::Unaware,
+            },
+            // Hearing it twice is enough to be sure
+            Awareness::Suspicious { .. } if heard.is_some() => {
+                Awareness::Hunting { turns_unsensed: 0 }
+            }
+            Awareness::Suspicious { turns_left, .. } if turns_left <= 1 => Awareness::Unaware,
+            Awareness::Suspicious { noise, turns_left } => Awareness::Suspicious {
+                noise,
+                turns_left: turns_left - 1,
+            },
+            Awareness::Hunting { .. } if heard.is_some() => {
+                Awareness::Hunting { turns_unsensed: 0 }
+            }
+            Awareness::Hunting { turns_unsensed } if turns_unsensed + 1 >= LOSE_TRACK_TURNS => {
+                Awareness::Suspicious {
+                    noise: player,
+                    turns_left: SUSPICION_TURNS,
+                }
+            }
+            Awareness::Hunting { turns_unsensed } => Awareness::Hunting {
+                turns_unsensed: turns_unsensed + 1,
+            },
+        }
+    }
+
+    /// Drawn over the enemy's head.
+    pub fn marker(&self) -> Option<char> {
+        match self {
+            Awareness::Unaware => None,
+            Awareness::Suspicious { .. } => Some('?'),
+            Awareness::Hunting { .. } => Some('!'),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_noise_raises_suspicion_then_a_hunt() {
+        let player = Position::new(5, 5);
+        let noise = Some(Position::new(4, 4));
+
+        let awareness = Awareness::Unaware.update(false, noise, player);
+        assert_eq!(awareness.marker(), Some('?'));
+        let awareness = awareness.update(false, noise, player);
+        assert_eq!(awareness, Awareness::Hunting { turns_unsensed: 0 });
+    }
+
+    #[test]
+    fn test_enemies_calm_down_when_you_go_quiet() {
+        let player = Position::new(5, 5);
+        let mut awareness = Awareness::Hunting { turns_unsensed: 0 };
+        for _ in 0..LOSE_TRACK_TURNS {
+            awareness = awareness.update(false, None, player);
+        }
+        assert!(matches!(awareness, Awareness::Suspicious { .. }));
+
+        for _ in 0..SUSPICION_TURNS {
+            awareness = awareness.update(false, None, player);
+        }
+        assert_eq!(awareness, Awareness::Unaware);
+    }
+
+    #[test]
+    fn test_sneaking_is_quieter_and_harder_to_spot() {
+        assert!(NoiseSource::Sneaking.radius() < NoiseSource::Walking.radius());
+        assert!(spot_range(10.0, true) < spot_range(10.0, false));
+        assert!(NoiseSource::Fighting.carries());
+        assert!(!NoiseSource::Running.carries());
+    }
+}
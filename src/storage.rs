@@ -0,0 +1,19 @@
+use std::env;
+use std::path::PathBuf;
+
+/// Where the game keeps files that outlive a run, like the high score table.
+///
+/// `RUST_PUNK_DATA_DIR` wins if it's set, so a team can point everyone at a
+/// shared folder. Otherwise it's the platform's usual per-user data directory.
+pub fn data_dir() -> PathBuf {
+    if let Some(dir) = env::var_os("RUST_PUNK_DATA_DIR") {
+        return PathBuf::from(dir);
+    }
+
+    let base = env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("APPDATA").map(PathBuf::from))
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")))
+        .unwrap_or_else(|| PathBuf::from("."));
+    base.join("rust_punk")
+}
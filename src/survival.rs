@@ -0,0 +1,150 @@
+pub const MAX_HUNGER: i32 = 100;
+pub const MAX_STAMINA: i32 = 100;
+
+/// Turns between each point of hunger lost.
+const HUNGER_INTERVAL: u32 = 2;
+/// Stamina regained on a turn spent walking or standing still.
+const STAMINA_REGEN: i32 = 3;
+
+pub const RUN_COST: i32 = 8;
+pub const ATTACK_COST: i32 = 5;
+
+const HUNGRY_BELOW: i32 = 25;
+const WINDED_BELOW: i32 = 20;
+
+/// Food in your belly and wind in your lungs. Both start full.
+#[derive(Debug, Clone)]
+pub struct Survival {
+    /// 100 is stuffed, 0 is starving.
+    pub hunger: i32,
+    pub stamina: i32,
+}
+
+impl Survival {
+    pub fn new() -> Self {
+        Survival {
+            hunger: MAX_HUNGER,
+            stamina: MAX_STAMINA,
+        }
+    }
+
+    /// Advances one turn. Running turns don't recover stamina.
+    pub fn tick(&mut self, turn: u32, running: bool) {
+        if turn.is_multiple_of(HUNGER_INTERVAL) {
+            self.hunger = (self.hunger - 1).max(0);
+        }
+        // An empty stomach won't get your breath back
+        if !running && !self.is_starving() {
+            self.stamina = (self.stamina + STAMINA_REGEN).min(MAX_STAMINA);
+        }
+    }
+
+    /// Spends stamina if there's enough of it.
+    pub fn exert(&mut self, cost: i32) -> bool {
+        if self.stamina < cost {
+            return false;
+        }
+        self.stamina -= cost;
+        true
+    }
+
+    /// Spends stamina, bottoming out at zero.
+    pub fn tire(&mut self, cost: i32) {
+        self.stamina = (self.stamina - cost).max(0);
+    }
+
+    pub fn eat(&mut self, hunger: i32, stamina: i32) {
+        self.hunger = (self.hunger + hunger).min(MAX_HUNGER);
+        self.stamina = (self.stamina + stamina).min(MAX_STAMINA);
+    }
+
+    pub fn is_hungry(&self) -> bool {
+        self.hunger < HUNGRY_BELOW
+    }
+
+    pub fn is_starving(&self) -> bool {
+        self.hunger == 0
+    }
+
+    pub fn is_winded(&self) -> bool {
+        self.stamina < WINDED_BELOW
+    }
+
+    /// Attack lost to an empty stomach.
+    pub fn attack_penalty(&self) -> i32 {
+        if self.is_starving() {
+            4
+        } else if self.is_hungry() {
+            2
+        } else {
+            0
+        }
+    }
+
+    /// Accuracy lost to being out of breath.
+    pub fn accuracy_penalty(&self) -> i32 {
+        if self.is_winded() { 15 } else { 0 }
+    }
+
+    pub fn hunger_label(&self) -> &'static str {
+        if self.is_starving() {
+            "Starving"
+        } else if self.is_hungry() {
+            "Hungry"
+        } else if self.hunger < 60 {
+            "Peckish"
+        } else {
+            "Fed"
+        }
+    }
+
+    pub fn stamina_label(&self) -> &'static str {
+        if self.is_winded() { "Winded" } else { "OK" }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hunger_drains_over_time() {
+        let mut survival = Survival::new();
+        for turn in 1..=10 {
+            survival.tick(turn, false);
+        }
+        assert_eq!(survival.hunger, MAX_HUNGER - 5);
+
+        survival.hunger = 20;
+        assert!(survival.is_hungry());
+        assert_eq!(survival.attack_penalty(), 2);
+        survival.eat(50, 0);
+        assert!(!survival.is_hungry());
+    }
+
+    #[test]
+    fn test_stamina_spent_and_recovered() {
+        let mut survival = Survival::new();
+        survival.stamina = RUN_COST + 1;
+        assert!(survival.exert(RUN_COST));
+        assert!(!survival.exert(RUN_COST));
+        assert!(survival.is_winded());
+        assert_eq!(survival.accuracy_penalty(), 15);
+
+        // Running doesn't recover, resting does
+        survival.tick(1, true);
+        assert_eq!(survival.stamina, 1);
+        survival.tick(1, false);
+        assert_eq!(survival.stamina, 1 + STAMINA_REGEN);
+    }
+
+    #[test]
+    fn test_starving_stops_stamina_recovery() {
+        let mut survival = Survival::new();
+        survival.hunger = 0;
+        survival.stamina = 10;
+        survival.tick(1, false);
+        assert_eq!(survival.stamina, 10);
+        assert_eq!(survival.attack_penalty(), 4);
+    }
+}
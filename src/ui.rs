@@ -4,18 +4,44 @@ use crossterm::{
     terminal::{self, Clear, ClearType},
 };
 use std::io::{Result, Write, stdout};
-
-use crate::game::{GameMode, GameState};
+use std::time::SystemTime;
+
+use crate::achievements::Achievement;
+use crate::backgrounds::Background;
+use crate::clock::Phase;
+use crate::crafting::RECIPES;
+use crate::daily;
+use crate::difficulty::DifficultySettings;
+use crate::dumpster::DumpsterKind;
+use crate::game::{EnemyType, GameMode, GameState, Position, TITLE_OPTIONS};
+use crate::items::{Armor, Consumable, Item, JUNK_CAPACITY, MaterialKind, Tool, Weapon};
+use crate::keymap;
+use crate::messages::{HUD_MESSAGES, LOG_PAGE, MessageKind};
+use crate::meta::Unlock;
+use crate::morgue;
+use crate::progression::Perk;
+use crate::quests::QuestStatus;
+use crate::scene::{Leave, PAUSE_OPTIONS, Scene};
+use crate::score::{MAX_HIGH_SCORES, MAX_NAME_LEN};
+use crate::settings::Settings;
+use crate::spawn::SpawnPointKind;
+use crate::survival::{MAX_HUNGER, MAX_STAMINA};
+use crate::vendor::Vendor;
 
 pub struct Renderer {
     pub width: u16,
     #[allow(dead_code)]
     pub height: u16,
+    pub show_corpses: bool,
 }
 
 impl Renderer {
     pub fn new(width: u16, height: u16) -> Self {
-        Renderer { width, height }
+        Renderer {
+            width,
+            height,
+            show_corpses: true,
+        }
     }
 
     pub fn clear_screen(&self) -> Result<()> {
@@ -26,14 +52,1019 @@ impl Renderer {
         self.clear_screen()?;
         execute!(stdout(), cursor::MoveTo(0, 0))?;
 
+        match game.scene {
+            Scene::Game => {}
+            Scene::Help => {
+                self.render_game_world(game)?;
+                return self.render_help(game);
+            }
+            Scene::Paused(selected) => return self.render_pause(game, selected),
+            Scene::Settings { selected, .. } => return self.render_settings(game, selected),
+            Scene::Confirm { leave, .. } => return self.render_confirm(game, leave),
+        }
+
         match game.mode {
             GameMode::Victory => self.render_victory(game),
             GameMode::GameOver => self.render_game_over(game),
+            GameMode::Crafting(selected) => self.render_crafting(game, selected),
+            GameMode::LevelUp(selected) => self.render_level_up(game, selected),
+            GameMode::Shop { selling, selected } => self.render_shop(game, selling, selected),
+            GameMode::Dialogue {
+                npc,
+                node,
+                selected,
+            } => self.render_dialogue(game, npc, node, selected),
+            GameMode::QuestLog => self.render_quest_log(game),
+            GameMode::Title(selected) => self.render_title(game, selected),
+            GameMode::NameEntry => self.render_name_entry(game),
+            GameMode::HighScores { .. } => self.render_high_scores(game),
+            GameMode::DailyScores { .. } => self.render_daily_scores(game),
+            GameMode::MessageLog { scroll, filter, .. } => {
+                self.render_message_log(game, scroll, filter)
+            }
+            GameMode::Achievements { .. } => self.render_achievements(game),
+            GameMode::Unlocks(selected) => self.render_unlocks(game, selected),
+            GameMode::CharacterSelect(selected) => self.render_character_select(game, selected),
+            GameMode::CustomDifficulty(selected) => self.render_custom_difficulty(game, selected),
             GameMode::LevelComplete => self.render_game_world(game),
             _ => self.render_game_world(game),
         }
     }
 
+    /// Draws a full-width horizontal border, e.g. `╠════╣`.
+    fn render_rule(&self, left: &str, right: &str, color: Color) -> Result<()> {
+        execute!(stdout(), SetForegroundColor(color), Print(left))?;
+        for _ in 0..self.width - 2 {
+            execute!(stdout(), Print("═"))?;
+        }
+        execute!(stdout(), Print(right), ResetColor, Print("\r\n"))
+    }
+
+    /// Draws one line of text inside the side borders, padded (or cut) to fit.
+    fn render_text_line(&self, text: &str, color: Color) -> Result<()> {
+        let inner = self.width as usize - 2;
+        let text: String = format!(" {}", text).chars().take(inner).collect();
+
+        execute!(
+            stdout(),
+            SetForegroundColor(Color::DarkGrey),
+            Print("║"),
+            ResetColor,
+            SetForegroundColor(color),
+            Print(&text),
+            ResetColor,
+        )?;
+        for _ in text.chars().count()..inner {
+            execute!(stdout(), Print(" "))?;
+        }
+        execute!(
+            stdout(),
+            SetForegroundColor(Color::DarkGrey),
+            Print("║"),
+            ResetColor,
+            Print("\r\n")
+        )
+    }
+
+    fn render_crafting(&self, game: &GameState, selected: usize) -> Result<()> {
+        let inv = &game.player.inventory;
+
+        self.render_rule("╔", "╗", Color::Red)?;
+        self.render_text_line("CRAFTING - Junk In, Weapons Out", Color::Yellow)?;
+        self.render_rule("╠", "╣", Color::DarkGrey)?;
+
+        for (idx, recipe) in RECIPES.iter().enumerate() {
+            let marker = if idx == selected { ">" } else { " " };
+            let (status, color) = match recipe.check(inv) {
+                Ok(()) => ("ready", Color::Green),
+                Err(_) => ("", Color::DarkGrey),
+            };
+            let color = if idx == selected && color == Color::DarkGrey {
+                Color::Reset
+            } else {
+                color
+            };
+            self.render_text_line(
+                &format!("{} {:<22} {}", marker, recipe.product.name(), status),
+                color,
+            )?;
+        }
+
+        self.render_rule("╠", "╣", Color::DarkGrey)?;
+
+        let recipe = &RECIPES[selected];
+        self.render_text_line(recipe.product.name(), Color::Cyan)?;
+        self.render_text_line(&recipe.product.summary(), Color::Reset)?;
+        let ingredients: Vec<String> = recipe.ingredients.iter().map(|i| i.to_string()).collect();
+        self.render_text_line(&format!("Needs: {}", ingredients.join(" + ")), Color::Reset)?;
+        match recipe.check(inv) {
+            Ok(()) => self.render_text_line("Press Enter to craft", Color::Green)?,
+            Err(err) => self.render_text_line(&err.to_string(), Color::Red)?,
+        }
+
+        self.render_rule("╠", "╣", Color::DarkGrey)?;
+        self.render_inventory(game)?;
+        for msg in game.messages.recent(HUD_MESSAGES) {
+            self.render_text_line(&msg.text, message_color(msg.kind))?;
+        }
+        self.render_text_line(
+            "W/S = Select | Enter = Craft | C/Esc = Back",
+            Color::DarkGrey,
+        )?;
+        self.render_rule("╚", "╝", Color::Red)?;
+
+        stdout().flush()
+    }
+
+    fn render_level_up(&self, game: &GameState, selected: usize) -> Result<()> {
+        let progression = &game.player.progression;
+
+        self.render_rule("╔", "╗", Color::Red)?;
+        self.render_text_line(
+            &format!("LEVEL UP! You reached level {}", progression.level),
+            Color::Yellow,
+        )?;
+        self.render_rule("╠", "╣", Color::DarkGrey)?;
+        self.render_text_line(
+            &format!(
+                "Max HP {} | Attack {} | Defense {}",
+                game.player.max_health,
+                game.player.total_attack(),
+                game.player.total_defense()
+            ),
+            Color::Reset,
+        )?;
+        self.render_text_line(
+            &format!("Perks: {}", progression.perk_summary()),
+            Color::Reset,
+        )?;
+        self.render_text_line("", Color::Reset)?;
+        self.render_text_line(
+            &format!("Choose a perk ({} to pick):", progression.pending_perks),
+            Color::Cyan,
+        )?;
+
+        for (idx, perk) in Perk::ALL.iter().enumerate() {
+            let (marker, color) = if idx == selected {
+                (">", Color::Green)
+            } else {
+                (" ", Color::Reset)
+            };
+            self.render_text_line(
+                &format!(
+                    "{} {:<16} (rank {}) {}",
+                    marker,
+                    perk.to_string(),
+                    progression.perk_rank(*perk),
+                    perk.description()
+                ),
+                color,
+            )?;
+        }
+
+        self.render_text_line("", Color::Reset)?;
+        self.render_text_line("W/S = Select | Enter = Choose", Color::DarkGrey)?;
+        self.render_rule("╚", "╝", Color::Red)?;
+
+        stdout().flush()
+    }
+
+    fn render_shop(&self, game: &GameState, selling: bool, selected: usize) -> Result<()> {
+        let inv = &game.player.inventory;
+
+        self.render_rule("╔", "╗", Color::Red)?;
+        self.render_text_line("BACK-DOOR VENDOR - No Refunds", Color::Yellow)?;
+        self.render_rule("╠", "╣", Color::DarkGrey)?;
+
+        let (buy_color, sell_color) = if selling {
+            (Color::DarkGrey, Color::Green)
+        } else {
+            (Color::Green, Color::DarkGrey)
+        };
+        execute!(
+            stdout(),
+            SetForegroundColor(Color::DarkGrey),
+            Print("║ "),
+            SetForegroundColor(buy_color),
+            Print("[ BUY ]"),
+            SetForegroundColor(sell_color),
+            Print(" [ SELL ]"),
+            ResetColor,
+        )?;
+        let caps_text = format!("   Your caps: {}", inv.cash);
+        execute!(
+            stdout(),
+            SetForegroundColor(Color::Yellow),
+            Print(&caps_text),
+            ResetColor
+        )?;
+        let used = 1 + "[ BUY ] [ SELL ]".len() + caps_text.len();
+        for _ in used..(self.width as usize - 2) {
+            execute!(stdout(), Print(" "))?;
+        }
+        execute!(
+            stdout(),
+            SetForegroundColor(Color::DarkGrey),
+            Print("║"),
+            ResetColor,
+            Print("\r\n")
+        )?;
+        self.render_text_line("", Color::Reset)?;
+
+        let rows: Vec<(String, u32)> = if selling {
+            inv.filled_slots()
+                .into_iter()
+                .filter_map(|slot| inv.peek(slot))
+                .map(|item| (item.describe(), Vendor::sell_price(&item)))
+                .collect()
+        } else {
+            game.vendor
+                .stock
+                .iter()
+                .map(|item| (item.describe(), Vendor::buy_price(item)))
+                .collect()
+        };
+
+        if rows.is_empty() {
+            let empty = if selling {
+                "You've got nothing worth selling."
+            } else {
+                "Sold out. Come back later."
+            };
+            self.render_text_line(empty, Color::DarkGrey)?;
+        }
+        for (idx, (name, price)) in rows.iter().enumerate() {
+            let marker = if idx == selected { ">" } else { " " };
+            let affordable = selling || *price <= inv.cash;
+            let color = match (idx == selected, affordable) {
+                (true, true) => Color::Green,
+                (true, false) => Color::Red,
+                (false, true) => Color::Reset,
+                (false, false) => Color::DarkGrey,
+            };
+            self.render_text_line(&format!("{} {:<42} {:>4} caps", marker, name, price), color)?;
+        }
+
+        self.render_rule("╠", "╣", Color::DarkGrey)?;
+        self.render_inventory(game)?;
+        for msg in game.messages.recent(HUD_MESSAGES) {
+            self.render_text_line(&msg.text, message_color(msg.kind))?;
+        }
+        self.render_text_line(
+            "W/S = Select | Tab = Buy/Sell | Enter = Trade | Esc = Leave",
+            Color::DarkGrey,
+        )?;
+        self.render_rule("╚", "╝", Color::Red)?;
+
+        stdout().flush()
+    }
+
+    fn render_dialogue(
+        &self,
+        game: &GameState,
+        npc_idx: usize,
+        node: usize,
+        selected: usize,
+    ) -> Result<()> {
+        let npc = &game.npcs[npc_idx];
+        let wrap_width = self.width as usize - 4;
+
+        self.render_rule("╔", "╗", Color::Red)?;
+        self.render_text_line(
+            &format!(
+                "{} ({})   Reputation: {}",
+                npc.name, npc.glyph, game.reputation
+            ),
+            Color::Yellow,
+        )?;
+        self.render_rule("╠", "╣", Color::DarkGrey)?;
+
+        for line in &npc.dialogue.nodes[node].lines {
+            for wrapped in wrap_text(line, wrap_width) {
+                self.render_text_line(&wrapped, Color::Reset)?;
+            }
+        }
+        self.render_text_line("", Color::Reset)?;
+
+        let options =
+            npc.visible_options(node, &game.player.inventory, game.reputation, &game.flags);
+        for (idx, (_, option)) in options.iter().enumerate() {
+            let (marker, color) = if idx == selected {
+                (">", Color::Green)
+            } else {
+                (" ", Color::Cyan)
+            };
+            for (row, wrapped) in wrap_text(&option.text, wrap_width - 5)
+                .into_iter()
+                .enumerate()
+            {
+                let prefix = if row == 0 {
+                    format!("{} {}. ", marker, idx + 1)
+                } else {
+                    "     ".to_string()
+                };
+                self.render_text_line(&format!("{}{}", prefix, wrapped), color)?;
+            }
+        }
+
+        self.render_rule("╠", "╣", Color::DarkGrey)?;
+        self.render_inventory(game)?;
+        for msg in game.messages.recent(HUD_MESSAGES) {
+            self.render_text_line(&msg.text, message_color(msg.kind))?;
+        }
+        self.render_text_line(
+            "W/S = Select | Enter or 1-9 = Say | Esc = Leave",
+            Color::DarkGrey,
+        )?;
+        self.render_rule("╚", "╝", Color::Red)?;
+
+        stdout().flush()
+    }
+
+    fn render_quest_log(&self, game: &GameState) -> Result<()> {
+        self.render_rule("╔", "╗", Color::Red)?;
+        self.render_text_line("QUEST LOG", Color::Yellow)?;
+        self.render_rule("╠", "╣", Color::DarkGrey)?;
+
+        let wrap_width = self.width as usize - 8;
+        for quest in &game.quests.quests {
+            let (status, color) = match quest.status {
+                QuestStatus::Inactive => continue,
+                QuestStatus::Active => ("", Color::Reset),
+                QuestStatus::Complete => (" - DONE", Color::Green),
+                QuestStatus::Failed => (" - FAILED", Color::Red),
+            };
+            let kind = if quest.main { "Main" } else { "Side" };
+            self.render_text_line(&format!("{}: {}{}", kind, quest.title, status), color)?;
+            for line in wrap_text(quest.description, wrap_width) {
+                self.render_text_line(&format!("   {}", line), Color::DarkGrey)?;
+            }
+            for objective in &quest.objectives {
+                let (mark, color) = if objective.is_complete() {
+                    ("x", Color::Green)
+                } else {
+                    (" ", Color::Reset)
+                };
+                self.render_text_line(&format!("   [{}] {}", mark, objective), color)?;
+            }
+            self.render_text_line("", Color::Reset)?;
+        }
+
+        self.render_text_line("J/Esc = Back", Color::DarkGrey)?;
+        self.render_rule("╚", "╝", Color::Red)?;
+
+        stdout().flush()
+    }
+
+    fn render_title(&self, game: &GameState, selected: usize) -> Result<()> {
+        self.render_rule("╔", "╗", Color::Red)?;
+        self.render_text_line("", Color::Reset)?;
+        self.render_text_line("R U S T   P U N K", Color::Red)?;
+        self.render_text_line("Behind Legally Distinct Burger Place", Color::DarkGrey)?;
+        self.render_text_line("", Color::Reset)?;
+        self.render_rule("╠", "╣", Color::DarkGrey)?;
+
+        let has_save = game.has_save();
+        for (idx, option) in TITLE_OPTIONS.iter().enumerate() {
+            let (marker, color) = if idx == selected {
+                (">", Color::Yellow)
+            } else if *option == "Continue" && !has_save {
+                (" ", Color::DarkGrey)
+            } else {
+                (" ", Color::Reset)
+            };
+            self.render_text_line(&format!("{} {}", marker, option), color)?;
+        }
+
+        self.render_text_line(game.notice.as_deref().unwrap_or(""), Color::Yellow)?;
+        let profile = &game.profile;
+        self.render_text_line(
+            &format!(
+                "Cred: {} | Runs: {} | Wins: {}",
+                profile.cred, profile.runs, profile.wins
+            ),
+            Color::DarkGrey,
+        )?;
+        if let Some(best) = game.high_scores.entries.first() {
+            self.render_text_line(
+                &format!(
+                    "Best run: {} - {} ({})",
+                    best.name,
+                    best.score,
+                    best.result()
+                ),
+                Color::DarkGrey,
+            )?;
+        }
+        self.render_text_line("W/S = Choose | Enter = Select | Q = Quit", Color::DarkGrey)?;
+        self.render_rule("╚", "╝", Color::Red)?;
+
+        stdout().flush()
+    }
+
+    /// Keys, a legend and a tip for what's going on, boxed over the alley
+    /// so the run stays in view underneath.
+    fn render_help(&self, game: &GameState) -> Result<()> {
+        let inner = self.width as usize - 12;
+        let column = inner / 2;
+        let mut lines: Vec<Vec<(String, Color)>> = Vec::new();
+        let heading = |text: &str| vec![(text.to_string(), Color::Yellow)];
+
+        lines.push(heading("Keys"));
+        for (keys, label) in keymap::bindings(game.mode) {
+            lines.push(vec![
+                (format!(" {:<16}", keys), Color::Cyan),
+                (label.to_string(), Color::Reset),
+            ]);
+        }
+
+        lines.push(Vec::new());
+        lines.push(heading("Legend"));
+        let mut pending: Option<Vec<(String, Color)>> = None;
+        for entry in legend(game) {
+            let len: usize = entry.iter().map(|(text, _)| text.chars().count()).sum();
+            if len >= column {
+                lines.extend(pending.take());
+                lines.push(entry);
+            } else if let Some(mut left) = pending.take() {
+                let used: usize = left.iter().map(|(text, _)| text.chars().count()).sum();
+                left.push((" ".repeat(column - used), Color::Reset));
+                left.extend(entry);
+                lines.push(left);
+            } else {
+                pending = Some(entry);
+            }
+        }
+        lines.extend(pending);
+
+        lines.push(Vec::new());
+        let tip = match game.mode {
+            GameMode::Combat(_) => {
+                "Space swings at whatever you bumped into. Unaware enemies take a sneak attack for double damage. R backs off, E patches you up mid-fight."
+            }
+            GameMode::LevelComplete => {
+                "The exit's open! Walk through it to escape. The alley doesn't stop while you finish up, so don't dawdle."
+            }
+            _ => {
+                "Scavenge the dumpsters and find a way through the chained gate. Sneak past anything you'd rather not fight."
+            }
+        };
+        for line in wrap_text(tip, inner - 1) {
+            lines.push(vec![(line, Color::Green)]);
+        }
+        lines.push(vec![("? / Esc = Close".to_string(), Color::DarkGrey)]);
+
+        let title = match game.mode {
+            GameMode::Combat(_) => " HELP - COMBAT ",
+            GameMode::LevelComplete => " HELP - LEVEL COMPLETE ",
+            _ => " HELP - EXPLORING ",
+        };
+        let left = 5;
+        let top = 2;
+        let rule = "─".repeat(inner - title.chars().count());
+        execute!(
+            stdout(),
+            cursor::MoveTo(left, top),
+            SetForegroundColor(Color::Cyan),
+            Print("┌"),
+            SetForegroundColor(Color::Yellow),
+            Print(title),
+            SetForegroundColor(Color::Cyan),
+            Print(format!("{}┐", rule)),
+            ResetColor
+        )?;
+        for (row, segments) in lines.iter().enumerate() {
+            execute!(
+                stdout(),
+                cursor::MoveTo(left, top + 1 + row as u16),
+                SetForegroundColor(Color::Cyan),
+                Print("│ "),
+                ResetColor
+            )?;
+            let mut used = 1;
+            for (text, color) in segments {
+                let text: String = text.chars().take(inner - used).collect();
+                used += text.chars().count();
+                execute!(
+                    stdout(),
+                    SetForegroundColor(*color),
+                    Print(text),
+                    ResetColor
+                )?;
+            }
+            execute!(
+                stdout(),
+                Print(" ".repeat(inner - used)),
+                SetForegroundColor(Color::Cyan),
+                Print("│"),
+                ResetColor
+            )?;
+        }
+        execute!(
+            stdout(),
+            cursor::MoveTo(left, top + 1 + lines.len() as u16),
+            SetForegroundColor(Color::Cyan),
+            Print(format!("└{}┘", "─".repeat(inner))),
+            ResetColor
+        )?;
+
+        stdout().flush()
+    }
+
+    fn render_pause(&self, game: &GameState, selected: usize) -> Result<()> {
+        self.render_rule("╔", "╗", Color::Red)?;
+        self.render_text_line("PAUSED", Color::Yellow)?;
+        self.render_text_line(
+            &format!(
+                "Turn {} | HP: {}/{} | {}",
+                game.turn_count,
+                game.player.health,
+                game.player.max_health,
+                game.clock()
+            ),
+            Color::DarkGrey,
+        )?;
+        self.render_rule("╠", "╣", Color::DarkGrey)?;
+
+        for (idx, option) in PAUSE_OPTIONS.iter().enumerate() {
+            let (marker, color) = if idx == selected {
+                (">", Color::Yellow)
+            } else {
+                (" ", Color::Reset)
+            };
+            self.render_text_line(&format!("{} {}", marker, option), color)?;
+        }
+
+        self.render_text_line("", Color::Reset)?;
+        self.render_text_line(game.notice.as_deref().unwrap_or(""), Color::Green)?;
+        self.render_text_line(
+            "W/S = Choose | Enter = Select | Esc = Resume",
+            Color::DarkGrey,
+        )?;
+        self.render_rule("╚", "╝", Color::Red)?;
+
+        stdout().flush()
+    }
+
+    fn render_settings(&self, game: &GameState, selected: usize) -> Result<()> {
+        let profile = &game.profile;
+        let mut rows = vec![("Difficulty", profile.difficulty.to_string())];
+        for (idx, label) in Settings::LABELS.iter().enumerate() {
+            let on = profile.settings.value(idx);
+            rows.push((label, (if on { "On" } else { "Off" }).to_string()));
+        }
+
+        self.render_rule("╔", "╗", Color::Red)?;
+        self.render_text_line("SETTINGS", Color::Yellow)?;
+        self.render_rule("╠", "╣", Color::DarkGrey)?;
+
+        for (idx, (label, value)) in rows.iter().enumerate() {
+            let (marker, color) = if idx == selected {
+                (">", Color::Cyan)
+            } else {
+                (" ", Color::Reset)
+            };
+            self.render_text_line(&format!("{} {:<18} {:>9}", marker, label, value), color)?;
+        }
+
+        self.render_rule("╠", "╣", Color::DarkGrey)?;
+        let help = match selected {
+            0 => "Used from the next run on.",
+            1 => "Save the run when you leave it, instead of losing it.",
+            _ => "Ask before leaving a run.",
+        };
+        self.render_text_line(help, Color::DarkGrey)?;
+        self.render_text_line("W/S = Choose | A/D = Change | Esc = Back", Color::DarkGrey)?;
+        self.render_rule("╚", "╝", Color::Red)?;
+
+        stdout().flush()
+    }
+
+    fn render_confirm(&self, game: &GameState, leave: Leave) -> Result<()> {
+        self.render_rule("╔", "╗", Color::Red)?;
+        self.render_text_line(
+            match leave {
+                Leave::ToMenu => "QUIT TO MENU?",
+                Leave::Quit => "QUIT THE GAME?",
+            },
+            Color::Yellow,
+        )?;
+        self.render_rule("╠", "╣", Color::DarkGrey)?;
+        if game.profile.settings.autosave {
+            self.render_text_line("Your run will be saved for later.", Color::Reset)?;
+        } else {
+            self.render_text_line("Anything since your last save is lost.", Color::Red)?;
+        }
+        self.render_text_line("", Color::Reset)?;
+        self.render_text_line("Y = Leave | N = Stay", Color::DarkGrey)?;
+        self.render_rule("╚", "╝", Color::Red)?;
+
+        stdout().flush()
+    }
+
+    fn render_name_entry(&self, game: &GameState) -> Result<()> {
+        let run = game.run_result();
+
+        self.render_rule("╔", "╗", Color::Red)?;
+        self.render_text_line("RECORD YOUR RUN", Color::Yellow)?;
+        self.render_rule("╠", "╣", Color::DarkGrey)?;
+        self.render_text_line(&format!("Score: {}", run.score()), Color::Reset)?;
+        if game.high_scores.qualifies(run.score()) {
+            self.render_text_line("That's good enough for the table!", Color::Green)?;
+        } else {
+            self.render_text_line("Not quite enough for the table this time.", Color::DarkGrey)?;
+        }
+        self.render_text_line("", Color::Reset)?;
+        self.render_text_line(&format!("Name: {}_", game.name_entry), Color::Cyan)?;
+        self.render_text_line(
+            &format!("(up to {} characters)", MAX_NAME_LEN),
+            Color::DarkGrey,
+        )?;
+        self.render_text_line("", Color::Reset)?;
+        self.render_text_line("Enter = Save | Esc = Cancel", Color::DarkGrey)?;
+        self.render_rule("╚", "╝", Color::Red)?;
+
+        stdout().flush()
+    }
+
+    fn render_high_scores(&self, game: &GameState) -> Result<()> {
+        let scores = &game.high_scores;
+
+        self.render_rule("╔", "╗", Color::Red)?;
+        self.render_text_line("HIGH SCORES", Color::Yellow)?;
+        self.render_rule("╠", "╣", Color::DarkGrey)?;
+        self.render_text_line(
+            &format!(
+                "{:>2}  {:<12} {:>6}  {:<8} {:>5} {:>5}  {}",
+                "#", "Name", "Score", "Result", "Turns", "Kills", "Mode"
+            ),
+            Color::DarkGrey,
+        )?;
+
+        if scores.entries.is_empty() {
+            self.render_text_line("No runs recorded yet.", Color::DarkGrey)?;
+        }
+        for (idx, entry) in scores.entries.iter().enumerate() {
+            let color = if game.score_recorded && scores.last_rank == Some(idx) {
+                Color::Yellow
+            } else if entry.won {
+                Color::Green
+            } else {
+                Color::Reset
+            };
+            self.render_text_line(
+                &format!(
+                    "{:>2}. {:<12} {:>6}  {:<8} {:>5} {:>5}  {}",
+                    idx + 1,
+                    entry.name,
+                    entry.score,
+                    entry.result(),
+                    entry.turns,
+                    entry.enemies_defeated,
+                    entry.difficulty
+                ),
+                color,
+            )?;
+        }
+
+        self.render_text_line("", Color::Reset)?;
+        if let Some(error) = &scores.error {
+            self.render_text_line(&format!("Couldn't save scores: {}", error), Color::Red)?;
+        } else if game.score_recorded && scores.last_rank.is_none() {
+            self.render_text_line("Your run didn't make the table.", Color::DarkGrey)?;
+        }
+        self.render_text_line("Esc = Back", Color::DarkGrey)?;
+        self.render_rule("╚", "╝", Color::Red)?;
+
+        stdout().flush()
+    }
+
+    fn render_message_log(
+        &self,
+        game: &GameState,
+        scroll: usize,
+        filter: Option<MessageKind>,
+    ) -> Result<()> {
+        let total = game.messages.filtered(filter).len();
+        let page = game.messages.page(filter, scroll);
+
+        self.render_rule("╔", "╗", Color::Red)?;
+        let tabs: Vec<String> = [None]
+            .into_iter()
+            .chain(MessageKind::ALL.map(Some))
+            .map(|kind| {
+                let name = kind.map_or("All".to_string(), |k| k.to_string());
+                if kind == filter {
+                    format!("[{}]", name)
+                } else {
+                    format!(" {} ", name)
+                }
+            })
+            .collect();
+        self.render_text_line(&format!("MESSAGE LOG  {}", tabs.join(" ")), Color::Yellow)?;
+        self.render_rule("╠", "╣", Color::DarkGrey)?;
+
+        if page.is_empty() {
+            self.render_text_line("Nothing here yet.", Color::DarkGrey)?;
+        }
+        for msg in &page {
+            self.render_text_line(
+                &format!("{:>4} {}", msg.turn, msg.text),
+                message_color(msg.kind),
+            )?;
+        }
+        // Keep the footer in one place however full the page is
+        for _ in page.len().max(1)..LOG_PAGE {
+            self.render_text_line("", Color::Reset)?;
+        }
+
+        self.render_rule("╠", "╣", Color::DarkGrey)?;
+        let end = total - scroll.min(total);
+        self.render_text_line(
+            &format!(
+                "Showing {}-{} of {}",
+                end - page.len() + usize::from(!page.is_empty()),
+                end,
+                total
+            ),
+            Color::DarkGrey,
+        )?;
+        self.render_text_line(
+            "W/S = Scroll | PgUp/PgDn = Page | Tab = Filter | L = Back",
+            Color::DarkGrey,
+        )?;
+        self.render_rule("╚", "╝", Color::Red)?;
+
+        stdout().flush()
+    }
+
+    fn render_daily_scores(&self, game: &GameState) -> Result<()> {
+        let date = daily::date(SystemTime::now());
+        let scores = game.daily_scores.on(&date);
+
+        self.render_rule("╔", "╗", Color::Red)?;
+        self.render_text_line(&format!("DAILY RUN - {} (UTC)", date), Color::Yellow)?;
+        self.render_text_line(
+            &format!("Seed: {} | Normal, no unlocks", daily::seed(&date)),
+            Color::DarkGrey,
+        )?;
+        self.render_rule("╠", "╣", Color::DarkGrey)?;
+        self.render_text_line(
+            &format!(
+                "{:>2}  {:<12} {:>6}  {:<8} {:>5} {:>5}",
+                "#", "Name", "Score", "Result", "Turns", "Kills"
+            ),
+            Color::DarkGrey,
+        )?;
+
+        if scores.is_empty() {
+            self.render_text_line("Nobody's played today's alley yet.", Color::DarkGrey)?;
+        }
+        for (idx, entry) in scores.iter().take(MAX_HIGH_SCORES).enumerate() {
+            let color = if entry.won {
+                Color::Green
+            } else {
+                Color::Reset
+            };
+            self.render_text_line(
+                &format!(
+                    "{:>2}. {:<12} {:>6}  {:<8} {:>5} {:>5}",
+                    idx + 1,
+                    entry.name,
+                    entry.score,
+                    entry.result(),
+                    entry.turns,
+                    entry.enemies_defeated
+                ),
+                color,
+            )?;
+        }
+
+        self.render_text_line("", Color::Reset)?;
+        if game.profile.daily.as_deref() == Some(date.as_str()) {
+            self.render_text_line(
+                "You've had today's scored attempt. More runs are practice.",
+                Color::DarkGrey,
+            )?;
+        } else {
+            self.render_text_line("Your scored attempt for today is ready.", Color::Green)?;
+        }
+        if let Some(notice) = &game.notice {
+            self.render_text_line(notice, Color::Cyan)?;
+        }
+        if let Some(error) = &game.daily_scores.error {
+            self.render_text_line(&format!("Couldn't save results: {}", error), Color::Red)?;
+        }
+        self.render_text_line(
+            "Enter = Play | X = Export | I = Import | Esc = Back",
+            Color::DarkGrey,
+        )?;
+        self.render_rule("╚", "╝", Color::Red)?;
+
+        stdout().flush()
+    }
+
+    fn render_unlocks(&self, game: &GameState, selected: usize) -> Result<()> {
+        let profile = &game.profile;
+
+        self.render_rule("╔", "╗", Color::Red)?;
+        self.render_text_line(
+            &format!("UNLOCKS - {} cred to spend", profile.cred),
+            Color::Yellow,
+        )?;
+        self.render_rule("╠", "╣", Color::DarkGrey)?;
+
+        for (idx, unlock) in Unlock::ALL.iter().enumerate() {
+            let marker = if idx == selected { ">" } else { " " };
+            let (status, color) = if profile.alley == Some(*unlock) {
+                ("PLAYING".to_string(), Color::Yellow)
+            } else if profile.owns(*unlock) {
+                ("owned".to_string(), Color::Green)
+            } else if profile.cred >= unlock.cost() {
+                (format!("{} cred", unlock.cost()), Color::Reset)
+            } else {
+                (format!("{} cred", unlock.cost()), Color::DarkGrey)
+            };
+            let color = if idx == selected && color == Color::DarkGrey {
+                Color::Reset
+            } else {
+                color
+            };
+            self.render_text_line(
+                &format!(
+                    "{} {:<5} {:<20} {:>10}",
+                    marker,
+                    unlock.kind(),
+                    unlock.name(),
+                    status
+                ),
+                color,
+            )?;
+        }
+
+        self.render_rule("╠", "╣", Color::DarkGrey)?;
+        let unlock = Unlock::ALL[selected];
+        self.render_text_line(unlock.description(), Color::Reset)?;
+        let alley = profile.alley.map_or("The Back Alley", |alley| alley.name());
+        self.render_text_line(&format!("Next run: {}", alley), Color::DarkGrey)?;
+        if let Some(notice) = &game.notice {
+            self.render_text_line(notice, Color::Cyan)?;
+        }
+        if let Some(error) = &profile.error {
+            self.render_text_line(&format!("Couldn't save profile: {}", error), Color::Red)?;
+        }
+        self.render_text_line(
+            "W/S = Choose | Enter = Buy / Play alley | Esc = Back",
+            Color::DarkGrey,
+        )?;
+        self.render_rule("╚", "╝", Color::Red)?;
+
+        stdout().flush()
+    }
+
+    fn render_character_select(&self, game: &GameState, selected: usize) -> Result<()> {
+        self.render_rule("╔", "╗", Color::Red)?;
+        self.render_text_line("WHO WERE YOU BEFORE THE ALLEY?", Color::Yellow)?;
+        self.render_rule("╠", "╣", Color::DarkGrey)?;
+
+        for (idx, background) in Background::ALL.iter().enumerate() {
+            let marker = if idx == selected { ">" } else { " " };
+            let locked = background
+                .required_unlock()
+                .filter(|unlock| !game.profile.owns(*unlock));
+            let (status, color) = match locked {
+                Some(unlock) => (format!("{} cred", unlock.cost()), Color::DarkGrey),
+                None => (background.passive_name().to_string(), Color::Reset),
+            };
+            let color = if idx == selected { Color::Cyan } else { color };
+            self.render_text_line(
+                &format!(
+                    "{} {:<16} HP {:>3}  ATK {:>2}  {:>12}",
+                    marker,
+                    background.to_string(),
+                    background.max_health(),
+                    background.attack(),
+                    status
+                ),
+                color,
+            )?;
+        }
+
+        self.render_rule("╠", "╣", Color::DarkGrey)?;
+        let background = Background::ALL[selected];
+        self.render_text_line(background.description(), Color::Reset)?;
+        self.render_text_line(
+            &format!("{}: {}", background.passive_name(), background.passive()),
+            Color::Green,
+        )?;
+        self.render_text_line(
+            &format!("Starts with: {}", background.starting_item().name()),
+            Color::DarkGrey,
+        )?;
+
+        self.render_rule("╠", "╣", Color::DarkGrey)?;
+        let difficulty = game.profile.difficulty;
+        let rules = difficulty.settings(game.profile.custom);
+        self.render_text_line(
+            &format!(
+                "Difficulty: < {} >  ({}% score)",
+                difficulty,
+                rules.score_percent()
+            ),
+            Color::Yellow,
+        )?;
+        self.render_text_line(difficulty.description(), Color::DarkGrey)?;
+        if let Some(notice) = &game.notice {
+            self.render_text_line(notice, Color::Cyan)?;
+        }
+        self.render_text_line(
+            "W/S = Pick | A/D = Difficulty | Enter = Go | Esc = Back",
+            Color::DarkGrey,
+        )?;
+        self.render_rule("╚", "╝", Color::Red)?;
+
+        stdout().flush()
+    }
+
+    fn render_custom_difficulty(&self, game: &GameState, selected: usize) -> Result<()> {
+        let custom = game.profile.custom;
+
+        self.render_rule("╔", "╗", Color::Red)?;
+        self.render_text_line("CUSTOM DIFFICULTY", Color::Yellow)?;
+        self.render_rule("╠", "╣", Color::DarkGrey)?;
+
+        for (idx, label) in DifficultySettings::LABELS.iter().enumerate() {
+            let marker = if idx == selected { ">" } else { " " };
+            let color = if idx == selected {
+                Color::Cyan
+            } else {
+                Color::Reset
+            };
+            self.render_text_line(
+                &format!("{} {:<16} {:>6}", marker, label, custom.value_text(idx)),
+                color,
+            )?;
+        }
+
+        self.render_rule("╠", "╣", Color::DarkGrey)?;
+        self.render_text_line(
+            &format!(
+                "Normal is 100%. Runs on these rules score {}%",
+                custom.score_percent()
+            ),
+            Color::DarkGrey,
+        )?;
+        self.render_text_line(
+            if custom.permadeath {
+                "Dying ends the run."
+            } else {
+                "Dying sends you back to the entrance, minus your caps."
+            },
+            Color::DarkGrey,
+        )?;
+        self.render_text_line("W/S = Choose | A/D = Change | Esc = Back", Color::DarkGrey)?;
+        self.render_rule("╚", "╝", Color::Red)?;
+
+        stdout().flush()
+    }
+
+    fn render_achievements(&self, game: &GameState) -> Result<()> {
+        let profile = &game.profile;
+
+        self.render_rule("╔", "╗", Color::Red)?;
+        self.render_text_line(
+            &format!(
+                "ACHIEVEMENTS - {}/{} unlocked",
+                profile.achievements.len(),
+                Achievement::ALL.len()
+            ),
+            Color::Yellow,
+        )?;
+        self.render_rule("╠", "╣", Color::DarkGrey)?;
+
+        for achievement in Achievement::ALL {
+            let (mark, color) = if profile.has(achievement) {
+                ("x", Color::Green)
+            } else {
+                (" ", Color::Reset)
+            };
+            self.render_text_line(&format!("[{}] {}", mark, achievement.name()), color)?;
+            self.render_text_line(
+                &format!("    {}", achievement.description()),
+                Color::DarkGrey,
+            )?;
+        }
+
+        self.render_text_line("", Color::Reset)?;
+        if let Some(error) = &profile.error {
+            self.render_text_line(&format!("Couldn't save profile: {}", error), Color::Red)?;
+        }
+        self.render_text_line("V/Esc = Back", Color::DarkGrey)?;
+        self.render_rule("╚", "╝", Color::Red)?;
+
+        stdout().flush()
+    }
+
     fn render_game_world(&self, game: &GameState) -> Result<()> {
         // Draw top border and title
         execute!(stdout(), SetForegroundColor(Color::Red), Print("╔"),)?;
@@ -65,6 +1096,14 @@ impl Renderer {
         }
         execute!(stdout(), Print("╣"), ResetColor, Print("\r\n"))?;
 
+        // Night dims the palette and shrinks what you can see
+        let phase = game.clock().phase();
+        let (wall_color, floor_color) = match phase {
+            Phase::Day => (Color::DarkGrey, Color::DarkGrey),
+            Phase::Dawn | Phase::Dusk => (Color::DarkMagenta, Color::DarkGrey),
+            Phase::Night => (Color::DarkBlue, Color::DarkBlue),
+        };
+
         // Draw game world
         for y in 0..game.height {
             execute!(
@@ -76,31 +1115,54 @@ impl Renderer {
 
             for x in 0..game.width {
                 let mut rendered = false;
+                let lit = game.is_visible(Position::new(x, y));
 
                 // Draw player
                 if game.player.position.x == x && game.player.position.y == y {
+                    let color = if game.player.sneaking {
+                        Color::DarkGreen
+                    } else {
+                        Color::Green
+                    };
+                    execute!(stdout(), SetForegroundColor(color), Print("@"), ResetColor)?;
+                    rendered = true;
+                }
+
+                // Draw enemies
+                if !rendered && lit {
+                    for enemy in &game.enemies {
+                        if enemy.is_alive && enemy.position.x == x && enemy.position.y == y {
+                            execute!(
+                                stdout(),
+                                SetForegroundColor(enemy_color(enemy.enemy_type)),
+                                Print(enemy.get_char()),
+                                ResetColor
+                            )?;
+                            rendered = true;
+                            break;
+                        }
+                    }
+                }
+
+                // Draw the back-door vendor
+                if !rendered && lit && game.vendor.position.x == x && game.vendor.position.y == y {
                     execute!(
                         stdout(),
                         SetForegroundColor(Color::Green),
-                        Print("@"),
+                        Print("V"),
                         ResetColor
                     )?;
                     rendered = true;
                 }
 
-                // Draw enemies
-                if !rendered {
-                    for enemy in &game.enemies {
-                        if enemy.is_alive && enemy.position.x == x && enemy.position.y == y {
-                            let color = match enemy.enemy_type {
-                                crate::game::EnemyType::Rat => Color::DarkYellow,
-                                crate::game::EnemyType::FerralCat => Color::Magenta,
-                                crate::game::EnemyType::RivalPunk => Color::Red,
-                            };
+                // Draw the locals
+                if !rendered && lit {
+                    for npc in &game.npcs {
+                        if npc.position.x == x && npc.position.y == y {
                             execute!(
                                 stdout(),
-                                SetForegroundColor(color),
-                                Print(enemy.get_char()),
+                                SetForegroundColor(Color::Magenta),
+                                Print(npc.glyph),
                                 ResetColor
                             )?;
                             rendered = true;
@@ -109,21 +1171,38 @@ impl Renderer {
                     }
                 }
 
+                // Draw the staff member out on a trash run
+                if !rendered
+                    && lit
+                    && let Some(run) = &game.trash_run
+                    && run.position.x == x
+                    && run.position.y == y
+                {
+                    execute!(
+                        stdout(),
+                        SetForegroundColor(Color::Yellow),
+                        Print("K"),
+                        ResetColor
+                    )?;
+                    rendered = true;
+                }
+
                 // Draw dumpsters
                 if !rendered {
                     for dumpster in &game.dumpsters {
                         if dumpster.position.x == x && dumpster.position.y == y {
-                            if dumpster.has_items {
+                            // You remember where they are, not what's in them
+                            if dumpster.has_items && lit {
                                 execute!(
                                     stdout(),
-                                    SetForegroundColor(Color::Cyan),
-                                    Print("▓"),
+                                    SetForegroundColor(dumpster_color(dumpster.kind)),
+                                    Print(dumpster.kind.glyph()),
                                     ResetColor
                                 )?;
                             } else {
                                 execute!(
                                     stdout(),
-                                    SetForegroundColor(Color::DarkGrey),
+                                    SetForegroundColor(wall_color),
                                     Print("▒"),
                                     ResetColor
                                 )?;
@@ -134,36 +1213,132 @@ impl Renderer {
                     }
                 }
 
-                // Draw chain (exit)
-                if !rendered && game.chain_position.x == x && game.chain_position.y == y {
-                    let chain_color = if game.player.inventory.bolt_cutters.found {
+                // Draw loot on the ground
+                if !rendered
+                    && lit
+                    && let Some(ground) = game
+                        .ground_items
+                        .iter()
+                        .find(|g| g.position.x == x && g.position.y == y)
+                {
+                    execute!(
+                        stdout(),
+                        SetForegroundColor(item_color(&ground.item)),
+                        Print(ground.item.glyph()),
+                        ResetColor
+                    )?;
+                    rendered = true;
+                }
+
+                // Draw corpses
+                if !rendered
+                    && lit
+                    && self.show_corpses
+                    && game
+                        .enemies
+                        .iter()
+                        .any(|e| !e.is_alive && e.position.x == x && e.position.y == y)
+                {
+                    execute!(
+                        stdout(),
+                        SetForegroundColor(Color::DarkGrey),
+                        Print("%"),
+                        ResetColor
+                    )?;
+                    rendered = true;
+                }
+
+                // Draw locks. The exit shows green once it's open; other
+                // locks leave a gap behind
+                if !rendered
+                    && let Some(lock) = game
+                        .locks
+                        .iter()
+                        .find(|l| l.position.x == x && l.position.y == y)
+                    && (lock.exit || !lock.open)
+                {
+                    let color = if lock.open {
                         Color::Green
-                    } else {
+                    } else if lock.exit {
                         Color::Yellow
+                    } else {
+                        Color::Grey
                     };
                     execute!(
                         stdout(),
-                        SetForegroundColor(chain_color),
-                        Print("⛓"),
+                        SetForegroundColor(color),
+                        Print(lock.kind.glyph()),
+                        ResetColor
+                    )?;
+                    rendered = true;
+                }
+
+                // Sewer grates and the gaps in the walls where things get in
+                let spawn_point = game
+                    .spawns
+                    .points
+                    .iter()
+                    .find(|p| p.position.x == x && p.position.y == y)
+                    .map(|p| p.kind);
+                if !rendered && spawn_point == Some(SpawnPointKind::SewerGrate) {
+                    execute!(
+                        stdout(),
+                        SetForegroundColor(wall_color),
+                        Print("≡"),
                         ResetColor
                     )?;
                     rendered = true;
                 }
 
                 // Draw borders
-                if !rendered && (x == 0 || x == game.width - 1 || y == 0 || y == game.height - 1) {
+                if !rendered
+                    && spawn_point != Some(SpawnPointKind::AlleyMouth)
+                    && (x == 0 || x == game.width - 1 || y == 0 || y == game.height - 1)
+                {
                     execute!(
                         stdout(),
-                        SetForegroundColor(Color::DarkGrey),
+                        SetForegroundColor(wall_color),
                         Print("#"),
                         ResetColor
                     )?;
                     rendered = true;
                 }
 
-                // Draw ground
+                // What the enemy just below knows about you: ? or !
+                if !rendered && lit {
+                    let marker = game
+                        .enemies
+                        .iter()
+                        .find(|e| e.is_alive && e.position.x == x && e.position.y == y + 1)
+                        .and_then(|e| e.awareness.marker());
+                    if let Some(marker) = marker {
+                        let color = if marker == '!' {
+                            Color::Red
+                        } else {
+                            Color::Yellow
+                        };
+                        execute!(
+                            stdout(),
+                            SetForegroundColor(color),
+                            Print(marker),
+                            ResetColor
+                        )?;
+                        rendered = true;
+                    }
+                }
+
+                // Draw ground, marking out how far you can see in the dark
                 if !rendered {
-                    execute!(stdout(), Print(" "))?;
+                    if lit && phase != Phase::Day {
+                        execute!(
+                            stdout(),
+                            SetForegroundColor(floor_color),
+                            Print("."),
+                            ResetColor
+                        )?;
+                    } else {
+                        execute!(stdout(), Print(" "))?;
+                    }
                 }
             }
 
@@ -197,6 +1372,14 @@ impl Renderer {
     }
 
     fn render_stats(&self, game: &GameState) -> Result<()> {
+        let clock = game.clock();
+        let time_text = format!("{} {}", clock, clock.phase());
+        let time_color = match clock.phase() {
+            Phase::Day => Color::Yellow,
+            Phase::Dawn | Phase::Dusk => Color::Magenta,
+            Phase::Night => Color::Blue,
+        };
+
         // Player stats
         execute!(
             stdout(),
@@ -211,17 +1394,24 @@ impl Renderer {
             SetForegroundColor(Color::Cyan),
             Print(format!("{}/3", game.player.scavenged_items)),
             ResetColor,
-            Print(" | Turn: "),
-            Print(format!("{}", game.turn_count)),
+            Print(" | "),
+            SetForegroundColor(time_color),
+            Print(&time_text),
+            ResetColor,
+            Print(" | Caps: "),
+            SetForegroundColor(Color::Yellow),
+            Print(format!("{}", game.player.inventory.cash)),
+            ResetColor,
         )?;
 
         // Pad the rest of the line
         let stats_text_len = format!(
-            " HP: {}/{} | Scavenged: {}/3 | Turn: {}",
+            " HP: {}/{} | Scavenged: {}/3 | {} | Caps: {}",
             game.player.health,
             game.player.max_health,
             game.player.scavenged_items,
-            game.turn_count
+            time_text,
+            game.player.inventory.cash
         )
         .len();
         for _ in stats_text_len..(self.width as usize - 2) {
@@ -236,6 +1426,68 @@ impl Renderer {
             Print("\r\n")
         )?;
 
+        // Character progression
+        let progression = &game.player.progression;
+        self.render_text_line(
+            &format!(
+                "Lv {} | XP {}/{} | ATK {} | DEF {} | Rep {} | Perks: {}",
+                progression.level,
+                progression.xp,
+                progression.xp_to_next_level(),
+                game.player.total_attack(),
+                game.player.total_defense(),
+                game.reputation,
+                progression.perk_summary()
+            ),
+            Color::Reset,
+        )?;
+
+        // Hunger and stamina
+        let survival = &game.player.survival;
+        let survival_color = if survival.is_starving() {
+            Color::Red
+        } else if survival.is_hungry() || survival.is_winded() {
+            Color::Yellow
+        } else {
+            Color::Green
+        };
+        self.render_text_line(
+            &format!(
+                "Food {} {:<8} | Stamina {} {}",
+                meter(survival.hunger, MAX_HUNGER),
+                survival.hunger_label(),
+                meter(survival.stamina, MAX_STAMINA),
+                survival.stamina_label()
+            ),
+            survival_color,
+        )?;
+
+        if game.player.sneaking {
+            self.render_text_line(
+                "Sneaking: quiet steps, harder to spot. Z to stand up.",
+                Color::DarkGreen,
+            )?;
+        }
+
+        if game.player.infection > 0 {
+            self.render_text_line(
+                &format!(
+                    "Infected! -1 HP a turn for {} more turns. Bandages help.",
+                    game.player.infection
+                ),
+                Color::Red,
+            )?;
+        }
+
+        // What's left before the exit opens
+        let remaining = game.quests.remaining_main();
+        let goal = if remaining.is_empty() {
+            "Get to the exit!".to_string()
+        } else {
+            remaining.join(" | ")
+        };
+        self.render_text_line(&format!("Goal: {}", goal), Color::Cyan)?;
+
         // Combat mode indicator
         if let GameMode::Combat(idx) = game.mode
             && idx < game.enemies.len()
@@ -275,17 +1527,19 @@ impl Renderer {
         self.render_inventory(game)?;
 
         // Messages
-        for msg in &game.messages {
+        for msg in game.messages.recent(HUD_MESSAGES) {
             execute!(
                 stdout(),
                 SetForegroundColor(Color::DarkGrey),
                 Print("║"),
                 ResetColor,
                 Print(" "),
-                Print(msg),
+                SetForegroundColor(message_color(msg.kind)),
+                Print(&msg.text),
+                ResetColor,
             )?;
 
-            let msg_len = msg.len() + 1;
+            let msg_len = msg.text.len() + 1;
             for _ in msg_len..(self.width as usize - 2) {
                 execute!(stdout(), Print(" "))?;
             }
@@ -305,17 +1559,16 @@ impl Renderer {
             SetForegroundColor(Color::DarkGrey),
             Print("║"),
             ResetColor,
-            Print(" @ = You | r = Rat | c = Cat | P = Punk | "),
+            Print(" @ = You | "),
             SetForegroundColor(Color::Cyan),
             Print("▓"),
             ResetColor,
-            Print(" = Dump | ⛓ = Exit | E = Use Item"),
+            Print(" = Dump | ╳ = Exit | ? = Help | Esc = Pause"),
         )?;
 
-        let legend_len =
-            " @ = You | r = Rat | c = Cat | P = Punk | ▓ = Dump | ⛓ = Exit | E = Use Item"
-                .chars()
-                .count();
+        let legend_len = " @ = You | ▓ = Dump | ╳ = Exit | ? = Help | Esc = Pause"
+            .chars()
+            .count();
         for _ in legend_len..(self.width as usize - 2) {
             execute!(stdout(), Print(" "))?;
         }
@@ -336,7 +1589,14 @@ impl Renderer {
 
         // Weapon slot
         let weapon_text = if let Some(weapon) = &inv.weapon {
-            format!("Weapon: {} (+{})", weapon.name, weapon.damage_bonus)
+            format!(
+                "Weapon: {} (+{}) [{} {}/{}]",
+                weapon.name,
+                weapon.effective_damage(),
+                weapon.durability.condition(),
+                weapon.durability.current,
+                weapon.durability.max
+            )
         } else {
             "Weapon: [Empty]".to_string()
         };
@@ -365,7 +1625,14 @@ impl Renderer {
 
         // Armor slot
         let armor_text = if let Some(armor) = &inv.armor {
-            format!("Armor: {} (+{})", armor.name, armor.defense_bonus)
+            format!(
+                "Armor: {} (+{}) [{} {}/{}]",
+                armor.name,
+                armor.effective_defense(),
+                armor.durability.condition(),
+                armor.durability.current,
+                armor.durability.max
+            )
         } else {
             "Armor: [Empty]".to_string()
         };
@@ -421,34 +1688,33 @@ impl Renderer {
             Print("\r\n")
         )?;
 
-        // Bolt cutters status
-        let cutters_text = if inv.bolt_cutters.found {
-            "Bolt Cutters: ✓ Found!".to_string()
+        // Junk pockets
+        let junk_text = if inv.materials.is_empty() {
+            "Junk: [Empty]".to_string()
         } else {
-            "Bolt Cutters: ✗ Not found".to_string()
+            let stacks: Vec<String> = inv
+                .materials
+                .iter()
+                .map(|(kind, count)| format!("{} {}", kind.short_name(), count))
+                .collect();
+            format!(
+                "Junk {}/{}: {}",
+                inv.total_materials(),
+                JUNK_CAPACITY,
+                stacks.join(", ")
+            )
         };
+        self.render_text_line(&junk_text, Color::Reset)?;
 
-        execute!(
-            stdout(),
-            SetForegroundColor(Color::DarkGrey),
-            Print("║"),
-            ResetColor,
-            Print(" "),
-            Print(&cutters_text),
-        )?;
-
-        let text_len = cutters_text.chars().count() + 1;
-        for _ in text_len..(self.width as usize - 2) {
-            execute!(stdout(), Print(" "))?;
-        }
-
-        execute!(
-            stdout(),
-            SetForegroundColor(Color::DarkGrey),
-            Print("║"),
-            ResetColor,
-            Print("\r\n")
-        )?;
+        // Lock-opening tools, an equipped crowbar included
+        let tools = inv.usable_tools();
+        let tools_text = if tools.is_empty() {
+            "Tools: [None]".to_string()
+        } else {
+            let names: Vec<&str> = tools.iter().map(|t| t.name()).collect();
+            format!("Tools: {}", names.join(", "))
+        };
+        self.render_text_line(&tools_text, Color::Reset)?;
 
         Ok(())
     }
@@ -490,16 +1756,17 @@ impl Renderer {
             stdout(),
             SetForegroundColor(Color::Green),
             Print(&stats_line),
+            Print("\r\n"),
+            Print(end_screen_line(&format!(
+                "Level {} | Perks: {}",
+                game.player.progression.level,
+                game.player.progression.perk_summary()
+            ))),
             ResetColor,
-            Print("\r\n")
+            Print("\r\n"),
         )?;
 
-        let end_art = vec![
-            "║                                                            ║",
-            "║          Press Q to quit                                   ║",
-            "║                                                            ║",
-            "╚════════════════════════════════════════════════════════════╝",
-        ];
+        let end_art = end_screen_footer(game);
 
         for line in &end_art {
             execute!(
@@ -557,16 +1824,17 @@ impl Renderer {
             stdout(),
             SetForegroundColor(Color::Red),
             Print(&stats_line),
+            Print("\r\n"),
+            Print(end_screen_line(&format!(
+                "Level {} | Perks: {}",
+                game.player.progression.level,
+                game.player.progression.perk_summary()
+            ))),
             ResetColor,
-            Print("\r\n")
+            Print("\r\n"),
         )?;
 
-        let end_art = vec![
-            "║                                                            ║",
-            "║          Press Q to quit                                   ║",
-            "║                                                            ║",
-            "╚════════════════════════════════════════════════════════════╝",
-        ];
+        let end_art = end_screen_footer(game);
 
         for line in &end_art {
             execute!(
@@ -582,6 +1850,220 @@ impl Renderer {
     }
 }
 
+fn message_color(kind: MessageKind) -> Color {
+    match kind {
+        MessageKind::Combat => Color::Red,
+        MessageKind::Loot => Color::Yellow,
+        MessageKind::System => Color::Reset,
+    }
+}
+
+/// What each glyph and color on the map means, as colored runs of text,
+/// drawn the same way the map draws them.
+fn legend(game: &GameState) -> Vec<Vec<(String, Color)>> {
+    let entry = |glyph: char, color: Color, text: &str| {
+        vec![
+            (format!(" {} ", glyph), color),
+            (text.to_string(), Color::Reset),
+        ]
+    };
+    let mut entries = vec![vec![
+        (" @".to_string(), Color::Green),
+        (" You  ".to_string(), Color::Reset),
+        ("@".to_string(), Color::DarkGreen),
+        (" Sneaking".to_string(), Color::Reset),
+    ]];
+    for enemy_type in EnemyType::ALL {
+        entries.push(entry(
+            enemy_type.glyph(),
+            enemy_color(enemy_type),
+            &enemy_type.to_string(),
+        ));
+    }
+    entries.push(entry('?', Color::Yellow, "Heard you"));
+    entries.push(entry('!', Color::Red, "Hunting you"));
+    entries.push(entry('V', Color::Green, "Vendor"));
+    for npc in &game.npcs {
+        entries.push(entry(npc.glyph, Color::Magenta, &npc.name));
+    }
+    entries.push(entry('K', Color::Yellow, "Staff restocking"));
+
+    let mut dumpsters = vec![(" ".to_string(), Color::Reset)];
+    for kind in DumpsterKind::ALL {
+        dumpsters.push((kind.glyph().to_string(), dumpster_color(kind)));
+    }
+    dumpsters.push((" Dumpsters by kind, ".to_string(), Color::Reset));
+    dumpsters.push(("▒".to_string(), Color::DarkGrey));
+    dumpsters.push((" picked clean".to_string(), Color::Reset));
+    entries.push(dumpsters);
+
+    let samples = [
+        Weapon::named("Switchblade").map(Item::Weapon),
+        Armor::named("Leather Jacket").map(Item::Armor),
+        Consumable::named("Bandages").map(Item::Consumable),
+        Some(Item::Material(MaterialKind::Cloth)),
+        Some(Item::Tool(Tool::LockPicks)),
+        Some(Item::Cash(0)),
+    ];
+    let mut loot = vec![(" ".to_string(), Color::Reset)];
+    for item in samples.iter().flatten() {
+        loot.push((item.glyph().to_string(), item_color(item)));
+    }
+    loot.push((
+        " Weapon, armor, item, junk, tool, caps".to_string(),
+        Color::Reset,
+    ));
+    entries.push(loot);
+
+    entries.push(entry('%', Color::DarkGrey, "Body"));
+    for lock in &game.locks {
+        if lock.exit {
+            entries.push(entry(lock.kind.glyph(), Color::Yellow, "Exit, locked"));
+            entries.push(entry(lock.kind.glyph(), Color::Green, "Exit, open"));
+        } else {
+            entries.push(entry(
+                lock.kind.glyph(),
+                Color::Grey,
+                &format!("Locked {}", lock.kind),
+            ));
+        }
+    }
+    entries.push(entry('≡', Color::DarkGrey, "Sewer grate"));
+    entries.push(entry('#', Color::DarkGrey, "Wall"));
+    entries
+}
+
+fn enemy_color(enemy_type: EnemyType) -> Color {
+    match enemy_type {
+        EnemyType::Rat => Color::DarkYellow,
+        EnemyType::FerralCat => Color::Magenta,
+        EnemyType::RivalPunk => Color::Red,
+    }
+}
+
+/// What loot on the ground is drawn in.
+fn item_color(item: &Item) -> Color {
+    match item {
+        Item::Weapon(_) => Color::White,
+        Item::Armor(_) => Color::Blue,
+        Item::Consumable(_) => Color::Magenta,
+        Item::Material(_) => Color::Grey,
+        Item::Tool(_) => Color::Cyan,
+        Item::Cash(_) => Color::Yellow,
+    }
+}
+
+fn dumpster_color(kind: DumpsterKind) -> Color {
+    match kind {
+        DumpsterKind::Regular => Color::Cyan,
+        DumpsterKind::GreaseBin => Color::DarkYellow,
+        DumpsterKind::Recycling => Color::Green,
+        DumpsterKind::Compactor => Color::Grey,
+        DumpsterKind::MedicalWaste => Color::Red,
+        DumpsterKind::Electronics => Color::Blue,
+    }
+}
+
+/// A ten-segment bar like `[#######---]`.
+fn meter(value: i32, max: i32) -> String {
+    let filled = (value * 10 / max.max(1)).clamp(0, 10) as usize;
+    format!("[{}{}]", "#".repeat(filled), "-".repeat(10 - filled))
+}
+
+/// Splits text into lines of at most `width` characters, breaking on spaces.
+fn wrap_text(text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut current = String::new();
+
+    for word in text.split_whitespace() {
+        if !current.is_empty() && current.chars().count() + 1 + word.chars().count() > width {
+            lines.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push(' ');
+        }
+        current.push_str(word);
+    }
+    if !current.is_empty() {
+        lines.push(current);
+    }
+    lines
+}
+
+/// Pads a line of text into the end screens' 60-column box.
+fn end_screen_line(text: &str) -> String {
+    let text: String = text.chars().take(48).collect();
+    format!("║          {:<50}║", text)
+}
+
+/// The run summary, the score and what to do next, closing off an end screen.
+fn end_screen_footer(game: &GameState) -> Vec<String> {
+    let stats = &game.stats;
+    let inv = &game.player.inventory;
+    let record = if game.score_recorded {
+        "Score recorded."
+    } else if !game.can_record_score() {
+        "Practice run - not scored."
+    } else if game.daily.is_some() {
+        "Enter = Record your daily score"
+    } else {
+        "Enter = Record your score"
+    };
+    let morgue = match &game.morgue {
+        Some(Ok(path)) => format!(
+            "Morgue: {}",
+            path.file_name().unwrap_or_default().to_string_lossy()
+        ),
+        Some(Err(err)) => format!("Morgue not saved: {}", err),
+        None => String::new(),
+    };
+    vec![
+        end_screen_line(""),
+        end_screen_line(&morgue::epitaph(game)),
+        end_screen_line(&format!(
+            "Dealt {} damage | Took {}",
+            stats.damage_dealt, stats.damage_taken
+        )),
+        end_screen_line(&format!("Defeated: {}", stats.kill_summary())),
+        end_screen_line(&format!(
+            "Found {} items | Used {}",
+            stats.items_found.len(),
+            stats.items_used.len()
+        )),
+        end_screen_line(&format!(
+            "Weapon: {}",
+            inv.weapon.as_ref().map_or("[None]", |w| w.name.as_str())
+        )),
+        end_screen_line(&format!(
+            "Armor: {}",
+            inv.armor.as_ref().map_or("[None]", |a| a.name.as_str())
+        )),
+        end_screen_line(&format!(
+            "{} | {} | Seed: {}",
+            game.player
+                .background
+                .map_or("Punk".to_string(), |b| b.to_string()),
+            match &game.daily {
+                Some(daily) => format!("Daily {}", daily.date),
+                None => game.difficulty.to_string(),
+            },
+            game.seed
+        )),
+        end_screen_line(&morgue),
+        end_screen_line(""),
+        end_screen_line(&format!(
+            "Score: {} | +{} cred ({} banked)",
+            game.run_result().score(),
+            game.cred_earned.unwrap_or(0),
+            game.profile.cred
+        )),
+        end_screen_line(""),
+        end_screen_line(record),
+        end_screen_line("H = High scores | N = New run | Q = Quit"),
+        format!("╚{}╝", "═".repeat(60)),
+    ]
+}
+
 pub fn setup_terminal() -> Result<()> {
     terminal::enable_raw_mode()?;
     execute!(stdout(), terminal::EnterAlternateScreen, cursor::Hide)?;
@@ -0,0 +1,170 @@
+use crate::game::Position;
+use crate::items::{
+    Armor, Consumable, Inventory, Item, ItemPool, MaterialKind, Slot, Tool, Weapon,
+};
+use rand::Rng;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TradeError {
+    NotEnoughCash(u32),
+    SlotFull,
+    NothingThere,
+}
+
+impl fmt::Display for TradeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TradeError::NotEnoughCash(price) => write!(f, "You need {} caps for that", price),
+            TradeError::SlotFull => write!(f, "No room for it. Sell something first"),
+            TradeError::NothingThere => write!(f, "Nothing to trade"),
+        }
+    }
+}
+
+/// The guy who hangs around the burger place back door, buying junk
+/// and selling whatever fell off the delivery truck.
+#[derive(Debug, Clone)]
+pub struct Vendor {
+    pub position: Position,
+    pub stock: Vec<Item>,
+}
+
+impl Vendor {
+    pub fn new(rng: &mut impl Rng, x: i32, y: i32) -> Self {
+        let stock = vec![
+            Item::Consumable(Consumable::random(rng)),
+            Item::Consumable(Consumable::random(rng)),
+            Item::Consumable(Consumable::random(rng)),
+            Item::Weapon(Weapon::random_from(rng, ItemPool::default())),
+            Item::Armor(Armor::random_from(rng, ItemPool::default())),
+            Item::Material(MaterialKind::DuctTape),
+            Item::Material(MaterialKind::DuctTape),
+            Item::Tool(Tool::LockPicks),
+        ];
+
+        Vendor {
+            position: Position::new(x, y),
+            stock,
+        }
+    }
+
+    /// What the vendor charges you.
+    pub fn buy_price(item: &Item) -> u32 {
+        let value = item.value();
+        value + value / 2
+    }
+
+    /// What the vendor pays you.
+    pub fn sell_price(item: &Item) -> u32 {
+        (item.value() / 2).max(1)
+    }
+
+    /// Buys the stock item at `idx`. Returns its name and the price paid.
+    pub fn buy(
+        &mut self,
+        idx: usize,
+        inventory: &mut Inventory,
+    ) -> Result<(String, u32), TradeError> {
+        let item = self.stock.get(idx).ok_or(TradeError::NothingThere)?;
+        let price = Self::buy_price(item);
+        if inventory.cash < price {
+            return Err(TradeError::NotEnoughCash(price));
+        }
+
+        let item = self.stock.remove(idx);
+        let name = item.name();
+        match inventory.try_add(item) {
+            Ok(()) => {
+                inventory.cash -= price;
+                Ok((name, price))
+            }
+            Err(item) => {
+                self.stock.insert(idx, item);
+                Err(TradeError::SlotFull)
+            }
+        }
+    }
+
+    /// Sells one item from `slot`. Returns its name and the price received.
+    pub fn sell(
+        &mut self,
+        slot: Slot,
+        inventory: &mut Inventory,
+    ) -> Result<(String, u32), TradeError> {
+        let item = inventory.take(slot).ok_or(TradeError::NothingThere)?;
+        let price = Self::sell_price(&item);
+        let name = item.name();
+
+        inventory.cash += price;
+        self.stock.push(item);
+        Ok((name, price))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::items::ConsumableEffect;
+
+    fn soda() -> Item {
+        Item::Consumable(Consumable::new(
+            "Expired Soda",
+            ConsumableEffect::Heal(10),
+            "Test",
+        ))
+    }
+
+    #[test]
+    fn test_prices() {
+        // Heal 10 is worth 7 caps
+        assert_eq!(Vendor::buy_price(&soda()), 10);
+        assert_eq!(Vendor::sell_price(&soda()), 3);
+        assert_eq!(Vendor::sell_price(&Item::Material(MaterialKind::Nails)), 1);
+    }
+
+    #[test]
+    fn test_buying() {
+        let mut vendor = Vendor::new(&mut rand::thread_rng(), 0, 0);
+        vendor.stock = vec![soda()];
+        let mut inventory = Inventory::new();
+
+        assert_eq!(
+            vendor.buy(0, &mut inventory),
+            Err(TradeError::NotEnoughCash(10))
+        );
+
+        inventory.cash = 15;
+        inventory.consumable = Some(Consumable::random_generate());
+        assert_eq!(vendor.buy(0, &mut inventory), Err(TradeError::SlotFull));
+        assert_eq!(vendor.stock.len(), 1);
+        assert_eq!(inventory.cash, 15);
+
+        inventory.consumable = None;
+        assert_eq!(
+            vendor.buy(0, &mut inventory),
+            Ok(("Expired Soda".to_string(), 10))
+        );
+        assert_eq!(inventory.cash, 5);
+        assert!(vendor.stock.is_empty());
+    }
+
+    #[test]
+    fn test_selling() {
+        let mut vendor = Vendor::new(&mut rand::thread_rng(), 0, 0);
+        vendor.stock.clear();
+        let mut inventory = Inventory::new();
+        inventory.add_material(MaterialKind::DuctTape);
+
+        assert_eq!(
+            vendor.sell(Slot::Material(MaterialKind::DuctTape), &mut inventory),
+            Ok(("Duct Tape".to_string(), 2))
+        );
+        assert_eq!(inventory.cash, 2);
+        assert_eq!(vendor.stock.len(), 1);
+        assert_eq!(
+            vendor.sell(Slot::Weapon, &mut inventory),
+            Err(TradeError::NothingThere)
+        );
+    }
+}